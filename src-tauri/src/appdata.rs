@@ -0,0 +1,275 @@
+use crate::{electron_cache, DiskSenseError};
+use fs_extra::dir::get_size;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AppDataScope {
+    // Machine-specific data, never synced
+    Local,
+    // Data of low-integrity processes such as sandboxed browsers and games
+    LocalLow,
+    // Synced to other machines in a domain with roaming profiles
+    Roaming,
+}
+
+// A cache folder inside an app's data, safe to clear while the app is closed
+#[derive(Debug, Serialize, Clone)]
+pub struct CacheFolder {
+    path: String,
+    size: u64,
+    // What it is, e.g. "Teams cache"
+    label: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AppDataEntry {
+    // Name of the app's (or vendor's) folder
+    name: String,
+    scope: AppDataScope,
+    path: String,
+    size: u64,
+    caches: Vec<CacheFolder>,
+    cache_size: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AppDataReport {
+    local_size: u64,
+    local_low_size: u64,
+    roaming_size: u64,
+    // Largest first
+    apps: Vec<AppDataEntry>,
+}
+
+struct KnownCache {
+    scope: AppDataScope,
+    // Relative to the scope's folder; a "*" part matches any one folder, such
+    // as a browser profile
+    path: &'static str,
+    label: &'static str,
+}
+
+// Caches that regularly grow to several GB
+const KNOWN_CACHES: [KnownCache; 16] = [
+    KnownCache {
+        scope: AppDataScope::Roaming,
+        path: r"Microsoft\Teams\Cache",
+        label: "Teams cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Roaming,
+        path: r"Microsoft\Teams\Service Worker\CacheStorage",
+        label: "Teams cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Packages\MSTeams_8wekyb3d8bbwe\LocalCache",
+        label: "Teams cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Spotify\Data",
+        label: "Spotify offline cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Spotify\Browser\Cache",
+        label: "Spotify cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Packages\SpotifyAB.SpotifyMusic_zpdnekdrzrea0\LocalCache",
+        label: "Spotify cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Google\Chrome\User Data\*\Cache",
+        label: "Chrome cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Google\Chrome\User Data\*\Service Worker\CacheStorage",
+        label: "Chrome cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Microsoft\Edge\User Data\*\Cache",
+        label: "Edge cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Microsoft\Edge\User Data\*\Service Worker\CacheStorage",
+        label: "Edge cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"Mozilla\Firefox\Profiles\*\cache2",
+        label: "Firefox cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: "Temp",
+        label: "Temporary files",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: "CrashDumps",
+        label: "Crash dumps",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: "D3DSCache",
+        label: "DirectX shader cache",
+    },
+    KnownCache {
+        scope: AppDataScope::Local,
+        path: r"NVIDIA\DXCache",
+        label: "NVIDIA shader cache",
+    },
+    KnownCache {
+        scope: AppDataScope::LocalLow,
+        path: r"NVIDIA\PerDriverVersion\DXCache",
+        label: "NVIDIA shader cache",
+    },
+];
+
+// Break the current user's AppData down by Local, LocalLow and Roaming and by
+// app, marking the known cache folders inside each app. Windows only.
+#[command]
+pub async fn analyze_appdata() -> Result<AppDataReport, DiskSenseError> {
+    let scopes = scope_dirs().ok_or_else(|| {
+        DiskSenseError::Unsupported("AppData is only analyzed on Windows".to_string())
+    })?;
+
+    let apps: Vec<(AppDataScope, PathBuf)> = scopes
+        .iter()
+        .flat_map(|(scope, dir)| {
+            std::fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|entry| (*scope, entry.path()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let known: Vec<(AppDataScope, PathBuf, &str)> = KNOWN_CACHES
+        .iter()
+        .flat_map(|cache| {
+            let base = scopes
+                .iter()
+                .find(|(scope, _)| *scope == cache.scope)
+                .map(|(_, dir)| dir.clone());
+            base.map(|base| resolve(&base, cache.path))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|path| (cache.scope, path, cache.label))
+        })
+        .collect();
+
+    let mut apps: Vec<AppDataEntry> = apps
+        .into_par_iter()
+        .map(|(scope, path)| measure(scope, path, &known))
+        .collect();
+    apps.sort_by_key(|app| std::cmp::Reverse(app.size));
+
+    let scope_size = |scope: AppDataScope| {
+        apps.iter()
+            .filter(|app| app.scope == scope)
+            .map(|app| app.size)
+            .sum()
+    };
+    Ok(AppDataReport {
+        local_size: scope_size(AppDataScope::Local),
+        local_low_size: scope_size(AppDataScope::LocalLow),
+        roaming_size: scope_size(AppDataScope::Roaming),
+        apps,
+    })
+}
+
+fn measure(
+    scope: AppDataScope,
+    path: PathBuf,
+    known: &[(AppDataScope, PathBuf, &str)],
+) -> AppDataEntry {
+    let mut caches: Vec<CacheFolder> = known
+        .iter()
+        .filter(|(cache_scope, cache, _)| *cache_scope == scope && cache.starts_with(&path))
+        .map(|(_, cache, label)| cache_folder(cache, label))
+        .collect();
+    if caches.is_empty() {
+        caches = electron_cache::cache_dirs(&path)
+            .iter()
+            .map(|dir| cache_folder(dir, "App cache"))
+            .collect();
+    }
+    caches.retain(|cache| cache.size > 0);
+
+    AppDataEntry {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        scope,
+        size: get_size(&path).unwrap_or(0),
+        cache_size: caches.iter().map(|cache| cache.size).sum(),
+        caches,
+        path: path.to_string_lossy().to_string(),
+    }
+}
+
+fn cache_folder(path: &Path, label: &str) -> CacheFolder {
+    CacheFolder {
+        path: path.to_string_lossy().to_string(),
+        size: get_size(path).unwrap_or(0),
+        label: label.to_string(),
+    }
+}
+
+// Existing folders matching a relative path whose "*" parts match any folder
+fn resolve(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut matches = vec![base.to_path_buf()];
+    for part in pattern.split('\\') {
+        matches = matches
+            .into_iter()
+            .flat_map(|dir| {
+                if part == "*" {
+                    std::fs::read_dir(&dir)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Result::ok)
+                        .map(|entry| entry.path())
+                        .collect()
+                } else {
+                    vec![dir.join(part)]
+                }
+            })
+            .filter(|dir| dir.is_dir())
+            .collect();
+    }
+    matches
+}
+
+fn scope_dirs() -> Option<Vec<(AppDataScope, PathBuf)>> {
+    if !cfg!(target_os = "windows") {
+        return None;
+    }
+    let local = PathBuf::from(std::env::var_os("LOCALAPPDATA")?);
+    let roaming = PathBuf::from(std::env::var_os("APPDATA")?);
+    let local_low = local.parent()?.join("LocalLow");
+
+    Some(
+        [
+            (AppDataScope::Local, local),
+            (AppDataScope::LocalLow, local_low),
+            (AppDataScope::Roaming, roaming),
+        ]
+        .into_iter()
+        .filter(|(_, dir)| dir.is_dir())
+        .collect(),
+    )
+}
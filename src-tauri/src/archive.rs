@@ -0,0 +1,259 @@
+use crate::delta::node_id;
+use crate::{os_path, scan_windows, DiskItem, DiskSenseError, ScanStore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveEntry {
+    path: String,
+    size: u64,
+    // Not recorded per entry by every format (e.g. tar streams)
+    compressed_size: Option<u64>,
+    is_dir: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveListing {
+    path: String,
+    format: String,
+    archive_size: u64,
+    uncompressed_size: u64,
+    entries: Vec<ArchiveEntry>,
+}
+
+#[command]
+pub async fn inspect_archive(path: String) -> Result<ArchiveListing, DiskSenseError> {
+    let archive_size = std::fs::metadata(&path)
+        .map_err(|e| DiskSenseError::from_io(e, &path))?
+        .len();
+    let (format, entries) = list_entries(Path::new(&path))?;
+
+    Ok(ArchiveListing {
+        uncompressed_size: entries.iter().map(|entry| entry.size).sum(),
+        path,
+        format: format.to_string(),
+        archive_size,
+        entries,
+    })
+}
+
+// List one archive of a stored scan into the tree as virtual children, sent out as
+// a "scan-delta". Scans only expand archives with an index; this is how a tar or
+// tar.gz, which has to be read (and decompressed) to its end to be listed, gets
+// its contents shown.
+#[command]
+pub async fn expand_archive(
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+    path: String,
+) -> Result<Vec<DiskItem>, DiskSenseError> {
+    // `path` may be a raw_path for names that aren't valid Unicode
+    let target = os_path::decode(&path);
+    let tree_path = target.to_string_lossy().to_string();
+    let (_, entries) = list_entries(&target)?;
+
+    let children = virtual_children(&tree_path, &entries);
+    for change in store.apply_expansion(&tree_path, &children) {
+        scan_windows::emit_for(&app, &tree_path, "scan-delta", &change);
+    }
+    Ok(children)
+}
+
+// Archive format by file name, if it is one we can read
+fn archive_format(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some("tar.gz")
+    } else if name.ends_with(".tar") {
+        Some("tar")
+    } else if name.ends_with(".zip") || name.ends_with(".jar") {
+        Some("zip")
+    } else if name.ends_with(".7z") {
+        Some("7z")
+    } else {
+        None
+    }
+}
+
+// Formats listed from an index without reading the entries themselves, cheap
+// enough to do for every archive a scan finds
+fn is_indexed(format: &str) -> bool {
+    matches!(format, "zip" | "7z")
+}
+
+fn list_entries(path: &Path) -> Result<(&'static str, Vec<ArchiveEntry>), DiskSenseError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let format = archive_format(&name).ok_or_else(|| {
+        DiskSenseError::Unsupported(format!("Not a supported archive: {}", path.display()))
+    })?;
+    let invalid = |e: &dyn std::fmt::Display| {
+        DiskSenseError::InvalidInput(format!("Cannot read {}: {}", path.display(), e))
+    };
+
+    let entries = match format {
+        "zip" => {
+            let file = File::open(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+            let mut archive =
+                zip::ZipArchive::new(BufReader::new(file)).map_err(|e| invalid(&e))?;
+            let mut entries = Vec::with_capacity(archive.len());
+            for index in 0..archive.len() {
+                // Raw access reads only the headers, even for encrypted entries
+                let entry = archive.by_index_raw(index).map_err(|e| invalid(&e))?;
+                entries.push(ArchiveEntry {
+                    path: entry.name().to_string(),
+                    size: entry.size(),
+                    compressed_size: Some(entry.compressed_size()),
+                    is_dir: entry.is_dir(),
+                });
+            }
+            entries
+        }
+        "tar" | "tar.gz" => {
+            let file = File::open(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+            let reader: Box<dyn std::io::Read> = if format == "tar.gz" {
+                Box::new(flate2::read::GzDecoder::new(BufReader::new(file)))
+            } else {
+                Box::new(BufReader::new(file))
+            };
+            let mut archive = tar::Archive::new(reader);
+            let mut entries = Vec::new();
+            for entry in archive.entries().map_err(|e| invalid(&e))? {
+                let entry = entry.map_err(|e| invalid(&e))?;
+                entries.push(ArchiveEntry {
+                    path: entry
+                        .path()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    size: entry.header().size().unwrap_or(0),
+                    compressed_size: None,
+                    is_dir: entry.header().entry_type().is_dir(),
+                });
+            }
+            entries
+        }
+        _ => {
+            let reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())
+                .map_err(|e| invalid(&e))?;
+            reader
+                .archive()
+                .files
+                .iter()
+                .map(|entry| ArchiveEntry {
+                    path: entry.name.clone(),
+                    size: entry.size,
+                    compressed_size: entry.has_stream.then_some(entry.compressed_size),
+                    is_dir: entry.is_directory,
+                })
+                .collect()
+        }
+    };
+
+    Ok((format, entries))
+}
+
+// Give indexed archives of at least `min_size` bytes their contents as virtual
+// children, so big archives can be expanded in the tree like folders. Others
+// are left to expand_archive.
+pub fn expand_archives(item: &mut DiskItem, min_size: u64) {
+    if let Some(children) = item.children.as_mut() {
+        for child in children {
+            expand_archives(child, min_size);
+        }
+        return;
+    }
+
+    if item.is_dir || item.size < min_size || !archive_format(&item.name).is_some_and(is_indexed) {
+        return;
+    }
+
+    // Unreadable or corrupt archives stay plain files
+    if let Ok((_, entries)) = list_entries(Path::new(&item.path)) {
+        item.children = Some(virtual_children(&item.path, &entries));
+    }
+}
+
+#[derive(Default)]
+struct VirtualDir {
+    size: u64,
+    dirs: BTreeMap<String, VirtualDir>,
+    files: Vec<(String, u64)>,
+}
+
+fn virtual_children(archive_path: &str, entries: &[ArchiveEntry]) -> Vec<DiskItem> {
+    let mut root = VirtualDir::default();
+    for entry in entries.iter().filter(|entry| !entry.is_dir) {
+        let parts: Vec<&str> = entry
+            .path
+            .split(['/', '\\'])
+            .filter(|part| !part.is_empty())
+            .collect();
+        let (file_name, dirs) = match parts.split_last() {
+            Some(split) => split,
+            None => continue,
+        };
+
+        let mut dir = &mut root;
+        dir.size += entry.size;
+        for part in dirs {
+            dir = dir.dirs.entry(part.to_string()).or_default();
+            dir.size += entry.size;
+        }
+        dir.files.push((file_name.to_string(), entry.size));
+    }
+
+    virtual_items(archive_path, root)
+}
+
+fn virtual_items(parent: &str, dir: VirtualDir) -> Vec<DiskItem> {
+    let mut items: Vec<DiskItem> = dir
+        .dirs
+        .into_iter()
+        .map(|(name, sub)| {
+            let path = format!("{}/{}", parent, name);
+            virtual_item(name, sub.size, true, Some(virtual_items(&path, sub)), path)
+        })
+        .chain(dir.files.into_iter().map(|(name, size)| {
+            let path = format!("{}/{}", parent, name);
+            virtual_item(name, size, false, None, path)
+        }))
+        .collect();
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+fn virtual_item(
+    name: String,
+    size: u64,
+    is_dir: bool,
+    children: Option<Vec<DiskItem>>,
+    path: String,
+) -> DiskItem {
+    DiskItem {
+        id: node_id(&path),
+        name,
+        path,
+        size,
+        is_dir,
+        children,
+        game: None,
+        git_repo: false,
+        allocated_size: None,
+        sparse: false,
+        link_target: None,
+        hard_links: None,
+        in_archive: true,
+        raw_path: None,
+        estimated: false,
+        hidden: false,
+        known_folder: None,
+        allocated_partial: false,
+    }
+}
@@ -0,0 +1,122 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir the audit entries are appended to, one JSON object per line
+const AUDIT_FILE: &str = "audit.log";
+
+// Entries returned when the caller doesn't set a limit
+const DEFAULT_ENTRY_LIMIT: usize = 500;
+
+// One destructive operation, written once it has finished or failed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    at: u64,
+    command: String,
+    // The UI action that started it, as named by the front-end
+    action: Option<String>,
+    targets: Vec<String>,
+    // Bytes freed, moved or rewritten, as far as the operation reports them
+    bytes: u64,
+    ok: bool,
+    error: Option<String>,
+}
+
+// Append-only record of everything the app deleted, moved or changed on disk.
+// Entries are never rewritten or dropped by the app itself.
+pub struct AuditLog {
+    file: Option<PathBuf>,
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub(crate) fn open(app: &AppHandle) -> Self {
+        AuditLog {
+            file: app
+                .path()
+                .app_data_dir()
+                .ok()
+                .map(|dir| dir.join(AUDIT_FILE)),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)?
+            .write_all(&line)
+    }
+}
+
+// Record a finished destructive command. Failed commands are recorded too,
+// with whatever they had done before failing.
+pub(crate) fn record(
+    app: &AppHandle,
+    command: &str,
+    action: Option<&str>,
+    targets: Vec<String>,
+    bytes: u64,
+    error: Option<&DiskSenseError>,
+) {
+    let log = match app.try_state::<AuditLog>() {
+        Some(log) => log,
+        None => return,
+    };
+
+    let entry = AuditEntry {
+        at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        command: command.to_string(),
+        action: action.map(str::to_string),
+        targets,
+        bytes,
+        ok: error.is_none(),
+        error: error.map(|e| e.to_string()),
+    };
+    if let Err(e) = log.append(&entry) {
+        log::error!("Failed to write audit entry for {}: {}", command, e);
+    }
+}
+
+// The most recent `limit` entries, newest first
+#[command]
+pub async fn get_audit_log(
+    audit: State<'_, AuditLog>,
+    limit: Option<usize>,
+) -> Result<Vec<AuditEntry>, DiskSenseError> {
+    let file = match &audit.file {
+        Some(file) if file.exists() => file,
+        _ => return Ok(Vec::new()),
+    };
+    let contents = {
+        let _guard = audit.lock.lock().unwrap_or_else(|e| e.into_inner());
+        std::fs::read_to_string(file).map_err(|e| DiskSenseError::from_io(e, file))?
+    };
+
+    // A line cut short by a crash is skipped rather than failing the whole log
+    Ok(contents
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit.unwrap_or(DEFAULT_ENTRY_LIMIT))
+        .collect())
+}
@@ -0,0 +1,201 @@
+use crate::delta::node_id;
+use crate::{DiskItem, DiskSenseError, ScanStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+
+// Folder in the app data dir holding one reference tree per root
+const BASELINE_DIR: &str = "baselines";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BaselineInfo {
+    root: String,
+    set_at: u64,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    #[serde(flatten)]
+    info: BaselineInfo,
+    tree: DiskItem,
+}
+
+// A node that differs from the baseline; a missing size means the node was
+// added (no baseline size) or removed (no current size) since
+#[derive(Debug, Serialize, Clone)]
+pub struct Drift {
+    path: String,
+    name: String,
+    is_dir: bool,
+    baseline_size: Option<u64>,
+    current_size: Option<u64>,
+    change: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BaselineReport {
+    baseline: BaselineInfo,
+    current_size: u64,
+    change: i64,
+    // Largest change first
+    drift: Vec<Drift>,
+}
+
+// Keep the stored scan of `root` as its reference footprint, replacing any
+// earlier baseline. Later scans are compared against it, not against each other.
+#[command]
+pub async fn set_baseline(
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+    root: String,
+) -> Result<BaselineInfo, DiskSenseError> {
+    let tree = store.get(&root)?;
+    let file = baseline_file(&app, &root)?;
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+    }
+
+    let baseline = Baseline {
+        info: BaselineInfo {
+            root,
+            set_at: now(),
+            size: tree.size,
+        },
+        tree,
+    };
+    let contents = serde_json::to_vec(&baseline)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to save baseline: {}", e)))?;
+    std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))?;
+    Ok(baseline.info)
+}
+
+// How the stored scan of `root` has drifted from its baseline. Changes smaller
+// than `min_change` bytes are left out, so log churn doesn't bury real growth.
+// Folders that only one of the two scans listed are compared by total alone.
+#[command]
+pub async fn compare_to_baseline(
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+    root: String,
+    min_change: Option<u64>,
+) -> Result<BaselineReport, DiskSenseError> {
+    let baseline = load(&app, &root)?;
+    let current = store.get(&root)?;
+
+    let mut drift = Vec::new();
+    compare(&baseline.tree, &current, &mut drift);
+    let min_change = min_change.unwrap_or(0);
+    drift.retain(|node| node.change.unsigned_abs() >= min_change);
+    drift.sort_by_key(|node| std::cmp::Reverse(node.change.unsigned_abs()));
+
+    Ok(BaselineReport {
+        change: change(baseline.info.size, current.size),
+        current_size: current.size,
+        baseline: baseline.info,
+        drift,
+    })
+}
+
+#[command]
+pub async fn remove_baseline(app: AppHandle, root: String) -> Result<(), DiskSenseError> {
+    let file = baseline_file(&app, &root)?;
+    match std::fs::remove_file(&file) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(DiskSenseError::from_io(e, &file))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[command]
+pub async fn list_baselines(app: AppHandle) -> Result<Vec<BaselineInfo>, DiskSenseError> {
+    let dir = match baseline_dir(&app) {
+        Ok(dir) if dir.is_dir() => dir,
+        _ => return Ok(Vec::new()),
+    };
+    let entries = std::fs::read_dir(&dir).map_err(|e| DiskSenseError::from_io(e, &dir))?;
+
+    let mut baselines: Vec<BaselineInfo> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| std::fs::read(entry.path()).ok())
+        .filter_map(|contents| serde_json::from_slice::<Baseline>(&contents).ok())
+        .map(|baseline| baseline.info)
+        .collect();
+    baselines.sort_by(|a, b| a.root.cmp(&b.root));
+    Ok(baselines)
+}
+
+fn load(app: &AppHandle, root: &str) -> Result<Baseline, DiskSenseError> {
+    let file = baseline_file(app, root)?;
+    let contents = std::fs::read(&file).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => DiskSenseError::NotFound {
+            path: root.to_string(),
+        },
+        _ => DiskSenseError::from_io(e, &file),
+    })?;
+    serde_json::from_slice(&contents)
+        .map_err(|e| DiskSenseError::Failed(format!("Baseline of {} is unreadable: {}", root, e)))
+}
+
+// Nodes are matched by id, which follows the path, so a moved folder shows up
+// as removed in one place and added in another
+fn compare(baseline: &DiskItem, current: &DiskItem, drift: &mut Vec<Drift>) {
+    if baseline.size != current.size {
+        drift.push(node_drift(current, Some(baseline.size), Some(current.size)));
+    }
+
+    let (old_children, new_children) = match (&baseline.children, &current.children) {
+        (Some(old), Some(new)) => (old, new),
+        _ => return,
+    };
+    let mut old_by_id: HashMap<&str, &DiskItem> = old_children
+        .iter()
+        .map(|child| (child.id.as_str(), child))
+        .collect();
+
+    for child in new_children {
+        match old_by_id.remove(child.id.as_str()) {
+            Some(previous) => compare(previous, child, drift),
+            None => drift.push(node_drift(child, None, Some(child.size))),
+        }
+    }
+    for removed in old_by_id.into_values() {
+        drift.push(node_drift(removed, Some(removed.size), None));
+    }
+}
+
+fn node_drift(item: &DiskItem, baseline_size: Option<u64>, current_size: Option<u64>) -> Drift {
+    Drift {
+        path: item.path.clone(),
+        name: item.name.clone(),
+        is_dir: item.is_dir,
+        baseline_size,
+        current_size,
+        change: change(baseline_size.unwrap_or(0), current_size.unwrap_or(0)),
+    }
+}
+
+fn change(from: u64, to: u64) -> i64 {
+    (to as i64).saturating_sub(from as i64)
+}
+
+fn baseline_dir(app: &AppHandle) -> Result<PathBuf, DiskSenseError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(BASELINE_DIR))
+        .map_err(|_| DiskSenseError::Failed("App data directory is unavailable".to_string()))
+}
+
+fn baseline_file(app: &AppHandle, root: &str) -> Result<PathBuf, DiskSenseError> {
+    Ok(baseline_dir(app)?.join(format!("{}.json", node_id(root))))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,217 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{command, State};
+use walkdir::WalkDir;
+
+// Upper bounds that keep the benchmark short on any device
+const SEQUENTIAL_LIMIT_BYTES: u64 = 256 * 1024 * 1024;
+const SEQUENTIAL_TIME_LIMIT: Duration = Duration::from_secs(3);
+const RANDOM_READ_LIMIT: usize = 2000;
+const RANDOM_TIME_LIMIT: Duration = Duration::from_secs(2);
+
+// Stop looking for sample files after visiting this many entries
+const MAX_ENTRIES_VISITED: usize = 20_000;
+
+// Files smaller than this are not useful for sequential or random reads
+const MIN_SAMPLE_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+const SEQUENTIAL_BLOCK: usize = 1024 * 1024;
+const RANDOM_BLOCK: usize = 4096;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BenchmarkResult {
+    mount_point: String,
+    sequential_mb_per_sec: f64,
+    random_iops: f64,
+    random_mb_per_sec: f64,
+    bytes_read: u64,
+    duration_ms: u64,
+    sample_files: usize,
+    recommended_threads: usize,
+}
+
+// Scan threads recommended by the last benchmark of each mount, for this session
+#[derive(Default)]
+pub struct ScanThreads {
+    by_mount: Mutex<HashMap<PathBuf, usize>>,
+}
+
+impl ScanThreads {
+    // Threads for scanning `path`, from the benchmark of the deepest mount holding it
+    pub(crate) fn for_path(&self, path: &Path) -> Option<usize> {
+        self.by_mount
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(mount, _)| path.starts_with(mount))
+            .max_by_key(|(mount, _)| mount.as_os_str().len())
+            .map(|(_, threads)| *threads)
+    }
+}
+
+// Scans of the drive afterwards use recommended_threads unless told otherwise
+#[command]
+pub async fn benchmark_drive(
+    scan_threads: State<'_, ScanThreads>,
+    mount: String,
+) -> Result<BenchmarkResult, DiskSenseError> {
+    let root = Path::new(&mount);
+    if !root.is_dir() {
+        return Err(DiskSenseError::NotFound { path: mount });
+    }
+
+    // Benchmark against existing files so nothing is written to the drive.
+    // Recently read files may be served from the OS cache and look faster.
+    let samples = find_sample_files(root);
+    if samples.is_empty() {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "No files large enough to benchmark were found on {}",
+            mount
+        )));
+    }
+
+    let started = Instant::now();
+    let (sequential_bytes, sequential_time) = sequential_read(&samples);
+    let (random_reads, random_time) = random_read(&samples);
+
+    let sequential_mb_per_sec = throughput_mb(sequential_bytes, sequential_time);
+    let random_bytes = (random_reads * RANDOM_BLOCK) as u64;
+    let random_iops = if random_time.as_secs_f64() > 0.0 {
+        random_reads as f64 / random_time.as_secs_f64()
+    } else {
+        0.0
+    };
+
+    let threads = recommended_threads(random_iops);
+    scan_threads
+        .by_mount
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            dunce::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()),
+            threads,
+        );
+
+    Ok(BenchmarkResult {
+        mount_point: mount,
+        sequential_mb_per_sec,
+        random_iops,
+        random_mb_per_sec: throughput_mb(random_bytes, random_time),
+        bytes_read: sequential_bytes + random_bytes,
+        duration_ms: started.elapsed().as_millis() as u64,
+        sample_files: samples.len(),
+        recommended_threads: threads,
+    })
+}
+
+// Spinning disks and network shares degrade with many parallel readers
+fn recommended_threads(random_iops: f64) -> usize {
+    if random_iops < 500.0 {
+        2
+    } else if random_iops < 5000.0 {
+        4
+    } else {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+    }
+}
+
+fn throughput_mb(bytes: u64, elapsed: Duration) -> f64 {
+    if elapsed.as_secs_f64() > 0.0 {
+        bytes as f64 / (1024.0 * 1024.0) / elapsed.as_secs_f64()
+    } else {
+        0.0
+    }
+}
+
+// Collect (path, size) of large readable files, largest first
+fn find_sample_files(root: &Path) -> Vec<(PathBuf, u64)> {
+    let mut samples: Vec<(PathBuf, u64)> = WalkDir::new(root)
+        .follow_links(false)
+        .same_file_system(true)
+        .into_iter()
+        .take(MAX_ENTRIES_VISITED)
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let size = entry.metadata().ok()?.len();
+            (size >= MIN_SAMPLE_FILE_SIZE).then(|| (entry.into_path(), size))
+        })
+        .collect();
+
+    samples.sort_by_key(|item| std::cmp::Reverse(item.1));
+    samples.truncate(16);
+    samples
+}
+
+fn sequential_read(samples: &[(PathBuf, u64)]) -> (u64, Duration) {
+    let mut buffer = vec![0u8; SEQUENTIAL_BLOCK];
+    let mut total = 0u64;
+    let started = Instant::now();
+
+    'files: for (path, _) in samples {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+
+        loop {
+            if total >= SEQUENTIAL_LIMIT_BYTES || started.elapsed() >= SEQUENTIAL_TIME_LIMIT {
+                break 'files;
+            }
+            match file.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => total += read as u64,
+            }
+        }
+    }
+
+    (total, started.elapsed())
+}
+
+fn random_read(samples: &[(PathBuf, u64)]) -> (usize, Duration) {
+    let mut files: Vec<(File, u64)> = samples
+        .iter()
+        .filter_map(|(path, size)| File::open(path).ok().map(|file| (file, *size)))
+        .collect();
+    if files.is_empty() {
+        return (0, Duration::ZERO);
+    }
+
+    // xorshift64 seeded from the clock is plenty for picking offsets
+    let mut state = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x9e3779b97f4a7c15)
+        | 1;
+    let mut next_random = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut buffer = [0u8; RANDOM_BLOCK];
+    let mut reads = 0;
+    let started = Instant::now();
+
+    while reads < RANDOM_READ_LIMIT && started.elapsed() < RANDOM_TIME_LIMIT {
+        let index = (next_random() % files.len() as u64) as usize;
+        let (file, size) = &mut files[index];
+        let blocks = *size / RANDOM_BLOCK as u64;
+        let offset = (next_random() % blocks.max(1)) * RANDOM_BLOCK as u64;
+
+        if file.seek(SeekFrom::Start(offset)).is_ok() && file.read_exact(&mut buffer).is_ok() {
+            reads += 1;
+        }
+    }
+
+    (reads, started.elapsed())
+}
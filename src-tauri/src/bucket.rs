@@ -0,0 +1,91 @@
+use crate::remote::{build_tree, RemoteEntry};
+use crate::{DiskItem, DiskSenseError};
+use s3::creds::Credentials;
+use s3::{Bucket, Region};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::command;
+
+// Objects without a storage class in the listing are in the default tier
+const DEFAULT_STORAGE_CLASS: &str = "STANDARD";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageClassUsage {
+    storage_class: String,
+    objects: u64,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BucketScan {
+    root: DiskItem,
+    object_count: u64,
+    storage_classes: Vec<StorageClassUsage>,
+}
+
+// List every object under `prefix` in an S3-compatible bucket and build a tree from
+// the key prefixes. `endpoint` is empty for AWS itself, otherwise the URL of a
+// compatible service (MinIO, R2, B2...). Credentials come from the usual AWS
+// environment variables or profile.
+#[command]
+pub async fn scan_bucket(
+    endpoint: String,
+    bucket: String,
+    prefix: Option<String>,
+) -> Result<BucketScan, DiskSenseError> {
+    let prefix = prefix.unwrap_or_default();
+    let region = if endpoint.trim().is_empty() {
+        Region::UsEast1
+    } else {
+        Region::Custom {
+            region: std::env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            endpoint: endpoint.trim().to_string(),
+        }
+    };
+    let credentials = Credentials::default()
+        .map_err(|e| DiskSenseError::Failed(format!("No S3 credentials available: {}", e)))?;
+    let mut handle = Bucket::new(&bucket, region, credentials)
+        .map_err(|e| DiskSenseError::InvalidInput(format!("Invalid bucket {}: {}", bucket, e)))?;
+    // Compatible services rarely support virtual-hosted bucket names
+    if !endpoint.trim().is_empty() {
+        handle = handle.with_path_style();
+    }
+
+    let pages = handle
+        .list(prefix.clone(), None)
+        .await
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to list {}: {}", bucket, e)))?;
+
+    let mut classes: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut entries = Vec::new();
+    for object in pages.into_iter().flat_map(|page| page.contents) {
+        let class = object
+            .storage_class
+            .unwrap_or_else(|| DEFAULT_STORAGE_CLASS.to_string());
+        let usage = classes.entry(class).or_default();
+        usage.0 += 1;
+        usage.1 += object.size;
+
+        entries.push(RemoteEntry {
+            path: object.key[prefix.len().min(object.key.len())..].to_string(),
+            size: object.size,
+        });
+    }
+
+    let mut storage_classes: Vec<StorageClassUsage> = classes
+        .into_iter()
+        .map(|(storage_class, (objects, size))| StorageClassUsage {
+            storage_class,
+            objects,
+            size,
+        })
+        .collect();
+    storage_classes.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    let root = format!("s3://{}/{}", bucket, prefix);
+    Ok(BucketScan {
+        object_count: entries.len() as u64,
+        root: build_tree(&root, entries),
+        storage_classes,
+    })
+}
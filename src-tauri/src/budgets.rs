@@ -0,0 +1,208 @@
+use crate::DiskSenseError;
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+// File in the app data dir holding the directory budgets
+const BUDGETS_FILE: &str = "dir_budgets.json";
+
+// How often the background checker re-measures budgeted directories
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirBudget {
+    path: String,
+    max_bytes: u64,
+    last_size: Option<u64>,
+    last_checked: Option<u64>,
+    exceeded: bool,
+}
+
+// Size limits on directories, persisted in the app data dir
+#[derive(Default)]
+pub struct DirBudgets {
+    budgets: Mutex<Vec<DirBudget>>,
+}
+
+impl DirBudgets {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let budgets = budgets_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        DirBudgets {
+            budgets: Mutex::new(budgets),
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = budgets_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let budgets = self.budgets.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = serde_json::to_string_pretty(&*budgets)
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to save budgets: {}", e)))?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    pub(crate) fn budgets(&self) -> Vec<DirBudget> {
+        self.budgets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    // Limits from another machine are kept, its measurements are not
+    pub(crate) fn import(
+        &self,
+        app: &AppHandle,
+        mut budgets: Vec<DirBudget>,
+    ) -> Result<(), DiskSenseError> {
+        for budget in &mut budgets {
+            budget.last_size = None;
+            budget.last_checked = None;
+            budget.exceeded = false;
+        }
+        *self.budgets.lock().unwrap_or_else(|e| e.into_inner()) = budgets;
+        self.save(app)
+    }
+}
+
+fn budgets_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(BUDGETS_FILE))
+}
+
+// Re-measure budgeted directories on a timer for as long as the app runs
+pub(crate) fn start_checker(app: AppHandle) {
+    std::thread::Builder::new()
+        .name("disksense-budgets".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            let _ = check_budgets(&app, &app.state::<DirBudgets>());
+        })
+        .ok();
+}
+
+#[command]
+pub async fn set_dir_budget(
+    app: AppHandle,
+    budgets: State<'_, DirBudgets>,
+    path: String,
+    max_bytes: u64,
+) -> Result<(), DiskSenseError> {
+    if !Path::new(&path).is_dir() {
+        return Err(DiskSenseError::NotFound { path });
+    }
+
+    {
+        let mut list = budgets.budgets.lock().unwrap_or_else(|e| e.into_inner());
+        match list.iter_mut().find(|budget| budget.path == path) {
+            Some(budget) => {
+                budget.max_bytes = max_bytes;
+                budget.exceeded = false;
+            }
+            None => list.push(DirBudget {
+                path,
+                max_bytes,
+                last_size: None,
+                last_checked: None,
+                exceeded: false,
+            }),
+        }
+    }
+    budgets.save(&app)
+}
+
+#[command]
+pub async fn remove_dir_budget(
+    app: AppHandle,
+    budgets: State<'_, DirBudgets>,
+    path: String,
+) -> Result<(), DiskSenseError> {
+    budgets
+        .budgets
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|budget| budget.path != path);
+    budgets.save(&app)
+}
+
+#[command]
+pub async fn list_dir_budgets(
+    budgets: State<'_, DirBudgets>,
+) -> Result<Vec<DirBudget>, DiskSenseError> {
+    Ok(budgets
+        .budgets
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone())
+}
+
+// Measure every budget now instead of waiting for the next background check
+#[command]
+pub async fn check_dir_budgets(
+    app: AppHandle,
+    budgets: State<'_, DirBudgets>,
+) -> Result<Vec<DirBudget>, DiskSenseError> {
+    check_budgets(&app, &budgets)
+}
+
+// Measure each budgeted directory and emit "budget-exceeded" (the DirBudget) once
+// for every directory that has newly gone over its limit
+fn check_budgets(app: &AppHandle, budgets: &DirBudgets) -> Result<Vec<DirBudget>, DiskSenseError> {
+    let paths: Vec<String> = budgets
+        .budgets
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|budget| budget.path.clone())
+        .collect();
+
+    // Measure without holding the lock; this can take a while for big folders
+    let sizes: Vec<(String, Option<u64>)> = paths
+        .into_iter()
+        .map(|path| {
+            let size = get_size(&path).ok();
+            (path, size)
+        })
+        .collect();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let updated = {
+        let mut list = budgets.budgets.lock().unwrap_or_else(|e| e.into_inner());
+        for (path, size) in sizes {
+            let budget = match list.iter_mut().find(|budget| budget.path == path) {
+                Some(budget) => budget,
+                None => continue,
+            };
+
+            budget.last_size = size;
+            budget.last_checked = Some(now);
+
+            let exceeded = size.is_some_and(|size| size > budget.max_bytes);
+            if exceeded && !budget.exceeded {
+                let _ = app.emit("budget-exceeded", &*budget);
+            }
+            budget.exceeded = exceeded;
+        }
+        list.clone()
+    };
+
+    budgets.save(app)?;
+    Ok(updated)
+}
@@ -0,0 +1,359 @@
+use crate::ScanOptions;
+#[cfg(target_os = "linux")]
+use crate::{incremental, perform_scan, ScanStore};
+#[cfg(target_os = "linux")]
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use std::sync::{Arc, Mutex};
+#[cfg(target_os = "linux")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+#[cfg(target_os = "linux")]
+use tauri::Manager;
+
+// Quiet time after the last change before the affected scans are refreshed
+#[cfg(target_os = "linux")]
+const REFRESH_DELAY: Duration = Duration::from_secs(2);
+
+#[cfg(target_os = "linux")]
+struct WatchedRoot {
+    depth: usize,
+    options: ScanOptions,
+    // When every folder below the root had a watch; None while they are being
+    // added, or for good if the watch limit was reached
+    watched_since: Option<u64>,
+    // Last time events were lost; the journal can't vouch for earlier snapshots
+    overflowed_at: Option<u64>,
+    // Events were lost since the last refresh, so only a full rescan is reliable
+    rescan: bool,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+struct Journal {
+    roots: HashMap<String, WatchedRoot>,
+    watches: HashMap<i32, PathBuf>,
+    // Last change seen in each directory, in seconds since the epoch
+    changed: HashMap<PathBuf, u64>,
+    // Directories changed since the stored scans were last refreshed
+    pending: HashSet<PathBuf>,
+}
+
+// Records which directories under the scanned roots change, using inotify on
+// Linux; elsewhere nothing is recorded. Stored scans are refreshed shortly after
+// their folders change, and a restored snapshot learns which folders to relist.
+pub struct ChangeRecorder {
+    #[cfg(target_os = "linux")]
+    journal: Arc<Mutex<Journal>>,
+    #[cfg(target_os = "linux")]
+    fd: Option<i32>,
+}
+
+impl ChangeRecorder {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn start(app: AppHandle) -> Self {
+        let journal = Arc::new(Mutex::new(Journal::default()));
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd >= 0 {
+            let recorded = journal.clone();
+            std::thread::Builder::new()
+                .name("disksense-change-recorder".to_string())
+                .spawn(move || read_events(&app, fd, &recorded))
+                .ok();
+        }
+
+        ChangeRecorder {
+            journal,
+            fd: (fd >= 0).then_some(fd),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn start(_app: AppHandle) -> Self {
+        ChangeRecorder {}
+    }
+}
+
+// Start recording changes under a finished scan's root, or keep its settings
+// current if it is already being recorded
+#[cfg(target_os = "linux")]
+pub(crate) fn record_scan(app: &AppHandle, root: &str, depth: usize, options: &ScanOptions) {
+    let recorder = match app.try_state::<ChangeRecorder>() {
+        Some(recorder) => recorder,
+        None => return,
+    };
+
+    let mut journal = recorder.journal.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(watched) = journal.roots.get_mut(root) {
+        watched.depth = depth;
+        watched.options = options.clone();
+        return;
+    }
+    journal.roots.insert(
+        root.to_string(),
+        WatchedRoot {
+            depth,
+            options: options.clone(),
+            watched_since: None,
+            overflowed_at: None,
+            rescan: false,
+        },
+    );
+    drop(journal);
+
+    // Adding a watch per folder walks the whole tree, so it isn't waited on
+    if let Some(fd) = recorder.fd {
+        let journal = recorder.journal.clone();
+        let root = PathBuf::from(root);
+        std::thread::spawn(move || {
+            let complete = watch_tree(fd, &journal, &root);
+            let mut journal = journal.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(watched) = journal.roots.get_mut(root.to_string_lossy().as_ref()) {
+                watched.watched_since = complete.then(now);
+            }
+        });
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn record_scan(_app: &AppHandle, _root: &str, _depth: usize, _options: &ScanOptions) {}
+
+// Directories under `root` changed at or after `since` (seconds since the epoch).
+// None unless every change since then was seen: the root must have been fully
+// watched from before `since`, with no events lost after it.
+#[cfg(target_os = "linux")]
+pub(crate) fn changed_since(app: &AppHandle, root: &Path, since: u64) -> Option<Vec<PathBuf>> {
+    let recorder = app.try_state::<ChangeRecorder>()?;
+    let journal = recorder.journal.lock().unwrap_or_else(|e| e.into_inner());
+
+    let watched = journal.roots.get(root.to_string_lossy().as_ref())?;
+    if watched.watched_since? > since || watched.overflowed_at.is_some_and(|at| at >= since) {
+        return None;
+    }
+
+    let mut dirty: Vec<PathBuf> = journal
+        .changed
+        .iter()
+        .filter(|(path, at)| **at >= since && path.starts_with(root))
+        .map(|(path, _)| path.clone())
+        .collect();
+    dirty.sort();
+    Some(dirty)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn changed_since(_app: &AppHandle, _root: &Path, _since: u64) -> Option<Vec<PathBuf>> {
+    None
+}
+
+// Bring every stored scan with pending changes up to date. Roots that lost
+// events are rescanned in full; the rest only relist the changed folders.
+#[cfg(target_os = "linux")]
+fn refresh(app: &AppHandle, journal: &Mutex<Journal>) {
+    let (pending, roots) = {
+        let mut journal = journal.lock().unwrap_or_else(|e| e.into_inner());
+        let pending: Vec<PathBuf> = journal.pending.drain().collect();
+        let roots: Vec<(String, usize, ScanOptions, bool)> = journal
+            .roots
+            .iter_mut()
+            .filter(|(root, _)| pending.iter().any(|path| path.starts_with(root)))
+            .map(|(root, watched)| {
+                let full = std::mem::take(&mut watched.rescan);
+                (root.clone(), watched.depth, watched.options.clone(), full)
+            })
+            .collect();
+        (pending, roots)
+    };
+
+    let store = app.state::<ScanStore>();
+    for (root, depth, options, full) in roots {
+        let tree = match store.get(&root) {
+            Ok(tree) => tree,
+            Err(_) => continue,
+        };
+
+        // Both report what changed to the front-end as a "scan-delta"
+        if full {
+            let _ = perform_scan(
+                app,
+                &store,
+                Path::new(&root),
+                depth,
+                options,
+                HashMap::new(),
+            );
+        } else {
+            let dirty: Vec<PathBuf> = pending
+                .iter()
+                .filter(|path| path.starts_with(&root))
+                .cloned()
+                .collect();
+            let _ = incremental::refresh(app, &store, &tree, depth, options, &dirty);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MODIFY
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_ONLYDIR
+    | libc::IN_DONT_FOLLOW;
+
+// Watch `dir` and every folder below it on the same file system. Returns false
+// when a folder couldn't be watched, usually because the watch limit was reached.
+#[cfg(target_os = "linux")]
+fn watch_tree(fd: i32, journal: &Mutex<Journal>, dir: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut complete = true;
+    let folders = walkdir::WalkDir::new(dir)
+        .follow_links(false)
+        .same_file_system(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_dir());
+    for entry in folders {
+        let path = match std::ffi::CString::new(entry.path().as_os_str().as_bytes()) {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+        let wd = unsafe { libc::inotify_add_watch(fd, path.as_ptr(), WATCH_MASK) };
+        if wd < 0 {
+            complete = false;
+            if std::io::Error::last_os_error().raw_os_error() == Some(libc::ENOSPC) {
+                break;
+            }
+            continue;
+        }
+        journal
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .watches
+            .insert(wd, entry.into_path());
+    }
+    complete
+}
+
+// Read inotify events until the descriptor fails, refreshing the stored scans
+// once changes have settled for REFRESH_DELAY
+#[cfg(target_os = "linux")]
+fn read_events(app: &AppHandle, fd: i32, journal: &Mutex<Journal>) {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let waiting = !journal
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pending
+            .is_empty();
+        let timeout = if waiting {
+            REFRESH_DELAY.as_millis() as i32
+        } else {
+            -1
+        };
+
+        let mut poll_fd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        match unsafe { libc::poll(&mut poll_fd, 1, timeout) } {
+            0 => {
+                refresh(app, journal);
+                continue;
+            }
+            ready if ready < 0 => {
+                if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let read = unsafe { libc::read(fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+        if read <= 0 {
+            if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return;
+        }
+
+        // Each event is wd, mask, cookie and name length (4 bytes each), then the name
+        let events = &buffer[..read as usize];
+        let mut offset = 0;
+        while offset + 16 <= events.len() {
+            let field = |at: usize| {
+                let bytes = [
+                    events[offset + at],
+                    events[offset + at + 1],
+                    events[offset + at + 2],
+                    events[offset + at + 3],
+                ];
+                u32::from_ne_bytes(bytes)
+            };
+            let (wd, mask, len) = (field(0) as i32, field(4), field(12) as usize);
+            let name =
+                &events[(offset + 16).min(events.len())..(offset + 16 + len).min(events.len())];
+            let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+            record_event(fd, journal, wd, mask, std::ffi::OsStr::from_bytes(name));
+            offset += 16 + len;
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn record_event(fd: i32, journal: &Mutex<Journal>, wd: i32, mask: u32, name: &std::ffi::OsStr) {
+    let at = now();
+    let mut state = journal.lock().unwrap_or_else(|e| e.into_inner());
+
+    if mask & libc::IN_Q_OVERFLOW != 0 {
+        let roots: Vec<PathBuf> = state.roots.keys().map(PathBuf::from).collect();
+        for watched in state.roots.values_mut() {
+            watched.overflowed_at = Some(at);
+            watched.rescan = true;
+        }
+        state.pending.extend(roots);
+        return;
+    }
+
+    // The folder itself was deleted; its parent records the change
+    if mask & libc::IN_IGNORED != 0 {
+        state.watches.remove(&wd);
+        return;
+    }
+
+    let dir = match state.watches.get(&wd) {
+        Some(dir) => dir.clone(),
+        None => return,
+    };
+    state.changed.insert(dir.clone(), at);
+    state.pending.insert(dir.clone());
+
+    // New and moved-in folders need watches of their own
+    if mask & libc::IN_ISDIR != 0 && mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+        drop(state);
+        let added = dir.join(name);
+        if !watch_tree(fd, journal, &added) {
+            let mut state = journal.lock().unwrap_or_else(|e| e.into_inner());
+            for (root, watched) in state.roots.iter_mut() {
+                if added.starts_with(root) {
+                    watched.watched_since = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
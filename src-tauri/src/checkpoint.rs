@@ -0,0 +1,129 @@
+use crate::{perform_scan, DiskItem, DiskSenseError, ScanOptions, ScanStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, State};
+
+const CHECKPOINT_FILE: &str = "scan-checkpoint.json";
+
+// How often the partial tree is written to disk while scanning
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointState {
+    root: String,
+    depth: usize,
+    options: ScanOptions,
+    // Finished directory subtrees whose parent has not finished yet
+    completed: HashMap<String, DiskItem>,
+}
+
+// Crash-safe record of a scan in progress. Every finished directory is kept, so a
+// resumed scan can reuse those subtrees and only walk what was left.
+pub(crate) struct ScanCheckpoint {
+    file: Option<PathBuf>,
+    state: Mutex<CheckpointState>,
+    resumed: HashMap<String, DiskItem>,
+    last_flush: Mutex<Instant>,
+}
+
+impl ScanCheckpoint {
+    pub(crate) fn new(
+        app: &AppHandle,
+        root: &Path,
+        depth: usize,
+        options: &ScanOptions,
+        resumed: HashMap<String, DiskItem>,
+    ) -> Self {
+        ScanCheckpoint {
+            file: checkpoint_file(app),
+            state: Mutex::new(CheckpointState {
+                root: root.to_string_lossy().to_string(),
+                depth,
+                options: options.clone(),
+                completed: resumed.clone(),
+            }),
+            resumed,
+            last_flush: Mutex::new(Instant::now()),
+        }
+    }
+
+    // A subtree finished by the interrupted scan, if there is one for this path
+    pub(crate) fn resumed(&self, path: &Path) -> Option<DiskItem> {
+        self.resumed.get(path.to_string_lossy().as_ref()).cloned()
+    }
+
+    // Record a finished directory, replacing the entries of its children
+    pub(crate) fn record(&self, item: &DiskItem) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(children) = &item.children {
+            for child in children {
+                state.completed.remove(&child.path);
+            }
+        }
+        state.completed.insert(item.path.clone(), item.clone());
+
+        let mut last_flush = self.last_flush.lock().unwrap_or_else(|e| e.into_inner());
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush(&state);
+            *last_flush = Instant::now();
+        }
+    }
+
+    // The scan completed, so there is nothing left to resume
+    pub(crate) fn finish(&self) {
+        if let Some(file) = &self.file {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+
+    fn flush(&self, state: &CheckpointState) {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return,
+        };
+        if let Some(parent) = file.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        // Write then rename, so a crash mid-write never leaves a corrupt checkpoint
+        let temp = file.with_extension("json.tmp");
+        if let Ok(contents) = serde_json::to_vec(state) {
+            if std::fs::write(&temp, contents).is_ok() {
+                let _ = std::fs::rename(&temp, file);
+            }
+        }
+    }
+}
+
+fn checkpoint_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(CHECKPOINT_FILE))
+}
+
+// Continue the last scan that was interrupted, reusing every directory it finished
+#[command]
+pub async fn resume_last_scan(
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+) -> Result<DiskItem, DiskSenseError> {
+    let file = checkpoint_file(&app)
+        .ok_or_else(|| DiskSenseError::Failed("App data directory is unavailable".to_string()))?;
+
+    let contents = std::fs::read(&file).map_err(|e| DiskSenseError::from_io(e, &file))?;
+    let state: CheckpointState = serde_json::from_slice(&contents)
+        .map_err(|e| DiskSenseError::Failed(format!("Scan checkpoint is unreadable: {}", e)))?;
+
+    perform_scan(
+        &app,
+        &store,
+        Path::new(&state.root),
+        state.depth,
+        state.options,
+        state.completed,
+    )
+}
@@ -0,0 +1,74 @@
+use crate::name_sort::NameCollation;
+use crate::{DiskItem, DiskSenseError, ScanStore};
+use serde::{Deserialize, Serialize};
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChildrenPage {
+    total: usize,
+    offset: usize,
+    // Children without their own subtrees; page into a folder to list it
+    children: Vec<DiskItem>,
+}
+
+// One page of a stored node's children, sorted by "size" (largest first, the
+// default) or "name". `collation` picks how names compare: "natural" (default),
+// "ordinal" or a locale tag.
+#[command]
+pub async fn get_children_page(
+    store: State<'_, ScanStore>,
+    path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort_by: Option<String>,
+    ascending: Option<bool>,
+    collation: Option<String>,
+) -> Result<ChildrenPage, DiskSenseError> {
+    let node = store.find_node(&path)?;
+    let collation = NameCollation::parse(collation.as_deref())?;
+    let mut children = node.children.unwrap_or_default();
+
+    let by_name = match sort_by.as_deref().unwrap_or("size") {
+        "size" => {
+            children.sort_by(|a, b| {
+                b.size
+                    .cmp(&a.size)
+                    .then_with(|| collation.compare(&a.name, &b.name))
+            });
+            false
+        }
+        "name" => {
+            children.sort_by(|a, b| collation.compare(&a.name, &b.name));
+            true
+        }
+        other => {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "Unsupported sort column: {}",
+                other
+            )))
+        }
+    };
+
+    // Sizes list largest first and names A-Z unless the caller asks otherwise
+    if ascending.unwrap_or(by_name) != by_name {
+        children.reverse();
+    }
+
+    let total = children.len();
+    let offset = offset.unwrap_or(0).min(total);
+    let children = children
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|mut child| {
+            child.children = None;
+            child
+        })
+        .collect();
+
+    Ok(ChildrenPage {
+        total,
+        offset,
+        children,
+    })
+}
@@ -0,0 +1,469 @@
+use crate::duplicates::FileFilter;
+use crate::hash_cache::HashCache;
+use crate::read_only::ReadOnlyMode;
+use crate::{
+    audit, delete_and_report, deletion, duplicates, home_dir, linux_logs, package_cache, staging,
+    DiskItem, DiskSenseError, ProtectedPaths, ScanStore,
+};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, SystemTime};
+use sysinfo::Disks;
+use tauri::{command, AppHandle, State};
+
+// Downloads untouched for this long are suggested for removal
+const OLD_DOWNLOAD_DAYS: u64 = 90;
+
+// Smallest file compared when looking for duplicate downloads
+const DUPLICATE_MIN_SIZE: u64 = 1024 * 1024;
+
+// Build output folders that can always be regenerated
+const DEV_ARTIFACT_DIRS: [&str; 4] = ["node_modules", "__pycache__", ".gradle", "target"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Safety {
+    // Nothing of value is lost
+    Safe,
+    // Worth a look before removing
+    Review,
+    // Needs elevated rights or affects the system
+    Caution,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CleanupAction {
+    EmptyTrash,
+    CleanPackageCache { manager: String },
+    DeletePaths { paths: Vec<String>, staged: bool },
+    PurgeStaged,
+    VacuumJournal,
+    // Nothing to run automatically; the wizard shows the paths for the user to pick
+    Review { paths: Vec<String> },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleanupStep {
+    id: String,
+    category: String,
+    title: String,
+    reclaimable: u64,
+    safety: Safety,
+    action: CleanupAction,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CleanupStepResult {
+    step_id: String,
+    message: String,
+    report: Option<deletion::DeletionReport>,
+}
+
+// Everything worth cleaning, safest and largest first, for a guided wizard.
+// Dev artifacts come from stored scans, so scan the home folder first to see them.
+#[command]
+pub async fn get_cleanup_plan(
+    store: State<'_, ScanStore>,
+    cache: State<'_, HashCache>,
+) -> Result<Vec<CleanupStep>, DiskSenseError> {
+    build_plan(&store, &cache).await
+}
+
+// Run one step of the plan. The plan is rebuilt so a stale step can't act on
+// paths that have changed since it was shown.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn run_cleanup_step(
+    read_only: State<'_, ReadOnlyMode>,
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+    protected: State<'_, ProtectedPaths>,
+    cache: State<'_, HashCache>,
+    step_id: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<CleanupStepResult, DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Cleanup step must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    let step = build_plan(&store, &cache)
+        .await?
+        .into_iter()
+        .find(|step| step.id == step_id)
+        .ok_or_else(|| DiskSenseError::NotFound {
+            path: step_id.clone(),
+        })?;
+
+    // Steps without a report of their own are taken to free what the plan showed
+    let result = run_step(&app, &store, &protected, step.action);
+    audit::record(
+        &app,
+        "run_cleanup_step",
+        action.as_deref(),
+        vec![step_id.clone()],
+        match &result {
+            Ok((_, Some(report))) => report.bytes_reclaimed(),
+            Ok((_, None)) => step.reclaimable,
+            Err(_) => 0,
+        },
+        result.as_ref().err(),
+    );
+    let (message, report) = result?;
+
+    Ok(CleanupStepResult {
+        step_id,
+        message,
+        report,
+    })
+}
+
+// The work of one step, with the message and deletion report to show
+fn run_step(
+    app: &AppHandle,
+    store: &ScanStore,
+    protected: &ProtectedPaths,
+    action: CleanupAction,
+) -> Result<(String, Option<deletion::DeletionReport>), DiskSenseError> {
+    let done = match action {
+        CleanupAction::EmptyTrash => (empty_trash()?, None),
+        CleanupAction::CleanPackageCache { manager } => {
+            (package_cache::clean_cache(&manager)?, None)
+        }
+        CleanupAction::PurgeStaged => {
+            let freed = staging::purge()?;
+            (format!("Purged {} bytes of staged deletes", freed), None)
+        }
+        CleanupAction::VacuumJournal => (linux_logs::vacuum(None)?, None),
+        CleanupAction::DeletePaths { paths, staged } => {
+            let cancelled = AtomicBool::new(false);
+            let mut report = deletion::DeletionReport::default();
+            for path in paths {
+                match delete_and_report(app, &cancelled, protected, store, &path, false, staged) {
+                    Ok(deleted) => report.merge(deleted),
+                    Err(e) => report.fail(e),
+                }
+            }
+            (
+                format!("Reclaimed {} bytes", report.bytes_reclaimed()),
+                Some(report),
+            )
+        }
+        CleanupAction::Review { .. } => {
+            return Err(DiskSenseError::InvalidInput(
+                "This step has no automatic action; pick the items to remove".to_string(),
+            ))
+        }
+    };
+    Ok(done)
+}
+
+async fn build_plan(
+    store: &ScanStore,
+    cache: &HashCache,
+) -> Result<Vec<CleanupStep>, DiskSenseError> {
+    let mut steps = Vec::new();
+
+    let trash = trash_dirs();
+    let trash_size: u64 = trash.iter().map(|dir| get_size(dir).unwrap_or(0)).sum();
+    if trash_size > 0 {
+        steps.push(step(
+            "trash",
+            "trash",
+            "Empty the trash",
+            trash_size,
+            Safety::Safe,
+            CleanupAction::EmptyTrash,
+        ));
+    }
+
+    let staged: u64 = staging::list_staged()
+        .await?
+        .iter()
+        .map(|item| item.size)
+        .sum();
+    if staged > 0 {
+        steps.push(step(
+            "staged",
+            "trash",
+            "Purge staged deletes",
+            staged,
+            Safety::Safe,
+            CleanupAction::PurgeStaged,
+        ));
+    }
+
+    for cache in package_cache::get_package_cache_sizes().await? {
+        if cache.size == 0 {
+            continue;
+        }
+        steps.push(step(
+            &format!("package-cache:{}", cache.manager),
+            "caches",
+            &format!("Clean the {} cache", cache.manager),
+            cache.size,
+            Safety::Safe,
+            CleanupAction::CleanPackageCache {
+                manager: cache.manager,
+            },
+        ));
+    }
+
+    let artifacts = dev_artifacts(store);
+    if !artifacts.is_empty() {
+        steps.push(step(
+            "dev-artifacts",
+            "dev_artifacts",
+            "Remove build output and dependency folders",
+            artifacts.iter().map(|item| item.size).sum(),
+            Safety::Review,
+            CleanupAction::DeletePaths {
+                paths: artifacts.into_iter().map(|item| item.path).collect(),
+                staged: false,
+            },
+        ));
+    }
+
+    if let Some(downloads) = home_dir()
+        .map(|home| home.join("Downloads"))
+        .filter(|dir| dir.is_dir())
+    {
+        let old = old_downloads(&downloads);
+        if !old.is_empty() {
+            steps.push(step(
+                "old-downloads",
+                "old_downloads",
+                &format!("Remove downloads untouched for {} days", OLD_DOWNLOAD_DAYS),
+                old.iter().map(|(_, size)| size).sum(),
+                Safety::Review,
+                CleanupAction::DeletePaths {
+                    paths: old.into_iter().map(|(path, _)| path).collect(),
+                    staged: true,
+                },
+            ));
+        }
+
+        let (_, groups) = duplicates::duplicate_groups(
+            cache,
+            &downloads.to_string_lossy(),
+            None,
+            &FileFilter::new(&[], &[], Some(DUPLICATE_MIN_SIZE))?,
+            &|_| {},
+        )?;
+        let reclaimable: u64 = groups.iter().map(|group| group.reclaimable).sum();
+        if reclaimable > 0 {
+            steps.push(step(
+                "duplicates",
+                "duplicates",
+                "Review duplicate downloads",
+                reclaimable,
+                Safety::Review,
+                CleanupAction::Review {
+                    paths: groups
+                        .into_iter()
+                        .flat_map(|group| group.files)
+                        .map(|file| file.path)
+                        .collect(),
+                },
+            ));
+        }
+    }
+
+    if cfg!(target_os = "linux") {
+        let report = linux_logs::analyze_linux_logs().await.ok();
+        if let Some(journal) = report.and_then(|report| report.journal_size) {
+            steps.push(step(
+                "journal",
+                "system_files",
+                "Shrink the systemd journal",
+                journal,
+                Safety::Caution,
+                CleanupAction::VacuumJournal,
+            ));
+        }
+    }
+
+    steps.sort_by(|a, b| {
+        a.safety
+            .cmp(&b.safety)
+            .then(b.reclaimable.cmp(&a.reclaimable))
+    });
+    Ok(steps)
+}
+
+fn step(
+    id: &str,
+    category: &str,
+    title: &str,
+    reclaimable: u64,
+    safety: Safety,
+    action: CleanupAction,
+) -> CleanupStep {
+    CleanupStep {
+        id: id.to_string(),
+        category: category.to_string(),
+        title: title.to_string(),
+        reclaimable,
+        safety,
+        action,
+    }
+}
+
+fn trash_dirs() -> Vec<PathBuf> {
+    let candidates: Vec<PathBuf> = if cfg!(target_os = "windows") {
+        Disks::new_with_refreshed_list()
+            .iter()
+            .map(|disk| disk.mount_point().join("$Recycle.Bin"))
+            .collect()
+    } else if cfg!(target_os = "macos") {
+        home_dir()
+            .map(|home| home.join(".Trash"))
+            .into_iter()
+            .collect()
+    } else {
+        home_dir()
+            .map(|home| home.join(".local/share/Trash"))
+            .into_iter()
+            .collect()
+    };
+
+    candidates.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+// The recycle bin holds a folder per user, so Windows empties it through the shell
+#[cfg(target_os = "windows")]
+fn empty_trash() -> Result<String, DiskSenseError> {
+    use winapi::um::shellapi::{
+        SHEmptyRecycleBinW, SHERB_NOCONFIRMATION, SHERB_NOPROGRESSUI, SHERB_NOSOUND,
+    };
+
+    let flags = SHERB_NOCONFIRMATION | SHERB_NOPROGRESSUI | SHERB_NOSOUND;
+    let result = unsafe { SHEmptyRecycleBinW(std::ptr::null_mut(), std::ptr::null(), flags) };
+    // S_OK, or E_UNEXPECTED when the bin was already empty
+    if result >= 0 || result == winapi::shared::winerror::E_UNEXPECTED {
+        Ok("Emptied the recycle bin".to_string())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "Failed to empty the recycle bin (0x{:08x})",
+            result
+        )))
+    }
+}
+
+// Finder's trash is a flat folder
+#[cfg(target_os = "macos")]
+fn empty_trash() -> Result<String, DiskSenseError> {
+    let removed = trash_dirs()
+        .iter()
+        .map(|dir| empty_dir(dir))
+        .sum::<Result<usize, _>>()?;
+    Ok(format!("Removed {} items from the trash", removed))
+}
+
+// freedesktop trash keeps the items in files/ and their metadata in info/
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn empty_trash() -> Result<String, DiskSenseError> {
+    let mut removed = 0;
+    for dir in trash_dirs() {
+        removed += empty_dir(&dir.join("files"))?;
+        empty_dir(&dir.join("info"))?;
+    }
+    Ok(format!("Removed {} items from the trash", removed))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn empty_dir(dir: &Path) -> Result<usize, DiskSenseError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(0),
+    };
+
+    let mut removed = 0;
+    for entry in entries.filter_map(Result::ok) {
+        deletion::remove_path(&entry.path())?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+// Build folders found in stored scans. A folder's own artifacts are not
+// searched, so nested node_modules are counted once.
+fn dev_artifacts(store: &ScanStore) -> Vec<DiskItem> {
+    fn collect(item: &DiskItem, found: &mut Vec<DiskItem>) {
+        for child in item
+            .children
+            .iter()
+            .flatten()
+            .filter(|child| !child.in_archive)
+        {
+            if child.is_dir && is_dev_artifact(child) {
+                found.push(DiskItem {
+                    children: None,
+                    ..child.clone()
+                });
+            } else {
+                collect(child, found);
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    for root in store.root_paths() {
+        if let Ok(tree) = store.get(&root) {
+            collect(&tree, &mut found);
+        }
+    }
+    // Overlapping scans report the same folder more than once, not always next
+    // to each other once sorted by size
+    let mut seen = HashSet::new();
+    found.retain(|item| seen.insert(item.path.clone()));
+    found.sort_by_key(|item| std::cmp::Reverse(item.size));
+    found
+}
+
+fn is_dev_artifact(item: &DiskItem) -> bool {
+    if !DEV_ARTIFACT_DIRS.contains(&item.name.as_str()) {
+        return false;
+    }
+    // "target" is too common a name; only Cargo's counts
+    item.name != "target"
+        || Path::new(&item.path)
+            .parent()
+            .is_some_and(|parent| parent.join("Cargo.toml").is_file())
+}
+
+fn old_downloads(downloads: &Path) -> Vec<(String, u64)> {
+    let cutoff = SystemTime::now() - Duration::from_secs(OLD_DOWNLOAD_DAYS * 24 * 60 * 60);
+    let entries = match std::fs::read_dir(downloads) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            // Access times are often not kept (noatime), so a recent change counts too
+            let modified = metadata.modified().ok()?;
+            let last_used = metadata
+                .accessed()
+                .map_or(modified, |accessed| accessed.max(modified));
+            if last_used > cutoff {
+                return None;
+            }
+            let size = if metadata.is_dir() {
+                get_size(entry.path()).unwrap_or(0)
+            } else {
+                metadata.len()
+            };
+            Some((entry.path().to_string_lossy().to_string(), size))
+        })
+        .collect()
+}
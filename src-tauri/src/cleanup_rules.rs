@@ -0,0 +1,306 @@
+use crate::hooks::{self, HookEvent};
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, deletion, protected, staging, DiskSenseError, ProtectedPaths, ScanStore};
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+use walkdir::WalkDir;
+
+// File in the app data dir holding the cleanup rules
+const RULES_FILE: &str = "cleanup_rules.json";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    // Files last modified more than `days` ago
+    OlderThanDays { days: u64 },
+    // All but the `count` most recently modified files
+    KeepNewest { count: usize },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleanupRule {
+    #[serde(default)]
+    id: String,
+    root: String,
+    // Glob matched against file names, e.g. "*.tmp"
+    pattern: String,
+    #[serde(default)]
+    recursive: bool,
+    condition: RuleCondition,
+    // Move matches to the staging area instead of deleting them outright
+    #[serde(default)]
+    staged: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RuleCandidate {
+    path: String,
+    size: u64,
+    modified: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RuleReport {
+    rule_id: String,
+    dry_run: bool,
+    candidates: Vec<RuleCandidate>,
+    total_bytes: u64,
+    removed: usize,
+    errors: Vec<DiskSenseError>,
+}
+
+// User-defined cleanup rules, persisted in the app data dir
+#[derive(Default)]
+pub struct CleanupRules {
+    rules: Mutex<Vec<CleanupRule>>,
+}
+
+impl CleanupRules {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let rules = rules_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        CleanupRules {
+            rules: Mutex::new(rules),
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = rules_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let rules = self.rules.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = serde_json::to_string_pretty(&*rules)
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to save cleanup rules: {}", e)))?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    pub(crate) fn rules(&self) -> Vec<CleanupRule> {
+        self.rules.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // Every pattern must compile before any rule is replaced. Roots are kept even
+    // when missing here; such rules simply find nothing.
+    pub(crate) fn import(
+        &self,
+        app: &AppHandle,
+        mut rules: Vec<CleanupRule>,
+    ) -> Result<(), DiskSenseError> {
+        for rule in &rules {
+            matcher(&rule.pattern)?;
+        }
+        for (i, rule) in rules.iter_mut().enumerate() {
+            if rule.id.is_empty() {
+                rule.id = format!("{}-{}", new_id(), i);
+            }
+        }
+        *self.rules.lock().unwrap_or_else(|e| e.into_inner()) = rules;
+        self.save(app)
+    }
+}
+
+fn new_id() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+fn rules_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(RULES_FILE))
+}
+
+#[command]
+pub async fn add_rule(
+    app: AppHandle,
+    rules: State<'_, CleanupRules>,
+    mut rule: CleanupRule,
+) -> Result<String, DiskSenseError> {
+    if !Path::new(&rule.root).is_dir() {
+        return Err(DiskSenseError::NotFound { path: rule.root });
+    }
+    matcher(&rule.pattern)?;
+
+    rule.id = new_id();
+    let id = rule.id.clone();
+
+    rules
+        .rules
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(rule);
+    rules.save(&app)?;
+
+    Ok(id)
+}
+
+#[command]
+pub async fn list_rules(
+    rules: State<'_, CleanupRules>,
+) -> Result<Vec<CleanupRule>, DiskSenseError> {
+    Ok(rules
+        .rules
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone())
+}
+
+#[command]
+pub async fn remove_rule(
+    app: AppHandle,
+    rules: State<'_, CleanupRules>,
+    id: String,
+) -> Result<(), DiskSenseError> {
+    rules
+        .rules
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|rule| rule.id != id);
+    rules.save(&app)
+}
+
+// Evaluate the rules (all of them, or just `rule_ids`). A dry run only reports what
+// would be removed; otherwise matches are removed, skipping protected paths.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn run_rules(
+    app: AppHandle,
+    rules: State<'_, CleanupRules>,
+    protected: State<'_, ProtectedPaths>,
+    store: State<'_, ScanStore>,
+    read_only: State<'_, ReadOnlyMode>,
+    dry_run: bool,
+    rule_ids: Option<Vec<String>>,
+    action: Option<String>,
+) -> Result<Vec<RuleReport>, DiskSenseError> {
+    if !dry_run {
+        read_only.ensure_writable()?;
+    }
+    let selected: Vec<CleanupRule> = rules
+        .rules
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|rule| rule_ids.as_ref().map_or(true, |ids| ids.contains(&rule.id)))
+        .cloned()
+        .collect();
+
+    let mut reports = Vec::new();
+    for rule in selected {
+        let candidates = find_candidates(&rule)?;
+        let mut report = RuleReport {
+            rule_id: rule.id.clone(),
+            dry_run,
+            total_bytes: candidates.iter().map(|c| c.size).sum(),
+            candidates,
+            removed: 0,
+            errors: Vec::new(),
+        };
+
+        if !dry_run {
+            let (mut removed, mut freed) = (Vec::new(), 0);
+            for candidate in &report.candidates {
+                let path = Path::new(&candidate.path);
+                let result =
+                    protected::ensure_deletable(path, &protected, &store, false).and_then(|_| {
+                        if rule.staged {
+                            staging::stage_path(path).map(|_| ())
+                        } else {
+                            deletion::remove_path(path)
+                        }
+                    });
+                match result {
+                    Ok(()) => {
+                        report.removed += 1;
+                        removed.push(candidate.path.clone());
+                        freed += candidate.size;
+                    }
+                    Err(e) => report.errors.push(e),
+                }
+            }
+
+            audit::record(
+                &app,
+                "run_rules",
+                action.as_deref(),
+                removed,
+                freed,
+                report.errors.first(),
+            );
+            hooks::run_hooks(&app, HookEvent::CleanupCompleted, &rule.root, &report);
+        }
+
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+fn matcher(pattern: &str) -> Result<GlobMatcher, DiskSenseError> {
+    Glob::new(pattern)
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| DiskSenseError::InvalidInput(format!("Invalid pattern {}: {}", pattern, e)))
+}
+
+// Files under the rule's root that its pattern and condition select
+fn find_candidates(rule: &CleanupRule) -> Result<Vec<RuleCandidate>, DiskSenseError> {
+    let matcher = matcher(&rule.pattern)?;
+    let max_depth = if rule.recursive { usize::MAX } else { 1 };
+
+    let mut files: Vec<RuleCandidate> = WalkDir::new(&rule.root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && matcher.is_match(entry.file_name()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Some(RuleCandidate {
+                path: entry.path().to_string_lossy().to_string(),
+                size: metadata.len(),
+                modified,
+            })
+        })
+        .collect();
+
+    match rule.condition {
+        RuleCondition::OlderThanDays { days } => {
+            let cutoff = SystemTime::now()
+                .checked_sub(Duration::from_secs(days * SECONDS_PER_DAY))
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            files.retain(|file| file.modified < cutoff);
+        }
+        RuleCondition::KeepNewest { count } => {
+            files.sort_by_key(|item| std::cmp::Reverse(item.modified));
+            files.drain(..count.min(files.len()));
+        }
+    }
+
+    files.sort_by_key(|item| std::cmp::Reverse(item.size));
+    Ok(files)
+}
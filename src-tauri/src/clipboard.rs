@@ -0,0 +1,230 @@
+use crate::{os_path, DiskSenseError};
+use std::path::PathBuf;
+use tauri::command;
+
+// Put the files on the clipboard so they can be pasted into Explorer, Finder or
+// the Linux file manager
+#[command]
+pub async fn clipboard_copy_files(paths: Vec<String>) -> Result<(), DiskSenseError> {
+    set_clipboard_files(&decode_paths(paths)?, false)
+}
+
+// Like clipboard_copy_files, but pasting moves the files. Finder has no cut:
+// there the files are copied references, and Option-Command-V moves them.
+#[command]
+pub async fn clipboard_cut_files(paths: Vec<String>) -> Result<(), DiskSenseError> {
+    set_clipboard_files(&decode_paths(paths)?, true)
+}
+
+fn decode_paths(paths: Vec<String>) -> Result<Vec<PathBuf>, DiskSenseError> {
+    if paths.is_empty() {
+        return Err(DiskSenseError::InvalidInput(
+            "No files selected".to_string(),
+        ));
+    }
+
+    paths
+        .iter()
+        .map(|path| {
+            let decoded = os_path::decode(path);
+            // The file manager needs absolute paths that exist
+            dunce::canonicalize(&decoded).map_err(|e| DiskSenseError::from_io(e, &decoded))
+        })
+        .collect()
+}
+
+// CF_HDROP holds the file list; "Preferred DropEffect" tells Explorer to move on paste
+#[cfg(target_os = "windows")]
+fn set_clipboard_files(paths: &[PathBuf], cut: bool) -> Result<(), DiskSenseError> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::shared::minwindef::{BOOL, DWORD};
+    use winapi::shared::windef::POINT;
+    use winapi::um::winbase::{GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use winapi::um::winuser::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+        CF_HDROP,
+    };
+
+    // DROPFILES from shlobj_core.h
+    #[repr(C)]
+    struct DropFiles {
+        p_files: DWORD,
+        pt: POINT,
+        f_nc: BOOL,
+        f_wide: BOOL,
+    }
+    const DROPEFFECT_COPY: u32 = 1;
+    const DROPEFFECT_MOVE: u32 = 2;
+
+    // Double-NUL-terminated list of wide paths after the header
+    let mut list: Vec<u16> = Vec::new();
+    for path in paths {
+        list.extend(path.as_os_str().encode_wide());
+        list.push(0);
+    }
+    list.push(0);
+
+    let header = std::mem::size_of::<DropFiles>();
+    let size = header + list.len() * 2;
+
+    unsafe {
+        let write_global = |bytes: &[u8]| -> Option<*mut winapi::ctypes::c_void> {
+            let handle = GlobalAlloc(GMEM_MOVEABLE, bytes.len());
+            if handle.is_null() {
+                return None;
+            }
+            let target = GlobalLock(handle) as *mut u8;
+            if target.is_null() {
+                GlobalFree(handle);
+                return None;
+            }
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), target, bytes.len());
+            GlobalUnlock(handle);
+            Some(handle)
+        };
+
+        let mut drop = vec![0u8; size];
+        let files = DropFiles {
+            p_files: header as DWORD,
+            pt: POINT { x: 0, y: 0 },
+            f_nc: 0,
+            f_wide: 1,
+        };
+        std::ptr::copy_nonoverlapping(
+            &files as *const DropFiles as *const u8,
+            drop.as_mut_ptr(),
+            header,
+        );
+        std::ptr::copy_nonoverlapping(
+            list.as_ptr() as *const u8,
+            drop.as_mut_ptr().add(header),
+            list.len() * 2,
+        );
+
+        if OpenClipboard(std::ptr::null_mut()) == 0 {
+            return Err(DiskSenseError::Failed(
+                "The clipboard is in use by another program".to_string(),
+            ));
+        }
+        EmptyClipboard();
+
+        let mut ok = match write_global(&drop) {
+            Some(handle) => !SetClipboardData(CF_HDROP, handle).is_null(),
+            None => false,
+        };
+
+        let format: Vec<u16> = "Preferred DropEffect\0".encode_utf16().collect();
+        let effect = if cut {
+            DROPEFFECT_MOVE
+        } else {
+            DROPEFFECT_COPY
+        };
+        let drop_effect = RegisterClipboardFormatW(format.as_ptr());
+        ok &= match write_global(&effect.to_le_bytes()) {
+            Some(handle) => !SetClipboardData(drop_effect, handle).is_null(),
+            None => false,
+        };
+        CloseClipboard();
+
+        if ok {
+            Ok(())
+        } else {
+            Err(DiskSenseError::Failed(
+                "Failed to put the files on the clipboard".to_string(),
+            ))
+        }
+    }
+}
+
+// File URLs on the general pasteboard, written through JXA like the Finder helpers
+#[cfg(target_os = "macos")]
+fn set_clipboard_files(paths: &[PathBuf], _cut: bool) -> Result<(), DiskSenseError> {
+    const SCRIPT: &str = r#"
+function run(argv) {
+    ObjC.import('AppKit');
+    var urls = argv.map(function (path) { return $.NSURL.fileURLWithPath(path); });
+    var pasteboard = $.NSPasteboard.generalPasteboard;
+    pasteboard.clearContents;
+    return pasteboard.writeObjects($(urls));
+}
+"#;
+
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", SCRIPT])
+        .args(paths)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "osascript"))?;
+
+    if output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true" {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(
+            "Failed to put the files on the clipboard".to_string(),
+        ))
+    }
+}
+
+// GNOME, KDE and most other file managers read x-special/gnome-copied-files.
+// Uses wl-copy on Wayland and xclip on X11.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn set_clipboard_files(paths: &[PathBuf], cut: bool) -> Result<(), DiskSenseError> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    const MIME: &str = "x-special/gnome-copied-files";
+
+    let mut contents = String::from(if cut { "cut" } else { "copy" });
+    for path in paths {
+        contents.push('\n');
+        contents.push_str(&file_url(path));
+    }
+
+    let mut command = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wl-copy");
+        command.args(["--type", MIME]);
+        command
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-t", MIME]);
+        command
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|_| {
+            DiskSenseError::Unsupported(
+                "Copying files needs wl-copy (Wayland) or xclip (X11)".to_string(),
+            )
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(contents.as_bytes())?;
+    }
+    // Both tools fork into the background to serve the clipboard once stdin closes
+    if child.wait()?.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(
+            "Failed to put the files on the clipboard".to_string(),
+        ))
+    }
+}
+
+// file:// URL with everything but unreserved characters and '/' percent-encoded
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn file_url(path: &std::path::Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut url = String::from("file://");
+    for byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(*byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
@@ -0,0 +1,131 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, parse_human_size, DiskSenseError};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+// WinSxS as DISM sees it. Most of the folder is hard links shared with Windows
+// itself, so a scan of C:\Windows counts those bytes twice; `actual_size` is
+// what the store really occupies and `reclaimable` what cleanup can free.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ComponentStoreReport {
+    // Size Explorer and scans report, counting hard links in full
+    apparent_size: u64,
+    actual_size: u64,
+    shared_with_windows: u64,
+    backups_and_disabled_features: u64,
+    cache_and_temporary_data: u64,
+    // Backups plus cache, the most cleanup could remove
+    reclaimable: u64,
+    reclaimable_packages: u32,
+    last_cleanup: Option<String>,
+    cleanup_recommended: bool,
+}
+
+// Analyze the component store. Needs administrator rights and takes a minute or two.
+#[command]
+pub async fn analyze_component_store() -> Result<ComponentStoreReport, DiskSenseError> {
+    let output = run_dism(&["/Online", "/Cleanup-Image", "/AnalyzeComponentStore"])?;
+    Ok(parse_report(&output))
+}
+
+// Remove superseded components. With `reset_base` all superseded versions are
+// removed for good, which frees more but means installed updates can no longer
+// be uninstalled.
+#[command]
+pub async fn clean_component_store(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    reset_base: bool,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    // Cleanup can't be undone, so the front-end must explicitly confirm it
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Component store cleanup must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    let mut args = vec!["/Online", "/Cleanup-Image", "/StartComponentCleanup"];
+    if reset_base {
+        args.push("/ResetBase");
+    }
+    let result = run_dism(&args);
+    audit::record(
+        &app,
+        "clean_component_store",
+        action.as_deref(),
+        vec![args.join(" ")],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn run_dism(args: &[&str]) -> Result<String, DiskSenseError> {
+    if !cfg!(target_os = "windows") {
+        return Err(DiskSenseError::Unsupported(
+            "The component store is only available on Windows".to_string(),
+        ));
+    }
+
+    let output = Command::new("dism")
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "DISM"))?;
+
+    // DISM writes its errors to stdout
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else if output.status.code() == Some(740) {
+        // ERROR_ELEVATION_REQUIRED
+        Err(DiskSenseError::PermissionDenied {
+            path: "WinSxS".to_string(),
+        })
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "DISM failed: {}",
+            stdout.lines().last().unwrap_or("").trim()
+        )))
+    }
+}
+
+// Lines look like "Actual Size of Component Store : 7.83 GB"
+fn parse_report(output: &str) -> ComponentStoreReport {
+    let mut report = ComponentStoreReport::default();
+    for line in output.lines() {
+        let (key, value) = match line.split_once(" : ") {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        match key {
+            "Windows Explorer Reported Size of Component Store" => {
+                report.apparent_size = parse_human_size(value, 1024.0)
+            }
+            "Actual Size of Component Store" => {
+                report.actual_size = parse_human_size(value, 1024.0)
+            }
+            "Shared with Windows" => report.shared_with_windows = parse_human_size(value, 1024.0),
+            "Backups and Disabled Features" => {
+                report.backups_and_disabled_features = parse_human_size(value, 1024.0)
+            }
+            "Cache and Temporary Data" => {
+                report.cache_and_temporary_data = parse_human_size(value, 1024.0)
+            }
+            "Date of Last Cleanup" => report.last_cleanup = Some(value.to_string()),
+            "Number of Reclaimable Packages" => {
+                report.reclaimable_packages = value.parse().unwrap_or(0)
+            }
+            "Component Store Cleanup Recommended" => {
+                report.cleanup_recommended = value.eq_ignore_ascii_case("yes")
+            }
+            _ => {}
+        }
+    }
+
+    report.reclaimable = report.backups_and_disabled_features + report.cache_and_temporary_data;
+    report
+}
@@ -0,0 +1,124 @@
+use crate::budgets::{DirBudget, DirBudgets};
+use crate::cleanup_rules::{CleanupRule, CleanupRules};
+use crate::hooks::{HookSettings, Hooks};
+use crate::read_only::ReadOnlyMode;
+use crate::search::{SavedSearch, SavedSearches};
+use crate::{DiskSenseError, ProtectedPaths};
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, State};
+
+// Bumped when a section changes incompatibly; newer files are refused
+const CONFIG_VERSION: u32 = 1;
+
+// Everything a user sets up, as written by `export_config`. Sections missing
+// from an imported file are left as they are.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppConfig {
+    version: u32,
+    read_only: Option<bool>,
+    // The user's own protected paths
+    protected_paths: Option<Vec<String>>,
+    budgets: Option<Vec<DirBudget>>,
+    cleanup_rules: Option<Vec<CleanupRule>>,
+    hooks: Option<HookSettings>,
+    saved_searches: Option<Vec<SavedSearch>>,
+    // Settings the front-end keeps itself, stored as given
+    front_end: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConfigImport {
+    // Names of the sections that were replaced
+    imported: Vec<String>,
+    // Front-end settings from the file, for the front-end to apply
+    front_end: Option<serde_json::Value>,
+}
+
+// Write the settings, protected paths, budgets, cleanup rules, hooks and saved
+// searches to `file` as JSON, for another machine or a team to import
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn export_config(
+    read_only: State<'_, ReadOnlyMode>,
+    protected: State<'_, ProtectedPaths>,
+    budgets: State<'_, DirBudgets>,
+    rules: State<'_, CleanupRules>,
+    hooks: State<'_, Hooks>,
+    searches: State<'_, SavedSearches>,
+    file: String,
+    front_end: Option<serde_json::Value>,
+) -> Result<(), DiskSenseError> {
+    let config = AppConfig {
+        version: CONFIG_VERSION,
+        read_only: Some(read_only.is_enabled()),
+        protected_paths: Some(protected.user_paths()),
+        budgets: Some(budgets.budgets()),
+        cleanup_rules: Some(rules.rules()),
+        hooks: Some(hooks.settings()),
+        saved_searches: Some(searches.searches()),
+        front_end,
+    };
+
+    let contents = serde_json::to_string_pretty(&config)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to export settings: {}", e)))?;
+    std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+}
+
+// Replace the sections present in `file` with its contents. Imported hooks are
+// switched off until enabled again here.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn import_config(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    protected: State<'_, ProtectedPaths>,
+    budgets: State<'_, DirBudgets>,
+    rules: State<'_, CleanupRules>,
+    hooks: State<'_, Hooks>,
+    searches: State<'_, SavedSearches>,
+    file: String,
+) -> Result<ConfigImport, DiskSenseError> {
+    let contents = std::fs::read_to_string(&file).map_err(|e| DiskSenseError::from_io(e, &file))?;
+    let config: AppConfig = serde_json::from_str(&contents).map_err(|e| {
+        DiskSenseError::InvalidInput(format!("{} is not a DiskSense settings file: {}", file, e))
+    })?;
+    if config.version > CONFIG_VERSION {
+        return Err(DiskSenseError::Unsupported(format!(
+            "{} was written by a newer version of DiskSense",
+            file
+        )));
+    }
+
+    // Sections that can be rejected go first, so a bad pattern stops the import
+    // before protected paths, budgets or read-only mode change
+    let mut imported = Vec::new();
+    if let Some(list) = config.cleanup_rules {
+        rules.import(&app, list)?;
+        imported.push("cleanup_rules".to_string());
+    }
+    if let Some(list) = config.saved_searches {
+        searches.import(&app, list)?;
+        imported.push("saved_searches".to_string());
+    }
+    if let Some(settings) = config.hooks {
+        hooks.import(&app, settings)?;
+        imported.push("hooks".to_string());
+    }
+    if let Some(paths) = config.protected_paths {
+        protected.import(&app, paths)?;
+        imported.push("protected_paths".to_string());
+    }
+    if let Some(list) = config.budgets {
+        budgets.import(&app, list)?;
+        imported.push("budgets".to_string());
+    }
+    if let Some(enabled) = config.read_only {
+        read_only.import(&app, enabled)?;
+        imported.push("read_only".to_string());
+    }
+
+    Ok(ConfigImport {
+        imported,
+        front_end: config.front_end,
+    })
+}
@@ -0,0 +1,172 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, os_path, DiskSenseError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Emitter, State};
+use walkdir::WalkDir;
+
+// Minimum time between "copy-progress" events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// What to do when a file already exists at the destination
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OverwritePolicy {
+    Skip,
+    Overwrite,
+    // Copy next to it as "name (1).ext"
+    Rename,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CopyProgress {
+    bytes_copied: u64,
+    bytes_total: u64,
+    current_path: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct CopyReport {
+    // Path of the copy of `src`
+    destination: String,
+    files_copied: u64,
+    files_skipped: u64,
+    files_renamed: u64,
+    bytes_copied: u64,
+    // Files that could not be copied; the rest still are
+    failures: Vec<DiskSenseError>,
+}
+
+// Copy a file or folder into the folder `dest`. Folders are merged into an
+// existing folder of the same name, with `overwrite_policy` deciding each
+// conflicting file. Links are copied as the files they point to.
+#[command]
+pub async fn copy_path(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    src: String,
+    dest: String,
+    overwrite_policy: OverwritePolicy,
+    action: Option<String>,
+) -> Result<CopyReport, DiskSenseError> {
+    // Copying only adds files unless it may replace existing ones
+    if overwrite_policy == OverwritePolicy::Overwrite {
+        read_only.ensure_writable()?;
+    }
+
+    let source = os_path::decode(&src);
+    let name = source
+        .file_name()
+        .ok_or_else(|| DiskSenseError::InvalidInput(format!("Cannot copy {}", src)))?;
+    let dest_dir = os_path::decode(&dest);
+    let target = dest_dir.join(name);
+    if target.starts_with(&source) {
+        return Err(DiskSenseError::InvalidInput(
+            "Cannot copy a folder into itself".to_string(),
+        ));
+    }
+
+    let files: Vec<(PathBuf, u64)> = WalkDir::new(&source)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            (entry.into_path(), size)
+        })
+        .collect();
+    let bytes_total = files.iter().map(|(_, size)| size).sum();
+
+    let mut report = CopyReport {
+        destination: target.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    // Empty folders are recreated too
+    for entry in WalkDir::new(&source)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_dir())
+    {
+        let dir = target.join(entry.path().strip_prefix(&source).unwrap_or(Path::new("")));
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            report.failures.push(DiskSenseError::from_io(e, &dir));
+        }
+    }
+
+    let mut last_progress = Instant::now();
+    for (file, size) in files {
+        let relative = file.strip_prefix(&source).unwrap_or(Path::new(""));
+        let mut copy = if relative.as_os_str().is_empty() {
+            target.clone()
+        } else {
+            target.join(relative)
+        };
+
+        if copy.exists() {
+            match overwrite_policy {
+                OverwritePolicy::Skip => {
+                    report.files_skipped += 1;
+                    continue;
+                }
+                OverwritePolicy::Overwrite => {}
+                OverwritePolicy::Rename => {
+                    copy = free_name(&copy);
+                    report.files_renamed += 1;
+                }
+            }
+        }
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            let _ = app.emit(
+                "copy-progress",
+                CopyProgress {
+                    bytes_copied: report.bytes_copied,
+                    bytes_total,
+                    current_path: file.to_string_lossy().to_string(),
+                },
+            );
+        }
+
+        match std::fs::copy(&file, &copy) {
+            Ok(_) => {
+                report.files_copied += 1;
+                report.bytes_copied += size;
+            }
+            Err(e) => report.failures.push(DiskSenseError::from_io(e, &file)),
+        }
+    }
+
+    // Only a copy that may have replaced files changed anything worth auditing
+    if overwrite_policy == OverwritePolicy::Overwrite {
+        audit::record(
+            &app,
+            "copy_path",
+            action.as_deref(),
+            vec![src, report.destination.clone()],
+            report.bytes_copied,
+            report.failures.first(),
+        );
+    }
+    Ok(report)
+}
+
+// "report.pdf" -> "report (1).pdf", the first such name not taken
+fn free_name(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
@@ -0,0 +1,374 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{command, State};
+
+// Minimum time between "delete-progress" events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DeleteProgress {
+    items_removed: u64,
+    bytes_freed: u64,
+    current_path: String,
+}
+
+// Set by cancel_delete; a delete checks it before removing each item
+#[derive(Default)]
+pub struct DeleteCancel {
+    cancelled: AtomicBool,
+}
+
+impl DeleteCancel {
+    // Clear any cancel left over from an earlier delete
+    pub(crate) fn reset(&self) -> &AtomicBool {
+        self.cancelled.store(false, Ordering::SeqCst);
+        &self.cancelled
+    }
+}
+
+// Stop the running delete. Whatever was already removed stays removed.
+#[command]
+pub async fn cancel_delete(cancel: State<'_, DeleteCancel>) -> Result<(), DiskSenseError> {
+    cancel.cancelled.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct DeletionReport {
+    files_removed: u64,
+    dirs_removed: u64,
+    bytes_reclaimed: u64,
+    // Items that could not be removed; the rest of the tree is still deleted
+    failures: Vec<DiskSenseError>,
+    // Stopped by cancel_delete; the counts above are what went before that
+    cancelled: bool,
+}
+
+impl DeletionReport {
+    pub(crate) fn bytes_reclaimed(&self) -> u64 {
+        self.bytes_reclaimed
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.failures.is_empty() && !self.cancelled
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    // The error the audit log records for this delete, if it was cut short
+    pub(crate) fn cancel_error(&self) -> Option<&DiskSenseError> {
+        static CANCELLED: DiskSenseError = DiskSenseError::Cancelled;
+        self.cancelled.then_some(&CANCELLED)
+    }
+
+    // Fold the report of another deleted path into this one
+    pub(crate) fn merge(&mut self, other: DeletionReport) {
+        self.files_removed += other.files_removed;
+        self.dirs_removed += other.dirs_removed;
+        self.bytes_reclaimed += other.bytes_reclaimed;
+        self.failures.extend(other.failures);
+        self.cancelled |= other.cancelled;
+    }
+
+    pub(crate) fn fail(&mut self, error: DiskSenseError) {
+        self.failures.push(error);
+    }
+
+    // Staging moves an item on the same drive, so nothing is reclaimed until purge
+    pub(crate) fn staged(is_dir: bool) -> Self {
+        DeletionReport {
+            files_removed: u64::from(!is_dir),
+            dirs_removed: u64::from(is_dir),
+            ..Default::default()
+        }
+    }
+}
+
+// Remove a file or folder tree without ever following symlinks or junctions:
+// a link is removed itself, never the data it points to
+pub(crate) fn remove_path(path: &Path) -> Result<(), DiskSenseError> {
+    let mut report = remove_path_with_progress(path, &AtomicBool::new(false), |_| {})?;
+    match report.failures.is_empty() {
+        true => Ok(()),
+        false => Err(report.failures.swap_remove(0)),
+    }
+}
+
+// Same as remove_path, but walks the tree with an explicit stack so deep trees
+// can't overflow, reports progress at most every PROGRESS_INTERVAL and stops
+// once `cancelled` is set, returning what was removed so far in a report marked
+// cancelled. Items that fail are recorded in the report and the walk carries on.
+pub(crate) fn remove_path_with_progress(
+    path: &Path,
+    cancelled: &AtomicBool,
+    mut on_progress: impl FnMut(&DeleteProgress),
+) -> Result<DeletionReport, DiskSenseError> {
+    // A missing root is an error rather than an empty report
+    std::fs::symlink_metadata(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+
+    let mut report = DeletionReport::default();
+    let mut progress = DeleteProgress::default();
+    let mut last_report = Instant::now();
+    // Folders are pushed twice: once to list them, then again to remove them
+    // after their contents are gone
+    let mut stack: Vec<(PathBuf, bool)> = vec![(path.to_path_buf(), false)];
+
+    while let Some((current, listed)) = stack.pop() {
+        if cancelled.load(Ordering::SeqCst) {
+            report.cancelled = true;
+            break;
+        }
+
+        match remove_entry(&current, listed, &mut stack) {
+            Ok(Removed::File(size)) => {
+                report.files_removed += 1;
+                report.bytes_reclaimed += size;
+            }
+            Ok(Removed::Dir) => report.dirs_removed += 1,
+            Ok(Removed::Listed) => continue,
+            Err(e) => {
+                // A folder whose contents already failed can't be removed either;
+                // only report the item that caused it
+                let under_failure = report.failures.iter().any(|failure| {
+                    failure
+                        .path()
+                        .is_some_and(|p| Path::new(p).starts_with(&current))
+                });
+                if !(listed && under_failure) {
+                    report.failures.push(e);
+                }
+                continue;
+            }
+        }
+
+        progress.items_removed = report.files_removed + report.dirs_removed;
+        progress.bytes_freed = report.bytes_reclaimed;
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            progress.current_path = current.to_string_lossy().to_string();
+            on_progress(&progress);
+            last_report = Instant::now();
+        }
+    }
+
+    progress.current_path = path.to_string_lossy().to_string();
+    on_progress(&progress);
+    Ok(report)
+}
+
+enum Removed {
+    File(u64),
+    Dir,
+    // A folder's contents were queued; the folder itself comes later
+    Listed,
+}
+
+fn remove_entry(
+    path: &Path,
+    listed: bool,
+    stack: &mut Vec<(PathBuf, bool)>,
+) -> Result<Removed, DiskSenseError> {
+    if listed {
+        std::fs::remove_dir(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+        return Ok(Removed::Dir);
+    }
+
+    let metadata = std::fs::symlink_metadata(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+    let file_type = metadata.file_type();
+
+    if file_type.is_symlink() {
+        remove_link(path)?;
+        Ok(Removed::File(0))
+    } else if file_type.is_dir() {
+        let entries = std::fs::read_dir(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+        stack.push((path.to_path_buf(), true));
+        for entry in entries {
+            let entry = entry.map_err(|e| DiskSenseError::from_io(e, path))?;
+            stack.push((entry.path(), false));
+        }
+        Ok(Removed::Listed)
+    } else {
+        std::fs::remove_file(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+        Ok(Removed::File(metadata.len()))
+    }
+}
+
+// Windows directory symlinks and junctions are removed like empty folders
+#[cfg(target_os = "windows")]
+fn remove_link(path: &Path) -> Result<(), DiskSenseError> {
+    use std::os::windows::fs::FileTypeExt;
+
+    let file_type = std::fs::symlink_metadata(path)
+        .map_err(|e| DiskSenseError::from_io(e, path))?
+        .file_type();
+
+    let result = if file_type.is_symlink_dir() {
+        std::fs::remove_dir(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    result.map_err(|e| DiskSenseError::from_io(e, path))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove_link(path: &Path) -> Result<(), DiskSenseError> {
+    std::fs::remove_file(path).map_err(|e| DiskSenseError::from_io(e, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh folder under the system temp dir, removed by the test itself
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let dir = std::env::temp_dir().join(format!(
+            "disksense-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    fn link_dir(target: &Path, link: &Path) {
+        std::os::unix::fs::symlink(target, link).unwrap();
+    }
+
+    // Junctions need no privileges, unlike directory symlinks
+    #[cfg(target_os = "windows")]
+    fn link_dir(target: &Path, link: &Path) {
+        let status = std::process::Command::new("cmd")
+            .arg("/C")
+            .arg("mklink")
+            .arg("/J")
+            .arg(link)
+            .arg(target)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn remove_path_keeps_linked_folder_contents() {
+        let outside = temp_dir("outside");
+        std::fs::write(outside.join("keep.txt"), b"still here").unwrap();
+
+        let doomed = temp_dir("doomed");
+        std::fs::create_dir(doomed.join("nested")).unwrap();
+        std::fs::write(doomed.join("nested").join("gone.txt"), b"bye").unwrap();
+        link_dir(&outside, &doomed.join("nested").join("link"));
+
+        remove_path(&doomed).unwrap();
+
+        assert!(!doomed.exists());
+        assert!(outside.is_dir());
+        assert_eq!(
+            std::fs::read(outside.join("keep.txt")).unwrap(),
+            b"still here"
+        );
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn remove_path_removes_link_itself() {
+        let outside = temp_dir("target");
+        std::fs::write(outside.join("keep.txt"), b"still here").unwrap();
+        let parent = temp_dir("parent");
+        let link = parent.join("link");
+        link_dir(&outside, &link);
+
+        let report = remove_path_with_progress(&link, &AtomicBool::new(false), |_| {}).unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!(report.bytes_reclaimed(), 0);
+        assert!(std::fs::symlink_metadata(&link).is_err());
+        assert!(outside.join("keep.txt").is_file());
+        std::fs::remove_dir_all(&outside).unwrap();
+        std::fs::remove_dir_all(&parent).unwrap();
+    }
+
+    #[test]
+    fn remove_path_with_progress_counts_what_it_removed() {
+        let dir = temp_dir("counted");
+        std::fs::create_dir(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("a.bin"), [0u8; 10]).unwrap();
+        std::fs::write(dir.join("nested").join("b.bin"), [0u8; 5]).unwrap();
+
+        let mut last = DeleteProgress::default();
+        let report = remove_path_with_progress(&dir, &AtomicBool::new(false), |progress| {
+            last = progress.clone();
+        })
+        .unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!(report.files_removed, 2);
+        assert_eq!(report.dirs_removed, 2);
+        assert_eq!(report.bytes_reclaimed(), 15);
+        assert_eq!(last.items_removed, 4);
+        assert_eq!(last.bytes_freed, 15);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn cancelled_delete_returns_a_partial_report() {
+        let dir = temp_dir("cancelled");
+        std::fs::write(dir.join("kept.txt"), b"kept").unwrap();
+
+        let report = remove_path_with_progress(&dir, &AtomicBool::new(true), |_| {}).unwrap();
+
+        assert!(report.is_cancelled());
+        assert!(!report.is_complete());
+        assert!(matches!(
+            report.cancel_error(),
+            Some(DiskSenseError::Cancelled)
+        ));
+        assert_eq!(report.bytes_reclaimed(), 0);
+        assert!(dir.join("kept.txt").is_file());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_root_is_an_error() {
+        let dir = temp_dir("missing");
+        std::fs::remove_dir(&dir).unwrap();
+
+        let result = remove_path_with_progress(&dir, &AtomicBool::new(false), |_| {});
+
+        assert!(matches!(result, Err(DiskSenseError::NotFound { .. })));
+    }
+
+    #[test]
+    fn merged_reports_add_up_and_keep_a_cancel() {
+        let mut report = DeletionReport {
+            files_removed: 1,
+            bytes_reclaimed: 10,
+            ..Default::default()
+        };
+        report.merge(DeletionReport {
+            files_removed: 2,
+            dirs_removed: 1,
+            bytes_reclaimed: 5,
+            cancelled: true,
+            ..Default::default()
+        });
+        report.merge(DeletionReport::default());
+        report.fail(DiskSenseError::Cancelled);
+
+        assert_eq!(report.files_removed, 3);
+        assert_eq!(report.dirs_removed, 1);
+        assert_eq!(report.bytes_reclaimed(), 15);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.is_cancelled());
+        assert!(report.cancel_error().is_some());
+        assert!(DeletionReport::default().cancel_error().is_none());
+    }
+}
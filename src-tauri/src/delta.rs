@@ -0,0 +1,485 @@
+use crate::{unicode_names, DiskItem};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NodeDelta {
+    id: String,
+    path: String,
+    size: u64,
+    estimated: bool,
+    added: Vec<DiskItem>,
+    removed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScanDelta {
+    scan_id: String,
+    changes: Vec<NodeDelta>,
+}
+
+impl ScanDelta {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+// Give every node an id derived from its canonical path, so it survives rescans.
+// The raw path keeps names that only show lossily apart.
+pub fn assign_ids(item: &mut DiskItem) {
+    item.id = node_id(item.raw_path.as_deref().unwrap_or(&item.path));
+
+    if let Some(children) = item.children.as_mut() {
+        for child in children {
+            assign_ids(child);
+        }
+    }
+}
+
+// 64-bit FNV-1a: unlike std's hasher, its output is fixed across builds and sessions.
+// Paths are hashed byte for byte: Linux allows two siblings whose names differ
+// only in normalization form, and they need ids of their own.
+pub(crate) fn node_id(path: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in path.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+// Give nodes of a rescan the ids they had in the previous tree when the rescan
+// reports their name in another normalization form (e.g. after a copy through
+// macOS), so the front-end keeps them instead of seeing one removed and one
+// added. Names are only compared in NFC form among the children left over
+// after matching by id, and only when that form is unique among them.
+pub fn carry_ids(old: &DiskItem, new: &mut DiskItem) {
+    let (Some(old_children), Some(new_children)) = (old.children.as_ref(), new.children.as_mut())
+    else {
+        return;
+    };
+
+    let by_id: HashMap<&str, &DiskItem> = old_children
+        .iter()
+        .map(|child| (child.id.as_str(), child))
+        .collect();
+    let new_ids: HashSet<&str> = new_children.iter().map(|child| child.id.as_str()).collect();
+    // None marks a name shared by several leftovers, which can't be told apart
+    let mut leftovers: HashMap<String, Option<&DiskItem>> = HashMap::new();
+    for child in old_children
+        .iter()
+        .filter(|child| !new_ids.contains(child.id.as_str()))
+    {
+        leftovers
+            .entry(unicode_names::nfc(&child.name).into_owned())
+            .and_modify(|leftover| *leftover = None)
+            .or_insert(Some(child));
+    }
+
+    for child in new_children.iter_mut() {
+        let previous = match by_id.get(child.id.as_str()) {
+            Some(previous) => Some(*previous),
+            None => leftovers
+                .get_mut(&*unicode_names::nfc(&child.name))
+                .and_then(Option::take),
+        };
+        if let Some(previous) = previous {
+            child.id = previous.id.clone();
+            carry_ids(previous, child);
+        }
+    }
+}
+
+// Minimal set of changes that turns `old` into `new`, for patching the front-end state
+pub fn diff(scan_id: &str, old: &DiskItem, new: &DiskItem) -> ScanDelta {
+    let mut changes = Vec::new();
+    diff_node(old, new, &mut changes);
+
+    ScanDelta {
+        scan_id: scan_id.to_string(),
+        changes,
+    }
+}
+
+fn diff_node(old: &DiskItem, new: &DiskItem, changes: &mut Vec<NodeDelta>) {
+    let old_children: HashMap<&str, &DiskItem> = old
+        .children
+        .iter()
+        .flatten()
+        .map(|child| (child.id.as_str(), child))
+        .collect();
+    let new_children = new.children.as_deref().unwrap_or_default();
+
+    let added: Vec<DiskItem> = new_children
+        .iter()
+        .filter(|child| !old_children.contains_key(child.id.as_str()))
+        .cloned()
+        .collect();
+    let removed: Vec<String> = old_children
+        .keys()
+        .filter(|id| !new_children.iter().any(|child| child.id == **id))
+        .map(|id| id.to_string())
+        .collect();
+
+    if old.size != new.size
+        || old.estimated != new.estimated
+        || !added.is_empty()
+        || !removed.is_empty()
+    {
+        changes.push(NodeDelta {
+            id: new.id.clone(),
+            path: new.path.clone(),
+            size: new.size,
+            estimated: new.estimated,
+            added,
+            removed,
+        });
+    }
+
+    // Unchanged sizes can still hide a move between two children, so always descend
+    for child in new_children {
+        if let Some(previous) = old_children.get(child.id.as_str()) {
+            diff_node(previous, child, changes);
+        }
+    }
+}
+
+// Update `root` in place after `path` was deleted, returning the changes. A fully
+// removed node is dropped from its parent; a partly deleted folder stays and
+// shrinks by `bytes`. None when `path` isn't in this tree.
+pub(crate) fn shrink(
+    scan_id: &str,
+    root: &mut DiskItem,
+    path: &str,
+    bytes: u64,
+    removed: bool,
+) -> Option<ScanDelta> {
+    let mut changes = Vec::new();
+    shrink_node(root, Path::new(path), bytes, removed, &mut changes)?;
+
+    Some(ScanDelta {
+        scan_id: scan_id.to_string(),
+        changes,
+    })
+}
+
+// Returns the bytes taken off `item`
+fn shrink_node(
+    item: &mut DiskItem,
+    path: &Path,
+    bytes: u64,
+    removed: bool,
+    changes: &mut Vec<NodeDelta>,
+) -> Option<u64> {
+    let children = item.children.as_mut()?;
+    let index = children
+        .iter()
+        .position(|child| !child.hidden && path.starts_with(&child.path))?;
+
+    let mut removed_ids = Vec::new();
+    let freed = if Path::new(&children[index].path) != path {
+        shrink_node(&mut children[index], path, bytes, removed, changes)?
+    } else if removed {
+        // The tree's own size for the node keeps every ancestor consistent
+        let child = children.remove(index);
+        removed_ids.push(child.id);
+        child.size
+    } else {
+        let child = &mut children[index];
+        let freed = bytes.min(child.size);
+        child.size -= freed;
+        changes.push(NodeDelta {
+            id: child.id.clone(),
+            path: child.path.clone(),
+            size: child.size,
+            estimated: child.estimated,
+            added: Vec::new(),
+            removed: Vec::new(),
+        });
+        freed
+    };
+
+    item.size = item.size.saturating_sub(freed);
+    changes.push(NodeDelta {
+        id: item.id.clone(),
+        path: item.path.clone(),
+        size: item.size,
+        estimated: item.estimated,
+        added: Vec::new(),
+        removed: removed_ids,
+    });
+    Some(freed)
+}
+
+// Update `root` in place after the file at `path` changed to `size` outside the
+// app, returning the changes. None when `path` isn't in this tree.
+pub(crate) fn resize(
+    scan_id: &str,
+    root: &mut DiskItem,
+    path: &str,
+    size: u64,
+) -> Option<ScanDelta> {
+    let mut changes = Vec::new();
+    resize_node(root, Path::new(path), size, &mut changes)?;
+
+    Some(ScanDelta {
+        scan_id: scan_id.to_string(),
+        changes,
+    })
+}
+
+fn resize_node(
+    item: &mut DiskItem,
+    path: &Path,
+    size: u64,
+    changes: &mut Vec<NodeDelta>,
+) -> Option<()> {
+    let children = item.children.as_mut()?;
+    let child = children
+        .iter_mut()
+        .find(|child| !child.hidden && path.starts_with(&child.path))?;
+
+    let before = child.size;
+    if Path::new(&child.path) != path {
+        resize_node(child, path, size, changes)?;
+    } else {
+        child.size = size;
+        changes.push(size_change(child));
+    }
+
+    item.size = item.size.saturating_sub(before) + child.size;
+    changes.push(size_change(item));
+    Some(())
+}
+
+// Give the node at `path` the children it was listed without, such as the
+// contents of an archive, returning the change. Sizes stay as they are. None
+// when `path` isn't in this tree.
+pub(crate) fn attach(
+    scan_id: &str,
+    root: &mut DiskItem,
+    path: &str,
+    children: &[DiskItem],
+) -> Option<ScanDelta> {
+    let node = find_node_mut(root, Path::new(path))?;
+    node.children = Some(children.to_vec());
+
+    Some(ScanDelta {
+        scan_id: scan_id.to_string(),
+        changes: vec![NodeDelta {
+            added: children.to_vec(),
+            ..size_change(node)
+        }],
+    })
+}
+
+fn find_node_mut<'a>(item: &'a mut DiskItem, path: &Path) -> Option<&'a mut DiskItem> {
+    if Path::new(&item.path) == path {
+        return Some(item);
+    }
+    item.children
+        .as_mut()?
+        .iter_mut()
+        .filter(|child| !child.hidden && path.starts_with(&child.path))
+        .find_map(|child| find_node_mut(child, path))
+}
+
+fn size_change(item: &DiskItem) -> NodeDelta {
+    NodeDelta {
+        id: item.id.clone(),
+        path: item.path.clone(),
+        size: item.size,
+        estimated: item.estimated,
+        added: Vec::new(),
+        removed: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: u64) -> DiskItem {
+        DiskItem {
+            name: Path::new(path)
+                .file_name()
+                .map_or_else(String::new, |n| n.to_string_lossy().to_string()),
+            path: path.to_string(),
+            size,
+            is_dir: false,
+            children: None,
+            game: None,
+            git_repo: false,
+            id: String::new(),
+            allocated_size: None,
+            sparse: false,
+            link_target: None,
+            hard_links: None,
+            in_archive: false,
+            raw_path: None,
+            estimated: false,
+            hidden: false,
+            known_folder: None,
+            allocated_partial: false,
+        }
+    }
+
+    // A folder sized by its children, with ids assigned throughout
+    fn dir(path: &str, children: Vec<DiskItem>) -> DiskItem {
+        let mut item = file(path, children.iter().map(|child| child.size).sum());
+        item.is_dir = true;
+        item.children = Some(children);
+        assign_ids(&mut item);
+        item
+    }
+
+    fn change<'a>(delta: &'a ScanDelta, path: &str) -> &'a NodeDelta {
+        delta
+            .changes
+            .iter()
+            .find(|change| change.path == path)
+            .unwrap_or_else(|| panic!("no change for {}", path))
+    }
+
+    #[test]
+    fn ids_depend_only_on_the_path() {
+        let a = dir("/r", vec![file("/r/a", 1)]);
+        let b = dir("/r", vec![file("/r/a", 99)]);
+        assert_eq!(a.id, b.id);
+        assert_eq!(
+            a.children.as_ref().unwrap()[0].id,
+            b.children.as_ref().unwrap()[0].id
+        );
+        assert_ne!(a.id, a.children.as_ref().unwrap()[0].id);
+    }
+
+    #[test]
+    fn diff_of_identical_trees_is_empty() {
+        let tree = dir("/r", vec![dir("/r/d", vec![file("/r/d/x", 5)])]);
+        assert!(diff("scan", &tree, &tree.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_lists_added_and_removed_children_and_new_sizes() {
+        let old = dir(
+            "/r",
+            vec![dir("/r/d", vec![file("/r/d/x", 5), file("/r/d/y", 3)])],
+        );
+        let new = dir(
+            "/r",
+            vec![dir("/r/d", vec![file("/r/d/x", 5), file("/r/d/z", 10)])],
+        );
+
+        let delta = diff("scan", &old, &new);
+        let folder = change(&delta, "/r/d");
+        assert_eq!(folder.size, 15);
+        assert_eq!(folder.added.len(), 1);
+        assert_eq!(folder.added[0].path, "/r/d/z");
+        assert_eq!(folder.removed, vec![node_id("/r/d/y")]);
+        assert_eq!(change(&delta, "/r").size, 15);
+        assert!(change(&delta, "/r").added.is_empty());
+    }
+
+    #[test]
+    fn diff_descends_when_a_size_moves_between_children() {
+        let old = dir("/r", vec![file("/r/a", 4), file("/r/b", 6)]);
+        let new = dir("/r", vec![file("/r/a", 6), file("/r/b", 4)]);
+
+        let delta = diff("scan", &old, &new);
+        assert_eq!(change(&delta, "/r/a").size, 6);
+        assert_eq!(change(&delta, "/r/b").size, 4);
+        assert!(delta.changes.iter().all(|change| change.path != "/r"));
+    }
+
+    #[test]
+    fn carry_ids_keeps_nodes_renamed_to_another_normalization_form() {
+        let old = dir(
+            "/r",
+            vec![dir("/r/caf\u{e9}", vec![file("/r/caf\u{e9}/menu", 2)])],
+        );
+        let mut new = dir(
+            "/r",
+            vec![dir("/r/cafe\u{301}", vec![file("/r/cafe\u{301}/menu", 2)])],
+        );
+
+        carry_ids(&old, &mut new);
+
+        let (old_child, new_child) = (
+            &old.children.as_ref().unwrap()[0],
+            &new.children.as_ref().unwrap()[0],
+        );
+        assert_eq!(new_child.id, old_child.id);
+        assert_eq!(
+            new_child.children.as_ref().unwrap()[0].id,
+            old_child.children.as_ref().unwrap()[0].id
+        );
+        assert!(diff("scan", &old, &new).is_empty());
+    }
+
+    #[test]
+    fn carry_ids_leaves_ambiguous_names_alone() {
+        // Both old siblings have the same NFC name, so neither can be picked
+        let old = dir(
+            "/r",
+            vec![file("/r/caf\u{e9}", 1), file("/r/cafe\u{301}", 1)],
+        );
+        let mut new = dir("/r", vec![file("/r/other", 1)]);
+        new.children.as_mut().unwrap()[0].name = "caf\u{e9}".to_string();
+
+        carry_ids(&old, &mut new);
+
+        assert_eq!(new.children.as_ref().unwrap()[0].id, node_id("/r/other"));
+    }
+
+    #[test]
+    fn shrink_drops_a_removed_node_and_its_size_from_every_ancestor() {
+        let mut root = dir(
+            "/r",
+            vec![dir("/r/d", vec![file("/r/d/x", 5), file("/r/d/y", 3)])],
+        );
+
+        let delta = shrink("scan", &mut root, "/r/d/x", 0, true).unwrap();
+
+        assert_eq!(root.size, 3);
+        let folder = &root.children.as_ref().unwrap()[0];
+        assert_eq!(folder.size, 3);
+        assert_eq!(folder.children.as_ref().unwrap().len(), 1);
+        assert_eq!(change(&delta, "/r/d").removed, vec![node_id("/r/d/x")]);
+        assert_eq!(change(&delta, "/r").size, 3);
+    }
+
+    #[test]
+    fn shrink_keeps_a_partly_deleted_folder() {
+        let mut root = dir("/r", vec![dir("/r/d", vec![file("/r/d/x", 5)])]);
+
+        let delta = shrink("scan", &mut root, "/r/d", 2, false).unwrap();
+
+        assert_eq!(root.size, 3);
+        assert_eq!(root.children.as_ref().unwrap()[0].size, 3);
+        assert!(change(&delta, "/r/d").removed.is_empty());
+    }
+
+    #[test]
+    fn shrink_and_resize_ignore_paths_outside_the_tree() {
+        let mut root = dir("/r", vec![file("/r/a", 1)]);
+        assert!(shrink("scan", &mut root, "/other/a", 1, true).is_none());
+        assert!(resize("scan", &mut root, "/r/missing", 1).is_none());
+        assert_eq!(root.size, 1);
+    }
+
+    #[test]
+    fn resize_updates_every_ancestor() {
+        let mut root = dir(
+            "/r",
+            vec![dir("/r/d", vec![file("/r/d/x", 5)]), file("/r/y", 1)],
+        );
+
+        let delta = resize("scan", &mut root, "/r/d/x", 12).unwrap();
+
+        assert_eq!(root.size, 13);
+        assert_eq!(root.children.as_ref().unwrap()[0].size, 12);
+        assert_eq!(change(&delta, "/r/d/x").size, 12);
+        assert_eq!(change(&delta, "/r").size, 13);
+    }
+}
@@ -0,0 +1,179 @@
+use std::io;
+use std::path::Path;
+
+// Counts the entries of a directory without building a DirEntry, or making a
+// system call, per entry. Directories with 100k+ entries are counted several
+// times in a scan (to spot large folders and to extrapolate their size), so
+// reading whole batches of names at once adds up.
+pub(crate) trait EntryCounter: Sync {
+    // Entries in `path` other than "." and "..", stopping once `limit` is reached
+    fn count_entries(&self, path: &Path, limit: usize) -> io::Result<usize>;
+}
+
+// Portable fallback through std's directory iterator
+pub(crate) struct ReadDirCounter;
+
+impl EntryCounter for ReadDirCounter {
+    fn count_entries(&self, path: &Path, limit: usize) -> io::Result<usize> {
+        Ok(std::fs::read_dir(path)?.take(limit).count())
+    }
+}
+
+// Raw getdents64, reading 64 KiB of names per call
+#[cfg(target_os = "linux")]
+pub(crate) struct GetdentsCounter;
+
+#[cfg(target_os = "linux")]
+impl EntryCounter for GetdentsCounter {
+    fn count_entries(&self, path: &Path, limit: usize) -> io::Result<usize> {
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+
+        // d_ino (8), d_off (8), d_reclen (2), d_type (1), then the name
+        const NAME_OFFSET: usize = 19;
+
+        let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        let fd = unsafe {
+            libc::open(
+                c_path.as_ptr(),
+                libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Closed on every return
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // u64 keeps the records 8-byte aligned, as the kernel writes them
+        let mut buffer = vec![0u64; 8192];
+        let buffer_len = buffer.len() * std::mem::size_of::<u64>();
+        let mut count = 0;
+        while count < limit {
+            let read = unsafe {
+                libc::syscall(
+                    libc::SYS_getdents64,
+                    fd.as_raw_fd(),
+                    buffer.as_mut_ptr(),
+                    buffer_len,
+                )
+            };
+            if read < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if read == 0 {
+                break;
+            }
+
+            let bytes =
+                unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, read as usize) };
+            let mut offset = 0;
+            while offset + NAME_OFFSET < bytes.len() {
+                let record_len = u16::from_ne_bytes([bytes[offset + 16], bytes[offset + 17]]);
+                let name = &bytes[offset + NAME_OFFSET..offset + record_len as usize];
+                let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+                if name != b"." && name != b".." {
+                    count += 1;
+                }
+                offset += record_len as usize;
+            }
+        }
+
+        Ok(count.min(limit))
+    }
+}
+
+// GetFileInformationByHandleEx with FileFullDirectoryInfo, which hands a 64 KiB
+// buffer to NtQueryDirectoryFile and fills it with as many entries as fit
+#[cfg(target_os = "windows")]
+pub(crate) struct DirectoryInfoCounter;
+
+#[cfg(target_os = "windows")]
+impl EntryCounter for DirectoryInfoCounter {
+    fn count_entries(&self, path: &Path, limit: usize) -> io::Result<usize> {
+        use std::os::windows::fs::OpenOptionsExt;
+        use std::os::windows::io::AsRawHandle;
+        use winapi::shared::winerror::ERROR_NO_MORE_FILES;
+        use winapi::um::minwinbase::FileFullDirectoryInfo;
+        use winapi::um::winbase::{
+            GetFileInformationByHandleEx, FILE_FLAG_BACKUP_SEMANTICS, FILE_FULL_DIR_INFO,
+        };
+
+        // Directories can only be opened with backup semantics
+        let dir = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+            .open(path)?;
+
+        // u64 keeps the records 8-byte aligned, as the file system writes them
+        let mut buffer = vec![0u64; 8192];
+        let buffer_len = buffer.len() * std::mem::size_of::<u64>();
+        let name_offset = std::mem::offset_of!(FILE_FULL_DIR_INFO, FileName);
+        let mut count = 0;
+        while count < limit {
+            let ok = unsafe {
+                GetFileInformationByHandleEx(
+                    dir.as_raw_handle() as _,
+                    FileFullDirectoryInfo,
+                    buffer.as_mut_ptr() as _,
+                    buffer_len as u32,
+                )
+            };
+            if ok == 0 {
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() == Some(ERROR_NO_MORE_FILES as i32) {
+                    break;
+                }
+                return Err(err);
+            }
+
+            let base = buffer.as_ptr() as *const u8;
+            let mut offset = 0;
+            loop {
+                let info = unsafe { &*(base.add(offset) as *const FILE_FULL_DIR_INFO) };
+                let name = unsafe {
+                    std::slice::from_raw_parts(
+                        base.add(offset + name_offset) as *const u16,
+                        info.FileNameLength as usize / 2,
+                    )
+                };
+                if name != [b'.' as u16] && name != [b'.' as u16, b'.' as u16] {
+                    count += 1;
+                }
+                if info.NextEntryOffset == 0 {
+                    break;
+                }
+                offset += info.NextEntryOffset as usize;
+            }
+        }
+
+        Ok(count.min(limit))
+    }
+}
+
+// The fastest counter for this platform
+pub(crate) fn platform_counter() -> &'static dyn EntryCounter {
+    #[cfg(target_os = "linux")]
+    {
+        &GetdentsCounter
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        &DirectoryInfoCounter
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        &ReadDirCounter
+    }
+}
+
+// Count with the platform counter, falling back to read_dir where it can't be
+// used, e.g. on file systems that don't support the batched call
+pub(crate) fn count_entries(path: &Path, limit: usize) -> io::Result<usize> {
+    platform_counter()
+        .count_entries(path, limit)
+        .or_else(|_| ReadDirCounter.count_entries(path, limit))
+}
@@ -0,0 +1,184 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, parse_human_size, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerStorageEntry {
+    kind: String,
+    total_count: usize,
+    active_count: usize,
+    size: u64,
+    reclaimable: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContainerStorageReport {
+    engine: String,
+    data_root: Option<String>,
+    data_root_size: Option<u64>,
+    entries: Vec<ContainerStorageEntry>,
+}
+
+// Container engines we know how to query, in order of preference
+const ENGINES: [&str; 2] = ["docker", "podman"];
+
+#[command]
+pub async fn analyze_docker() -> Result<ContainerStorageReport, DiskSenseError> {
+    for engine in ENGINES {
+        // Skip engines that are not installed or whose daemon is not reachable
+        let entries = match query_system_df(engine) {
+            Some(entries) => entries,
+            None => continue,
+        };
+
+        let data_root = query_data_root(engine);
+        let data_root_size = data_root
+            .as_ref()
+            .and_then(|root| get_size(Path::new(root)).ok());
+
+        return Ok(ContainerStorageReport {
+            engine: engine.to_string(),
+            data_root,
+            data_root_size,
+            entries,
+        });
+    }
+
+    Err(DiskSenseError::Unsupported(
+        "No running Docker or Podman engine was found".to_string(),
+    ))
+}
+
+#[command]
+pub async fn prune_docker(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    engine: String,
+    target: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    // Pruning is irreversible, so the front-end must explicitly confirm it
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Prune must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    if !ENGINES.contains(&engine.as_str()) {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Unsupported container engine: {}",
+            engine
+        )));
+    }
+
+    let args: &[&str] = match (engine.as_str(), target.as_str()) {
+        (_, "images") => &["image", "prune", "-a", "-f"],
+        (_, "containers") => &["container", "prune", "-f"],
+        (_, "volumes") => &["volume", "prune", "-f"],
+        ("docker", "build-cache") => &["builder", "prune", "-a", "-f"],
+        _ => {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "Unsupported prune target for {}: {}",
+                engine, target
+            )))
+        }
+    };
+
+    let result = prune(&engine, args);
+    audit::record(
+        &app,
+        "prune_docker",
+        action.as_deref(),
+        vec![format!("{} {}", engine, target)],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn prune(engine: &str, args: &[&str]) -> Result<String, DiskSenseError> {
+    let output = Command::new(engine)
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, engine))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "{} prune failed: {}",
+            engine,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+// Run `<engine> system df` and parse one entry per storage kind
+fn query_system_df(engine: &str) -> Option<Vec<ContainerStorageEntry>> {
+    // Docker and Podman name the count column differently
+    let template = if engine == "podman" {
+        "{{.Type}}\t{{.Total}}\t{{.Active}}\t{{.Size}}\t{{.Reclaimable}}"
+    } else {
+        "{{.Type}}\t{{.TotalCount}}\t{{.Active}}\t{{.Size}}\t{{.Reclaimable}}"
+    };
+
+    let output = Command::new(engine)
+        .args(["system", "df", "--format", template])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let entries = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 5 {
+                return None;
+            }
+
+            Some(ContainerStorageEntry {
+                kind: fields[0].trim().to_string(),
+                total_count: fields[1].trim().parse().unwrap_or(0),
+                active_count: fields[2].trim().parse().unwrap_or(0),
+                size: parse_human_size(fields[3], 1000.0),
+                reclaimable: parse_human_size(fields[4], 1000.0),
+            })
+        })
+        .collect();
+
+    Some(entries)
+}
+
+// Ask the engine where it keeps its images, containers and volumes on disk
+fn query_data_root(engine: &str) -> Option<String> {
+    let template = if engine == "podman" {
+        "{{.Store.GraphRoot}}"
+    } else {
+        "{{.DockerRootDir}}"
+    };
+
+    let output = Command::new(engine)
+        .args(["info", "--format", template])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let root = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if root.is_empty() {
+        None
+    } else {
+        Some(root)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{collect_drive_info, refresh_drive_space, DiskSenseError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter, State};
+
+// Shortest allowed interval, so a subscription can't busy-loop the disks
+const MIN_INTERVAL_MS: u64 = 500;
+
+// Emits "drive-stats" (the get_drive_info list) on a timer. Each subscription
+// bumps the generation, which stops the timer of the previous one.
+#[derive(Default)]
+pub struct DriveStatsTimer {
+    generation: Arc<AtomicU64>,
+}
+
+#[command]
+pub async fn subscribe_drive_stats(
+    app: AppHandle,
+    timer: State<'_, DriveStatsTimer>,
+    interval_ms: u64,
+) -> Result<(), DiskSenseError> {
+    let interval = Duration::from_millis(interval_ms.max(MIN_INTERVAL_MS));
+    let generation = timer.generation.clone();
+    let subscription = generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    std::thread::Builder::new()
+        .name("disksense-drive-stats".to_string())
+        .spawn(move || {
+            let mut drives = collect_drive_info();
+            while generation.load(Ordering::SeqCst) == subscription {
+                // Drive details only need collecting again when the drive set changes
+                if !refresh_drive_space(&mut drives) {
+                    drives = collect_drive_info();
+                }
+                let _ = app.emit("drive-stats", &drives);
+                std::thread::sleep(interval);
+            }
+        })
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to start drive stats timer: {}", e)))?;
+
+    Ok(())
+}
+
+#[command]
+pub async fn unsubscribe_drive_stats(
+    timer: State<'_, DriveStatsTimer>,
+) -> Result<(), DiskSenseError> {
+    timer.generation.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
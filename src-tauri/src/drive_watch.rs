@@ -0,0 +1,326 @@
+use crate::{collect_drive_info, DiskSenseError, DriveInfo};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::Disks;
+use tauri::{command, AppHandle, Emitter, State};
+
+// How often the mount list is checked where the OS offers no change notification
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Watches for volumes being mounted or removed and emits "drive-added" (DriveInfo)
+// and "drive-removed" (mount point) events
+pub struct DriveWatcher {
+    known: Arc<Mutex<HashMap<String, DriveInfo>>>,
+}
+
+impl DriveWatcher {
+    pub(crate) fn start(app: AppHandle) -> Self {
+        let known = Arc::new(Mutex::new(index_drives(collect_drive_info())));
+
+        let watched = known.clone();
+        std::thread::Builder::new()
+            .name("disksense-drive-watch".to_string())
+            .spawn(move || {
+                let mut monitor = MountMonitor::new();
+                let mut mounts = mount_points();
+                loop {
+                    monitor.wait();
+
+                    // Only gather full drive details once the set of mounts changes
+                    let current = mount_points();
+                    if current != mounts {
+                        emit_changes(&app, &watched);
+                        mounts = current;
+                    }
+                }
+            })
+            .ok();
+
+        DriveWatcher { known }
+    }
+}
+
+#[command]
+pub async fn rescan_drives(
+    app: AppHandle,
+    watcher: State<'_, DriveWatcher>,
+) -> Result<Vec<DriveInfo>, DiskSenseError> {
+    Ok(emit_changes(&app, &watcher.known))
+}
+
+fn index_drives(drives: Vec<DriveInfo>) -> HashMap<String, DriveInfo> {
+    drives
+        .into_iter()
+        .map(|drive| (drive.mount_point.clone(), drive))
+        .collect()
+}
+
+// Compare the current drives with the last known list and report the difference
+fn emit_changes(app: &AppHandle, known: &Mutex<HashMap<String, DriveInfo>>) -> Vec<DriveInfo> {
+    let drives = collect_drive_info();
+    let current = index_drives(drives.clone());
+    let mut known = known.lock().unwrap_or_else(|e| e.into_inner());
+
+    for (mount_point, drive) in &current {
+        if !known.contains_key(mount_point) {
+            let _ = app.emit("drive-added", drive);
+        }
+    }
+    for mount_point in known.keys() {
+        if !current.contains_key(mount_point) {
+            let _ = app.emit("drive-removed", mount_point);
+        }
+    }
+
+    *known = current;
+    drives
+}
+
+fn mount_points() -> HashSet<PathBuf> {
+    Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| disk.mount_point().to_path_buf())
+        .collect()
+}
+
+// Blocks until the mount table may have changed, using the platform's own
+// notification: the kernel flags /proc/self/mounts with POLLPRI on every mount
+// or unmount on Linux, Windows broadcasts WM_DEVICECHANGE to top-level windows,
+// and DiskArbitration calls back on macOS. Where none could be set up, a short
+// poll of the drive list stands in.
+struct MountMonitor {
+    #[cfg(target_os = "linux")]
+    mounts: Option<std::fs::File>,
+    #[cfg(target_os = "windows")]
+    window: Option<winapi::shared::windef::HWND>,
+    #[cfg(target_os = "macos")]
+    session: Option<ffi::CFTypeRef>,
+}
+
+// Set by the Windows window procedure or the DiskArbitration callbacks, which
+// run inside `wait` on the watcher's own thread
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+static MOUNTS_CHANGED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+impl MountMonitor {
+    fn new() -> Self {
+        MountMonitor {
+            #[cfg(target_os = "linux")]
+            mounts: std::fs::File::open("/proc/self/mounts").ok(),
+            #[cfg(target_os = "windows")]
+            window: device_window(),
+            #[cfg(target_os = "macos")]
+            session: arbitration_session(),
+        }
+    }
+
+    fn wait(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Some(mounts) = self.mounts.as_mut() {
+            use std::io::{Read, Seek, SeekFrom};
+            use std::os::unix::io::AsRawFd;
+
+            let mut fd = libc::pollfd {
+                fd: mounts.as_raw_fd(),
+                events: libc::POLLPRI,
+                revents: 0,
+            };
+            if unsafe { libc::poll(&mut fd, 1, -1) } > 0 {
+                // Re-reading the table acknowledges the change
+                let _ = mounts.seek(SeekFrom::Start(0));
+                let _ = mounts.read_to_end(&mut Vec::new());
+                return;
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        if self.window.is_some() {
+            use std::sync::atomic::Ordering;
+            use winapi::um::winuser::{DispatchMessageW, GetMessageW, TranslateMessage, MSG};
+
+            // Broadcasts are sent rather than posted, so they reach the window
+            // procedure from inside GetMessageW, which then returns for the
+            // message the procedure posts to wake it
+            let mut msg: MSG = unsafe { std::mem::zeroed() };
+            while unsafe { GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) } > 0 {
+                unsafe {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+                if MOUNTS_CHANGED.swap(false, Ordering::SeqCst) {
+                    return;
+                }
+            }
+        }
+
+        #[cfg(target_os = "macos")]
+        if self.session.is_some() {
+            use std::sync::atomic::Ordering;
+
+            // The run loop delivers the callbacks; it returns at once if it has
+            // nothing to run, in which case the poll below takes over
+            while unsafe { ffi::CFRunLoopRunInMode(ffi::kCFRunLoopDefaultMode, 60.0, 1) }
+                != ffi::RUN_FINISHED
+            {
+                if MOUNTS_CHANGED.swap(false, Ordering::SeqCst) {
+                    return;
+                }
+            }
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+// Volume arrival and removal, including mapped network drives, is only
+// broadcast to top-level windows, so the window is hidden rather than message-only
+#[cfg(target_os = "windows")]
+fn device_window() -> Option<winapi::shared::windef::HWND> {
+    use winapi::um::libloaderapi::GetModuleHandleW;
+    use winapi::um::winuser::{CreateWindowExW, RegisterClassW, WNDCLASSW};
+
+    let class_name: Vec<u16> = "DiskSenseDriveWatch\0".encode_utf16().collect();
+    let instance = unsafe { GetModuleHandleW(std::ptr::null()) };
+    let class = WNDCLASSW {
+        style: 0,
+        lpfnWndProc: Some(device_window_proc),
+        cbClsExtra: 0,
+        cbWndExtra: 0,
+        hInstance: instance,
+        hIcon: std::ptr::null_mut(),
+        hCursor: std::ptr::null_mut(),
+        hbrBackground: std::ptr::null_mut(),
+        lpszMenuName: std::ptr::null(),
+        lpszClassName: class_name.as_ptr(),
+    };
+    if unsafe { RegisterClassW(&class) } == 0 {
+        return None;
+    }
+
+    let window = unsafe {
+        CreateWindowExW(
+            0,
+            class_name.as_ptr(),
+            class_name.as_ptr(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            instance,
+            std::ptr::null_mut(),
+        )
+    };
+    (!window.is_null()).then_some(window)
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn device_window_proc(
+    window: winapi::shared::windef::HWND,
+    message: winapi::shared::minwindef::UINT,
+    wparam: winapi::shared::minwindef::WPARAM,
+    lparam: winapi::shared::minwindef::LPARAM,
+) -> winapi::shared::minwindef::LRESULT {
+    use winapi::shared::minwindef::WPARAM;
+    use winapi::um::dbt::{DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE};
+    use winapi::um::winuser::{DefWindowProcW, PostMessageW, WM_DEVICECHANGE, WM_NULL};
+
+    if message == WM_DEVICECHANGE
+        && (wparam == DBT_DEVICEARRIVAL as WPARAM || wparam == DBT_DEVICEREMOVECOMPLETE as WPARAM)
+    {
+        MOUNTS_CHANGED.store(true, std::sync::atomic::Ordering::SeqCst);
+        PostMessageW(window, WM_NULL, 0, 0);
+    }
+    DefWindowProcW(window, message, wparam, lparam)
+}
+
+// A session calling back whenever a volume is mounted or unmounted (its volume
+// path changes) or its disk goes away, scheduled on the watcher thread's run loop
+#[cfg(target_os = "macos")]
+fn arbitration_session() -> Option<ffi::CFTypeRef> {
+    unsafe {
+        let session = ffi::DASessionCreate(std::ptr::null());
+        if session.is_null() {
+            return None;
+        }
+        ffi::DARegisterDiskDescriptionChangedCallback(
+            session,
+            std::ptr::null(),
+            ffi::kDADiskDescriptionWatchVolumePath,
+            volume_path_changed,
+            std::ptr::null_mut(),
+        );
+        ffi::DARegisterDiskDisappearedCallback(
+            session,
+            std::ptr::null(),
+            disk_disappeared,
+            std::ptr::null_mut(),
+        );
+        ffi::DASessionScheduleWithRunLoop(
+            session,
+            ffi::CFRunLoopGetCurrent(),
+            ffi::kCFRunLoopDefaultMode,
+        );
+        Some(session)
+    }
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn volume_path_changed(
+    _disk: ffi::CFTypeRef,
+    _keys: ffi::CFTypeRef,
+    _context: *mut std::ffi::c_void,
+) {
+    MOUNTS_CHANGED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn disk_disappeared(_disk: ffi::CFTypeRef, _context: *mut std::ffi::c_void) {
+    MOUNTS_CHANGED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(target_os = "macos")]
+#[allow(non_upper_case_globals)]
+mod ffi {
+    use std::ffi::c_void;
+
+    pub type CFTypeRef = *const c_void;
+
+    // CFRunLoopRunInMode's result when the loop has no sources to run
+    pub const RUN_FINISHED: i32 = 1;
+
+    #[link(name = "DiskArbitration", kind = "framework")]
+    extern "C" {
+        pub static kDADiskDescriptionWatchVolumePath: CFTypeRef;
+        pub fn DASessionCreate(allocator: CFTypeRef) -> CFTypeRef;
+        pub fn DASessionScheduleWithRunLoop(
+            session: CFTypeRef,
+            run_loop: CFTypeRef,
+            mode: CFTypeRef,
+        );
+        pub fn DARegisterDiskDescriptionChangedCallback(
+            session: CFTypeRef,
+            matching: CFTypeRef,
+            watch: CFTypeRef,
+            callback: extern "C" fn(CFTypeRef, CFTypeRef, *mut c_void),
+            context: *mut c_void,
+        );
+        pub fn DARegisterDiskDisappearedCallback(
+            session: CFTypeRef,
+            matching: CFTypeRef,
+            callback: extern "C" fn(CFTypeRef, *mut c_void),
+            context: *mut c_void,
+        );
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        pub static kCFRunLoopDefaultMode: CFTypeRef;
+        pub fn CFRunLoopGetCurrent() -> CFTypeRef;
+        pub fn CFRunLoopRunInMode(mode: CFTypeRef, seconds: f64, return_after_source: u8) -> i32;
+    }
+}
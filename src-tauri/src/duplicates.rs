@@ -0,0 +1,596 @@
+use crate::hash_cache::{self, HashCache, HashedFile};
+use crate::unicode_names::nfc;
+use crate::{hashing, DiskItem, DiskSenseError, ScanStore};
+use globset::{GlobBuilder, GlobMatcher};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, State};
+use walkdir::WalkDir;
+
+// Audio files read for tags in "audio" mode
+const AUDIO_EXTENSIONS: [&str; 9] = [
+    "mp3", "flac", "m4a", "aac", "ogg", "opus", "wav", "wma", "aiff",
+];
+
+// Tracks whose durations differ by at most this much can be the same recording
+const AUDIO_DURATION_TOLERANCE_SECS: u64 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateFile {
+    pub(crate) path: String,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateGroup {
+    // Content hash, "artist - title" in audio mode, or "name (size)" in name mode
+    key: String,
+    pub(crate) files: Vec<DuplicateFile>,
+    // Space freed by keeping only the largest file of the group
+    pub(crate) reclaimable: u64,
+}
+
+// A content group confirmed while the rest of the search is still hashing, sent
+// as a "duplicate-group" event. `roots` are the roots searched, as given.
+#[derive(Debug, Serialize, Clone)]
+pub struct DuplicateFound {
+    roots: Vec<String>,
+    group: DuplicateGroup,
+}
+
+// Files and bytes a root holds at or above the size threshold
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RootTotals {
+    root: String,
+    files: u64,
+    bytes: u64,
+}
+
+// Content two roots have in common, counting one copy per group
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RootOverlap {
+    first: String,
+    second: String,
+    shared_files: u64,
+    shared_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrossRootDuplicates {
+    groups: Vec<DuplicateGroup>,
+    roots: Vec<RootTotals>,
+    overlaps: Vec<RootOverlap>,
+}
+
+// Which files a search looks at, independent of any scan's options. A pattern
+// without a slash matches the file name ("*.jpg"), otherwise the path relative to
+// the searched root ("Photos/**/*.mp4"); case is ignored. Without include
+// patterns every file is included. Folders matching an exclude pattern
+// ("node_modules", "**/.git") are skipped whole.
+pub(crate) struct FileFilter {
+    include: Vec<(GlobMatcher, bool)>,
+    exclude: Vec<(GlobMatcher, bool)>,
+    min_size: u64,
+}
+
+impl FileFilter {
+    pub(crate) fn new(
+        include: &[String],
+        exclude: &[String],
+        min_size: Option<u64>,
+    ) -> Result<Self, DiskSenseError> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    GlobBuilder::new(&nfc(pattern))
+                        .case_insensitive(true)
+                        .literal_separator(true)
+                        .build()
+                        .map(|glob| (glob.compile_matcher(), !pattern.contains('/')))
+                        .map_err(|e| {
+                            DiskSenseError::InvalidInput(format!(
+                                "Invalid pattern {}: {}",
+                                pattern, e
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(FileFilter {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+            min_size: min_size.unwrap_or(1),
+        })
+    }
+}
+
+fn matches_any(patterns: &[(GlobMatcher, bool)], root: &Path, path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let name = nfc(&name);
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let relative = nfc(&relative);
+    patterns
+        .iter()
+        .any(|(matcher, on_name)| matcher.is_match(if *on_name { &*name } else { &*relative }))
+}
+
+// Groups from the last content-mode search of each root (or set of roots),
+// kept for overlays
+#[derive(Default)]
+pub struct DuplicateResults {
+    groups: Mutex<HashMap<String, Vec<DuplicateGroup>>>,
+}
+
+// Find duplicate files under `root`. "content" mode (the default) compares file
+// bytes; "audio" mode matches tracks by artist, title and duration tags, so the
+// same song in different encodings is found too; "name" mode matches files with
+// the same name and size, ignoring case and Unicode normalization. `include`
+// and `exclude` globs narrow the search, e.g. to "*.jpg" and "*.mp4". In content
+// mode each group is also reported as soon as it is confirmed, largest saving
+// first, so it can be reviewed while hashing goes on.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn find_duplicates(
+    app: AppHandle,
+    results: State<'_, DuplicateResults>,
+    cache: State<'_, HashCache>,
+    root: String,
+    mode: Option<String>,
+    min_size: Option<u64>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> Result<Vec<DuplicateGroup>, DiskSenseError> {
+    let content = mode.as_deref().unwrap_or("content") == "content";
+    let filter = FileFilter::new(
+        &include.unwrap_or_default(),
+        &exclude.unwrap_or_default(),
+        min_size,
+    )?;
+    let roots = vec![root.clone()];
+    let on_group = |group: &DuplicateGroup| {
+        let _ = app.emit(
+            "duplicate-group",
+            DuplicateFound {
+                roots: roots.clone(),
+                group: group.clone(),
+            },
+        );
+    };
+    let (root, groups) = duplicate_groups(&cache, &root, mode.as_deref(), &filter, &on_group)?;
+
+    // Only matching bytes prove that content exists elsewhere
+    if content {
+        results
+            .groups
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(root, groups.clone());
+    }
+    Ok(groups)
+}
+
+// Compare the contents of several roots, e.g. a user folder and its backup drive,
+// in one pass. Besides the groups, each pair of roots reports how much content it
+// shares, so a backup can be checked against the originals before they are deleted.
+// Groups are reported as they are confirmed, as in `find_duplicates`.
+#[command]
+pub async fn find_duplicates_across(
+    app: AppHandle,
+    results: State<'_, DuplicateResults>,
+    cache: State<'_, HashCache>,
+    roots: Vec<String>,
+    min_size: Option<u64>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> Result<CrossRootDuplicates, DiskSenseError> {
+    if roots.len() < 2 {
+        return Err(DiskSenseError::InvalidInput(
+            "At least two roots are needed to compare".to_string(),
+        ));
+    }
+    let filter = FileFilter::new(
+        &include.unwrap_or_default(),
+        &exclude.unwrap_or_default(),
+        min_size,
+    )?;
+
+    let mut canonical = Vec::new();
+    for root in &roots {
+        if !Path::new(root).is_dir() {
+            return Err(DiskSenseError::NotFound { path: root.clone() });
+        }
+        canonical.push(dunce::canonicalize(root).map_err(|e| DiskSenseError::from_io(e, root))?);
+    }
+
+    // Nested roots list the same files twice; a file is never its own duplicate
+    let mut files: HashMap<PathBuf, u64> = HashMap::new();
+    let mut totals = Vec::new();
+    for root in &canonical {
+        let listed = list_files(root, &filter);
+        totals.push(RootTotals {
+            root: root.to_string_lossy().to_string(),
+            files: listed.len() as u64,
+            bytes: listed.iter().map(|(_, size)| size).sum(),
+        });
+        files.extend(listed);
+    }
+
+    let on_group = |group: &DuplicateGroup| {
+        let _ = app.emit(
+            "duplicate-group",
+            DuplicateFound {
+                roots: roots.clone(),
+                group: group.clone(),
+            },
+        );
+    };
+    let mut groups = content_duplicates(files.into_iter().collect(), &cache, &on_group);
+    groups.sort_by_key(|item| std::cmp::Reverse(item.reclaimable));
+
+    let mut overlaps = Vec::new();
+    for (i, first) in canonical.iter().enumerate() {
+        for second in &canonical[i + 1..] {
+            let mut overlap = RootOverlap {
+                first: first.to_string_lossy().to_string(),
+                second: second.to_string_lossy().to_string(),
+                shared_files: 0,
+                shared_bytes: 0,
+            };
+            for group in &groups {
+                let has_copy_in = |root: &Path| {
+                    group
+                        .files
+                        .iter()
+                        .any(|file| Path::new(&file.path).starts_with(root))
+                };
+                if has_copy_in(first) && has_copy_in(second) {
+                    overlap.shared_files += 1;
+                    overlap.shared_bytes += group.files[0].size;
+                }
+            }
+            overlaps.push(overlap);
+        }
+    }
+
+    results
+        .groups
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(roots.join("\n"), groups.clone());
+
+    Ok(CrossRootDuplicates {
+        groups,
+        roots: totals,
+        overlaps,
+    })
+}
+
+// Duplicate groups under `root`, largest saving first, with the canonical root.
+// Content groups are also passed to `on_group` one by one while hashing.
+pub(crate) fn duplicate_groups(
+    cache: &HashCache,
+    root: &str,
+    mode: Option<&str>,
+    filter: &FileFilter,
+    on_group: &(dyn Fn(&DuplicateGroup) + Sync),
+) -> Result<(String, Vec<DuplicateGroup>), DiskSenseError> {
+    if !Path::new(root).is_dir() {
+        return Err(DiskSenseError::NotFound {
+            path: root.to_string(),
+        });
+    }
+    let root = dunce::canonicalize(root).map_err(|e| DiskSenseError::from_io(e, root))?;
+
+    let files = list_files(&root, filter);
+    let mut groups = match mode.unwrap_or("content") {
+        "content" => content_duplicates(files, cache, on_group),
+        "audio" => audio_duplicates(files),
+        "name" => name_duplicates(files),
+        other => {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "Unsupported duplicate mode: {}",
+                other
+            )))
+        }
+    };
+
+    groups.sort_by_key(|item| std::cmp::Reverse(item.reclaimable));
+    Ok((root.to_string_lossy().to_string(), groups))
+}
+
+// Bytes under each node of a stored scan that also exist elsewhere, keyed by
+// node id, from every duplicate search so far. Every file of a group has a copy
+// somewhere else, so all of them count. Nodes without duplicates are left out.
+#[command]
+pub async fn get_duplicate_overlay(
+    store: State<'_, ScanStore>,
+    results: State<'_, DuplicateResults>,
+    scan_id: String,
+) -> Result<HashMap<String, u64>, DiskSenseError> {
+    let root = store.get(&scan_id)?;
+
+    // A file found by searches of two overlapping roots still counts once
+    let mut files: HashMap<String, u64> = HashMap::new();
+    for group in results
+        .groups
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .flatten()
+    {
+        for file in &group.files {
+            files.insert(file.path.clone(), file.size);
+        }
+    }
+
+    // Folders past the depth limit have no children in the tree, so bytes are
+    // added to every ancestor path rather than summed from the nodes
+    let mut by_path: HashMap<&Path, u64> = HashMap::new();
+    for (path, size) in &files {
+        for ancestor in Path::new(path).ancestors() {
+            if !ancestor.starts_with(&root.path) {
+                break;
+            }
+            *by_path.entry(ancestor).or_default() += size;
+        }
+    }
+
+    let mut overlay = HashMap::new();
+    collect_overlay(&root, &by_path, &mut overlay);
+    Ok(overlay)
+}
+
+fn collect_overlay(
+    item: &DiskItem,
+    by_path: &HashMap<&Path, u64>,
+    overlay: &mut HashMap<String, u64>,
+) {
+    // The hidden entries' node stands for many files, none of them duplicates
+    if item.hidden {
+        return;
+    }
+    let bytes = match by_path.get(Path::new(&item.path)) {
+        Some(bytes) => *bytes,
+        None => return,
+    };
+    overlay.insert(item.id.clone(), bytes);
+
+    for child in item.children.iter().flatten() {
+        collect_overlay(child, by_path, overlay);
+    }
+}
+
+fn list_files(root: &Path, filter: &FileFilter) -> Vec<(PathBuf, u64)> {
+    WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !matches_any(&filter.exclude, root, entry.path())
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            filter.include.is_empty() || matches_any(&filter.include, root, entry.path())
+        })
+        .filter_map(|entry| {
+            let size = entry.metadata().ok()?.len();
+            (size >= filter.min_size).then(|| (entry.into_path(), size))
+        })
+        .collect()
+}
+
+fn group(key: String, files: Vec<(PathBuf, u64)>) -> DuplicateGroup {
+    let total: u64 = files.iter().map(|(_, size)| size).sum();
+    let largest = files.iter().map(|(_, size)| *size).max().unwrap_or(0);
+
+    DuplicateGroup {
+        key,
+        files: files
+            .into_iter()
+            .map(|(path, size)| DuplicateFile {
+                path: path.to_string_lossy().to_string(),
+                size,
+            })
+            .collect(),
+        reclaimable: total - largest,
+    }
+}
+
+// Only files sharing a size can be identical, so only those get hashed. Sizes
+// are taken largest possible saving first, and once all files of a size are
+// hashed its groups go to `on_group` while other sizes are still hashing.
+fn content_duplicates(
+    files: Vec<(PathBuf, u64)>,
+    cache: &HashCache,
+    on_group: &(dyn Fn(&DuplicateGroup) + Sync),
+) -> Vec<DuplicateGroup> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for (path, size) in files {
+        by_size.entry(size).or_default().push(path);
+    }
+
+    let mut candidates: Vec<(u64, Vec<PathBuf>)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+    candidates.sort_by_key(|(size, paths)| Reverse(size * (paths.len() as u64 - 1)));
+
+    // Bridged so the sizes are started in order rather than split across threads
+    let (groups, fresh): (Vec<Vec<DuplicateGroup>>, Vec<Vec<HashedFile>>) = candidates
+        .into_iter()
+        .par_bridge()
+        .map(|(size, paths)| {
+            let (groups, fresh) = same_size_duplicates(size, paths, cache);
+            groups.iter().for_each(on_group);
+            (groups, fresh)
+        })
+        .unzip();
+
+    cache.put_all(&fresh.into_iter().flatten().collect::<Vec<_>>());
+    groups.into_iter().flatten().collect()
+}
+
+// Groups among files of one size, with the hashes that weren't cached yet
+fn same_size_duplicates(
+    size: u64,
+    paths: Vec<PathBuf>,
+    cache: &HashCache,
+) -> (Vec<DuplicateGroup>, Vec<HashedFile>) {
+    // Files unchanged since an earlier search keep their stored hash
+    let mut hashed: Vec<(String, PathBuf)> = Vec::new();
+    let mut to_hash = Vec::new();
+    for path in paths {
+        let modified = hash_cache::modified(&path).unwrap_or_default();
+        match cache.get(&path, size, modified) {
+            Some(hash) => hashed.push((hash, path)),
+            None => to_hash.push((path, modified)),
+        }
+    }
+
+    let fresh: Vec<HashedFile> = to_hash
+        .into_par_iter()
+        .filter_map(|(path, modified)| {
+            let hash = hashing::hash_file(&path).ok()?;
+            Some(HashedFile {
+                path,
+                size,
+                modified,
+                hash,
+            })
+        })
+        .collect();
+    hashed.extend(
+        fresh
+            .iter()
+            .map(|file| (file.hash.clone(), file.path.clone())),
+    );
+
+    let mut by_hash: HashMap<String, Vec<(PathBuf, u64)>> = HashMap::new();
+    for (hash, path) in hashed {
+        by_hash.entry(hash).or_default().push((path, size));
+    }
+
+    let groups = by_hash
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(hash, files)| group(hash, files))
+        .collect();
+    (groups, fresh)
+}
+
+// Cheap check without reading contents, for copies spread over slow drives.
+// Names are compared in NFC form, so copies made on macOS still match.
+fn name_duplicates(files: Vec<(PathBuf, u64)>) -> Vec<DuplicateGroup> {
+    let mut by_name: HashMap<(String, u64), Vec<(PathBuf, u64)>> = HashMap::new();
+    for (path, size) in files {
+        let name = match path.file_name() {
+            Some(name) => nfc(&name.to_string_lossy()).to_lowercase(),
+            None => continue,
+        };
+        by_name.entry((name, size)).or_default().push((path, size));
+    }
+
+    by_name
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|((name, size), files)| group(format!("{} ({})", name, size), files))
+        .collect()
+}
+
+fn audio_duplicates(files: Vec<(PathBuf, u64)>) -> Vec<DuplicateGroup> {
+    let tagged: Vec<(String, u64, PathBuf, u64)> = files
+        .into_par_iter()
+        .filter(|(path, _)| {
+            path.extension().is_some_and(|ext| {
+                AUDIO_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+            })
+        })
+        .filter_map(|(path, size)| {
+            let (key, duration) = audio_key(&path)?;
+            Some((key, duration, path, size))
+        })
+        .collect();
+
+    let mut by_key: HashMap<String, Vec<(u64, PathBuf, u64)>> = HashMap::new();
+    for (key, duration, path, size) in tagged {
+        by_key.entry(key).or_default().push((duration, path, size));
+    }
+
+    // Within one artist/title, split into runs of similar duration so a live or
+    // extended version is not mistaken for the album track
+    let mut groups = Vec::new();
+    for (key, mut tracks) in by_key {
+        tracks.sort_by_key(|(duration, _, _)| *duration);
+
+        let mut run: Vec<(PathBuf, u64)> = Vec::new();
+        let mut last_duration = 0;
+        for (duration, path, size) in tracks {
+            if !run.is_empty() && duration - last_duration > AUDIO_DURATION_TOLERANCE_SECS {
+                if run.len() > 1 {
+                    groups.push(group(key.clone(), std::mem::take(&mut run)));
+                }
+                run.clear();
+            }
+            run.push((path, size));
+            last_duration = duration;
+        }
+        if run.len() > 1 {
+            groups.push(group(key, run));
+        }
+    }
+
+    groups
+}
+
+// Normalized "artist - title" and duration in seconds from a track's tags
+fn audio_key(path: &Path) -> Option<(String, u64)> {
+    use lofty::prelude::{Accessor, AudioFile, TaggedFileExt};
+
+    let tagged = lofty::read_from_path(path).ok()?;
+    let tag = tagged.primary_tag().or_else(|| tagged.first_tag())?;
+    let artist = normalize_tag(&tag.artist()?);
+    let title = normalize_tag(&tag.title()?);
+    if artist.is_empty() || title.is_empty() {
+        return None;
+    }
+
+    let duration = tagged.properties().duration().as_secs();
+    Some((format!("{} - {}", artist, title), duration))
+}
+
+// NFC, lowercase, drop bracketed notes like "(Remastered)" and punctuation,
+// collapse spaces
+fn normalize_tag(value: &str) -> String {
+    let mut depth = 0;
+    let stripped: String = nfc(value)
+        .chars()
+        .filter(|c| match c {
+            '(' | '[' => {
+                depth += 1;
+                false
+            }
+            ')' | ']' => {
+                depth = (depth - 1).max(0);
+                false
+            }
+            _ => depth == 0,
+        })
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    stripped
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
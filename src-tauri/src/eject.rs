@@ -0,0 +1,153 @@
+use crate::DiskSenseError;
+use std::path::Path;
+use tauri::command;
+
+#[command]
+pub async fn eject_drive(mount_point: String) -> Result<(), DiskSenseError> {
+    if !Path::new(&mount_point).exists() {
+        return Err(DiskSenseError::NotFound { path: mount_point });
+    }
+
+    eject(&mount_point)
+}
+
+// Lock and dismount the volume, then ask the device to eject it. Locking fails
+// while any process still has a file open on the volume.
+#[cfg(target_os = "windows")]
+fn eject(mount_point: &str) -> Result<(), DiskSenseError> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::ptr::null_mut;
+    use winapi::ctypes::c_void;
+    use winapi::um::fileapi::{CreateFileW, OPEN_EXISTING};
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::ioapiset::DeviceIoControl;
+    use winapi::um::winioctl::{
+        FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME, IOCTL_STORAGE_EJECT_MEDIA,
+        IOCTL_STORAGE_MEDIA_REMOVAL, PREVENT_MEDIA_REMOVAL,
+    };
+    use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE, GENERIC_READ, GENERIC_WRITE};
+
+    let letter = mount_point.trim_end_matches(['\\', '/']);
+    if letter.len() != 2 || !letter.ends_with(':') {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Only drive letters can be ejected: {}",
+            mount_point
+        )));
+    }
+
+    let device: Vec<u16> = std::ffi::OsStr::new(&format!(r"\\.\{}", letter))
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+
+    unsafe {
+        let handle = CreateFileW(
+            device.as_ptr(),
+            GENERIC_READ | GENERIC_WRITE,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            null_mut(),
+            OPEN_EXISTING,
+            0,
+            null_mut(),
+        );
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(DiskSenseError::from_io(
+                std::io::Error::last_os_error(),
+                mount_point,
+            ));
+        }
+
+        let mut returned = 0;
+        let mut control = |code: u32, input: *mut c_void, input_size: u32| {
+            DeviceIoControl(
+                handle,
+                code,
+                input,
+                input_size,
+                null_mut(),
+                0,
+                &mut returned,
+                null_mut(),
+            ) != 0
+        };
+
+        let result = if !control(FSCTL_LOCK_VOLUME, null_mut(), 0) {
+            Err(DiskSenseError::InUse {
+                path: mount_point.to_string(),
+            })
+        } else {
+            let mut removal = PREVENT_MEDIA_REMOVAL {
+                PreventMediaRemoval: 0,
+            };
+            let ejected = control(FSCTL_DISMOUNT_VOLUME, null_mut(), 0)
+                && control(
+                    IOCTL_STORAGE_MEDIA_REMOVAL,
+                    &mut removal as *mut _ as *mut _,
+                    std::mem::size_of::<PREVENT_MEDIA_REMOVAL>() as u32,
+                )
+                && control(IOCTL_STORAGE_EJECT_MEDIA, null_mut(), 0);
+            if ejected {
+                Ok(())
+            } else {
+                Err(DiskSenseError::from_io(
+                    std::io::Error::last_os_error(),
+                    mount_point,
+                ))
+            }
+        };
+
+        CloseHandle(handle);
+        result
+    }
+}
+
+// Unmount and power off through udisks so no root is needed, then fall back to umount
+#[cfg(target_os = "linux")]
+fn eject(mount_point: &str) -> Result<(), DiskSenseError> {
+    let device = sysinfo::Disks::new_with_refreshed_list()
+        .iter()
+        .find(|disk| disk.mount_point() == Path::new(mount_point))
+        .map(|disk| disk.name().to_string_lossy().to_string());
+
+    match device {
+        Some(device) => {
+            run_eject_tool(mount_point, "udisksctl", &["unmount", "-b", &device])
+                .or_else(|_| run_eject_tool(mount_point, "umount", &[mount_point]))?;
+            // Not every device can be powered off (e.g. SD card readers); it is unmounted either way
+            let _ = run_eject_tool(mount_point, "udisksctl", &["power-off", "-b", &device]);
+            Ok(())
+        }
+        None => run_eject_tool(mount_point, "umount", &[mount_point]),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn eject(mount_point: &str) -> Result<(), DiskSenseError> {
+    run_eject_tool(mount_point, "diskutil", &["eject", mount_point])
+}
+
+#[cfg(not(target_os = "windows"))]
+fn run_eject_tool(mount_point: &str, tool: &str, args: &[&str]) -> Result<(), DiskSenseError> {
+    let output = std::process::Command::new(tool)
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, tool))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    // "target is busy" (umount/udisks) and "dissented" (diskutil) mean files are still open
+    let lower = stderr.to_lowercase();
+    if lower.contains("busy") || lower.contains("in use") || lower.contains("dissent") {
+        return Err(DiskSenseError::InUse {
+            path: mount_point.to_string(),
+        });
+    }
+
+    Err(DiskSenseError::Failed(format!(
+        "{} failed: {}",
+        tool, stderr
+    )))
+}
@@ -0,0 +1,271 @@
+use crate::deletion::{DeleteCancel, DeletionReport};
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, delete_and_report, DiskSenseError, ProtectedPaths, ScanStore};
+use fs_extra::dir::get_size;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State};
+use walkdir::WalkDir;
+
+// Folders Chromium keeps in every profile; a data folder holding at least two of
+// them belongs to an Electron app or a Chromium-based browser
+const MARKER_DIRS: [&str; 4] = ["Cache", "Code Cache", "GPUCache", "Service Worker"];
+
+// Caches inside such a data folder. Chromium rebuilds all of them, so removing
+// them loses nothing but warm-up time. Service Worker registrations and
+// databases are kept; only their cached responses and scripts go.
+pub(crate) const CACHE_DIRS: [&str; 9] = [
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "DawnCache",
+    "DawnGraphiteCache",
+    "GrShaderCache",
+    "ShaderCache",
+    "Service Worker/CacheStorage",
+    "Service Worker/ScriptCache",
+];
+
+// How far below an app data root to look; deep enough for browser profiles
+// such as "Google/Chrome/User Data/Default"
+const SEARCH_DEPTH: usize = 4;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ElectronCache {
+    path: String,
+    // Which of CACHE_DIRS it is
+    kind: String,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ElectronApp {
+    // The app's folder under the app data root, e.g. "discord" or "Google"
+    name: String,
+    // The data folder the caches were found in
+    path: String,
+    caches: Vec<ElectronCache>,
+    cache_size: u64,
+    // The app holds its profile lock, so it is running and its caches are left alone
+    in_use: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ElectronCleanReport {
+    report: DeletionReport,
+    // Data folders skipped because their app was running
+    skipped_in_use: Vec<String>,
+}
+
+// Find every Electron app and Chromium browser profile in the user's app data
+// (%APPDATA% and %LOCALAPPDATA%, ~/Library/Application Support, or
+// ~/.config) by its folder layout, with the size of each cache. Largest first.
+#[command]
+pub async fn find_electron_caches() -> Result<Vec<ElectronApp>, DiskSenseError> {
+    let mut apps: Vec<ElectronApp> = data_dirs()
+        .into_par_iter()
+        .map(|(root, dir)| measure(&root, dir))
+        .filter(|app| !app.caches.is_empty())
+        .collect();
+    apps.sort_by_key(|app| std::cmp::Reverse(app.cache_size));
+    Ok(apps)
+}
+
+// Remove the caches of every detected app, or only of the data folders in
+// `paths`. Apps that are running are skipped, and the caches are found again
+// here rather than taken from the caller, so nothing but cache folders can go.
+#[command]
+pub async fn clean_electron_caches(
+    read_only: State<'_, ReadOnlyMode>,
+    app: AppHandle,
+    cancel: State<'_, DeleteCancel>,
+    protected: State<'_, ProtectedPaths>,
+    store: State<'_, ScanStore>,
+    paths: Option<Vec<String>>,
+    action: Option<String>,
+) -> Result<ElectronCleanReport, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let cancelled = cancel.reset();
+    let mut report = DeletionReport::default();
+    let mut skipped_in_use = Vec::new();
+    let mut targets = Vec::new();
+
+    for (_, dir) in data_dirs() {
+        let dir_path = dir.to_string_lossy().to_string();
+        if paths
+            .as_ref()
+            .is_some_and(|paths| !paths.contains(&dir_path))
+        {
+            continue;
+        }
+        if in_use(&dir) {
+            skipped_in_use.push(dir_path);
+            continue;
+        }
+
+        for cache in cache_dirs(&dir) {
+            let cache = cache.to_string_lossy().to_string();
+            match delete_and_report(&app, cancelled, &protected, &store, &cache, false, false) {
+                Ok(deleted) => report.merge(deleted),
+                Err(e) => report.fail(e),
+            }
+            targets.push(cache);
+            if report.is_cancelled() {
+                break;
+            }
+        }
+        if report.is_cancelled() {
+            break;
+        }
+    }
+
+    // A cancelled clean is recorded with what it deleted before the cancel
+    audit::record(
+        &app,
+        "clean_electron_caches",
+        action.as_deref(),
+        targets,
+        report.bytes_reclaimed(),
+        report.cancel_error(),
+    );
+    Ok(ElectronCleanReport {
+        report,
+        skipped_in_use,
+    })
+}
+
+// The existing caches of a Chromium data folder
+pub(crate) fn cache_dirs(dir: &Path) -> Vec<PathBuf> {
+    CACHE_DIRS
+        .iter()
+        .map(|rel| {
+            rel.split('/')
+                .fold(dir.to_path_buf(), |path, part| path.join(part))
+        })
+        .filter(|path| std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir()))
+        .collect()
+}
+
+fn measure(root: &Path, dir: PathBuf) -> ElectronApp {
+    let caches: Vec<ElectronCache> = cache_dirs(&dir)
+        .into_iter()
+        .map(|cache| ElectronCache {
+            kind: cache
+                .strip_prefix(&dir)
+                .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default(),
+            size: get_size(&cache).unwrap_or(0),
+            path: cache.to_string_lossy().to_string(),
+        })
+        .filter(|cache| cache.size > 0)
+        .collect();
+
+    ElectronApp {
+        name: dir
+            .strip_prefix(root)
+            .ok()
+            .and_then(|rel| rel.components().next())
+            .map(|name| name.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default(),
+        cache_size: caches.iter().map(|cache| cache.size).sum(),
+        caches,
+        in_use: in_use(&dir),
+        path: dir.to_string_lossy().to_string(),
+    }
+}
+
+// Chromium data folders under the app data roots, each with its root
+fn data_dirs() -> Vec<(PathBuf, PathBuf)> {
+    app_data_roots()
+        .into_iter()
+        .flat_map(|root| {
+            let mut found = Vec::new();
+            let mut walker = WalkDir::new(&root)
+                .min_depth(1)
+                .max_depth(SEARCH_DEPTH)
+                .into_iter()
+                .filter_entry(|entry| entry.file_type().is_dir());
+            while let Some(entry) = walker.next() {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(_) => continue,
+                };
+                if is_chromium_data_dir(entry.path()) {
+                    found.push((root.clone(), entry.into_path()));
+                    // Its own folders are caches and storage, not more profiles
+                    walker.skip_current_dir();
+                }
+            }
+            found
+        })
+        .collect()
+}
+
+fn is_chromium_data_dir(dir: &Path) -> bool {
+    MARKER_DIRS
+        .iter()
+        .filter(|name| dir.join(name).is_dir())
+        .count()
+        >= 2
+}
+
+// Chromium locks the user data folder while running: a "lockfile" on Windows,
+// deleted when the app closes, and a "SingletonLock" link to "<host>-<pid>"
+// elsewhere. Browsers lock the folder above their profiles, so both are checked.
+fn in_use(dir: &Path) -> bool {
+    [Some(dir), dir.parent()].into_iter().flatten().any(|dir| {
+        #[cfg(target_os = "windows")]
+        {
+            dir.join("lockfile").exists()
+        }
+
+        #[cfg(unix)]
+        {
+            std::fs::read_link(dir.join("SingletonLock"))
+                .ok()
+                .and_then(|target| {
+                    let target = target.to_string_lossy().to_string();
+                    target.rsplit_once('-')?.1.parse::<libc::pid_t>().ok()
+                })
+                // EPERM means the process exists but belongs to another user
+                .is_some_and(|pid| {
+                    let alive = unsafe { libc::kill(pid, 0) } == 0;
+                    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+                })
+        }
+
+        #[cfg(not(any(target_os = "windows", unix)))]
+        {
+            false
+        }
+    })
+}
+
+fn app_data_roots() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        ["APPDATA", "LOCALAPPDATA"]
+            .iter()
+            .filter_map(|var| std::env::var_os(var))
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::home_dir()
+            .map(|home| home.join("Library").join("Application Support"))
+            .into_iter()
+            .collect()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| crate::home_dir().map(|home| home.join(".config")))
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,163 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+// Windows error codes for files locked by another process
+#[cfg(target_os = "windows")]
+const IN_USE_CODES: [i32; 2] = [32, 33]; // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+#[cfg(not(target_os = "windows"))]
+const IN_USE_CODES: [i32; 2] = [16, 26]; // EBUSY, ETXTBSY
+
+// Error returned by every command, serialized as a flat object the front-end
+// can branch on: { kind, message, path, code }
+#[derive(Debug, Clone)]
+pub enum DiskSenseError {
+    NotFound {
+        path: String,
+    },
+    PermissionDenied {
+        path: String,
+    },
+    InUse {
+        path: String,
+    },
+    Protected {
+        path: String,
+    },
+    Cancelled,
+    // Read-only (audit) mode is on
+    ReadOnly,
+    Io {
+        path: Option<String>,
+        code: Option<i32>,
+        message: String,
+    },
+    InvalidInput(String),
+    Unsupported(String),
+    Failed(String),
+}
+
+impl DiskSenseError {
+    // Classify an I/O error that happened while working on `path`
+    pub fn from_io(err: io::Error, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_string_lossy().to_string();
+        let code = err.raw_os_error();
+
+        if code.is_some_and(|code| IN_USE_CODES.contains(&code)) {
+            return DiskSenseError::InUse { path };
+        }
+
+        match err.kind() {
+            io::ErrorKind::NotFound => DiskSenseError::NotFound { path },
+            io::ErrorKind::PermissionDenied => DiskSenseError::PermissionDenied { path },
+            _ => DiskSenseError::Io {
+                path: Some(path),
+                code,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    // Classify a failure to start the external tool `program`
+    pub fn from_spawn(err: io::Error, program: &str) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => DiskSenseError::Unsupported(format!(
+                "{} is not installed or not on the PATH",
+                program
+            )),
+            io::ErrorKind::PermissionDenied => DiskSenseError::PermissionDenied {
+                path: program.to_string(),
+            },
+            _ => DiskSenseError::Io {
+                path: None,
+                code: err.raw_os_error(),
+                message: format!("Failed to run {}: {}", program, err),
+            },
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            DiskSenseError::NotFound { .. } => "NotFound",
+            DiskSenseError::PermissionDenied { .. } => "PermissionDenied",
+            DiskSenseError::InUse { .. } => "InUse",
+            DiskSenseError::Protected { .. } => "Protected",
+            DiskSenseError::Cancelled => "Cancelled",
+            DiskSenseError::ReadOnly => "ReadOnly",
+            DiskSenseError::Io { .. } => "Io",
+            DiskSenseError::InvalidInput(_) => "InvalidInput",
+            DiskSenseError::Unsupported(_) => "Unsupported",
+            DiskSenseError::Failed(_) => "Failed",
+        }
+    }
+
+    pub(crate) fn path(&self) -> Option<&str> {
+        match self {
+            DiskSenseError::NotFound { path }
+            | DiskSenseError::PermissionDenied { path }
+            | DiskSenseError::InUse { path }
+            | DiskSenseError::Protected { path } => Some(path),
+            DiskSenseError::Io { path, .. } => path.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn code(&self) -> Option<i32> {
+        match self {
+            DiskSenseError::Io { code, .. } => *code,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DiskSenseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiskSenseError::NotFound { path } => write!(f, "Path does not exist: {}", path),
+            DiskSenseError::PermissionDenied { path } => write!(f, "Access denied: {}", path),
+            DiskSenseError::InUse { path } => {
+                write!(f, "File is in use by another process: {}", path)
+            }
+            DiskSenseError::Protected { path } => {
+                write!(f, "Path is protected and cannot be removed: {}", path)
+            }
+            DiskSenseError::Cancelled => write!(f, "Operation was cancelled"),
+            DiskSenseError::ReadOnly => {
+                write!(f, "Read-only mode is on; nothing can be changed")
+            }
+            DiskSenseError::Io { message, .. } => write!(f, "{}", message),
+            DiskSenseError::InvalidInput(message)
+            | DiskSenseError::Unsupported(message)
+            | DiskSenseError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DiskSenseError {}
+
+// Classified like from_io, for errors whose path isn't known where they surface
+impl From<io::Error> for DiskSenseError {
+    fn from(err: io::Error) -> Self {
+        match DiskSenseError::from_io(err, "") {
+            DiskSenseError::Io { code, message, .. } => DiskSenseError::Io {
+                path: None,
+                code,
+                message,
+            },
+            classified => classified,
+        }
+    }
+}
+
+impl Serialize for DiskSenseError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DiskSenseError", 4)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("path", &self.path())?;
+        state.serialize_field("code", &self.code())?;
+        state.end()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{hashing, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SelectionEntry {
+    path: String,
+    size: u64,
+    is_dir: bool,
+    modified: Option<u64>,
+    sha256: Option<String>,
+}
+
+// Write the selected items to `file` as a plain list of paths ("text") or a JSON
+// manifest ("json") so they can be reviewed or processed before deleting them
+#[command]
+pub async fn export_selection(
+    paths: Vec<String>,
+    format: String,
+    file: String,
+    include_hash: Option<bool>,
+) -> Result<usize, DiskSenseError> {
+    let contents = match format.as_str() {
+        "text" => {
+            let mut list = paths.join("\n");
+            list.push('\n');
+            list
+        }
+        "json" => {
+            let include_hash = include_hash.unwrap_or(false);
+            let entries = paths
+                .iter()
+                .map(|path| selection_entry(path, include_hash))
+                .collect::<Result<Vec<_>, _>>()?;
+            serde_json::to_string_pretty(&entries)
+                .map_err(|e| DiskSenseError::Failed(format!("Failed to build manifest: {}", e)))?
+        }
+        other => {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "Unsupported export format: {}",
+                other
+            )))
+        }
+    };
+
+    std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))?;
+    Ok(paths.len())
+}
+
+fn selection_entry(path: &str, include_hash: bool) -> Result<SelectionEntry, DiskSenseError> {
+    let item = Path::new(path);
+    let metadata = std::fs::symlink_metadata(item).map_err(|e| DiskSenseError::from_io(e, path))?;
+
+    let size = if metadata.is_dir() {
+        get_size(item).unwrap_or(0)
+    } else {
+        metadata.len()
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    // Folders have no single content hash
+    let sha256 = if include_hash && metadata.is_file() {
+        Some(hashing::hash_file(item).map_err(|e| DiskSenseError::from_io(e, path))?)
+    } else {
+        None
+    };
+
+    Ok(SelectionEntry {
+        path: path.to_string(),
+        size,
+        is_dir: metadata.is_dir(),
+        modified,
+        sha256,
+    })
+}
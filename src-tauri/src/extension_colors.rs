@@ -0,0 +1,116 @@
+use crate::delta::node_id;
+use crate::report::file_category;
+use crate::{DiskItem, DiskSenseError, ScanStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionColor {
+    extension: String,
+    category: String,
+    color: String,
+    size: u64,
+    count: usize,
+}
+
+// Base hue of each file category, so related types share a color family
+fn category_hue(category: &str) -> f64 {
+    match category {
+        "Images" => 140.0,
+        "Video" => 0.0,
+        "Audio" => 280.0,
+        "Documents" => 210.0,
+        "Archives" => 35.0,
+        "Executables" => 320.0,
+        "Code" => 175.0,
+        _ => 60.0,
+    }
+}
+
+#[command]
+pub async fn get_extension_colors(
+    store: State<'_, ScanStore>,
+    scan_id: String,
+) -> Result<Vec<ExtensionColor>, DiskSenseError> {
+    let root = store.get(&scan_id)?;
+
+    let mut totals: HashMap<String, (u64, usize)> = HashMap::new();
+    collect_extensions(&root, &mut totals);
+
+    let mut colors: Vec<ExtensionColor> = totals
+        .into_iter()
+        .map(|(extension, (size, count))| {
+            let category = file_category(&format!(".{}", extension));
+            ExtensionColor {
+                color: extension_color(&extension, category),
+                category: category.to_string(),
+                extension,
+                size,
+                count,
+            }
+        })
+        .collect();
+
+    colors.sort_by_key(|item| std::cmp::Reverse(item.size));
+    Ok(colors)
+}
+
+fn collect_extensions(item: &DiskItem, totals: &mut HashMap<String, (u64, usize)>) {
+    // The hidden entries' node sums files of any extension
+    if item.hidden {
+        return;
+    }
+    // An archive counts as the file on disk, not as its listed contents
+    match item.children.as_ref() {
+        Some(children) if item.is_dir => {
+            for child in children {
+                collect_extensions(child, totals);
+            }
+        }
+        _ if !item.is_dir => {
+            let entry = totals.entry(file_extension(&item.name)).or_insert((0, 0));
+            entry.0 += item.size;
+            entry.1 += 1;
+        }
+        _ => {}
+    }
+}
+
+// Lowercased extension, or "" for files without one (dotfiles included)
+pub(crate) fn file_extension(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => ext.to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+// The color depends only on the extension, so it is the same in every scan and session
+fn extension_color(extension: &str, category: &str) -> String {
+    let hash = u64::from_str_radix(&node_id(extension), 16).unwrap_or(0);
+
+    // Spread extensions within their category's hue band and vary the shade
+    let hue = (category_hue(category) + (hash % 40) as f64 - 20.0).rem_euclid(360.0);
+    let saturation = 55.0 + ((hash >> 8) % 30) as f64;
+    let lightness = 40.0 + ((hash >> 16) % 25) as f64;
+
+    hsl_to_hex(hue, saturation / 100.0, lightness / 100.0)
+}
+
+fn hsl_to_hex(hue: f64, saturation: f64, lightness: f64) -> String {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match (hue / 60.0) as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let channel = |value: f64| ((value + m) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(r), channel(g), channel(b))
+}
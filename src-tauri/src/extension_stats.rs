@@ -0,0 +1,97 @@
+use crate::extension_colors::file_extension;
+use crate::name_sort::NameCollation;
+use crate::{DiskItem, DiskSenseError, ScanStore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{command, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionStats {
+    extension: String,
+    total_size: u64,
+    file_count: usize,
+    largest_file: String,
+    largest_size: u64,
+    average_size: u64,
+}
+
+#[command]
+pub async fn get_extension_stats(
+    store: State<'_, ScanStore>,
+    root: String,
+    sort_by: Option<String>,
+    ascending: Option<bool>,
+    collation: Option<String>,
+) -> Result<Vec<ExtensionStats>, DiskSenseError> {
+    let tree = store.get(&root)?;
+    let collation = NameCollation::parse(collation.as_deref())?;
+
+    let mut stats: HashMap<String, ExtensionStats> = HashMap::new();
+    collect_stats(&tree, &mut stats);
+
+    let mut stats: Vec<ExtensionStats> = stats
+        .into_values()
+        .map(|mut entry| {
+            entry.average_size = entry.total_size / entry.file_count as u64;
+            entry
+        })
+        .collect();
+
+    match sort_by.as_deref().unwrap_or("total_size") {
+        "total_size" => stats.sort_by_key(|item| std::cmp::Reverse(item.total_size)),
+        "file_count" => stats.sort_by_key(|item| std::cmp::Reverse(item.file_count)),
+        "largest_size" => stats.sort_by_key(|item| std::cmp::Reverse(item.largest_size)),
+        "average_size" => stats.sort_by_key(|item| std::cmp::Reverse(item.average_size)),
+        // Names read naturally A-Z, so this one starts ascending
+        "extension" => stats.sort_by(|a, b| collation.compare(&b.extension, &a.extension)),
+        other => {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "Unsupported sort column: {}",
+                other
+            )))
+        }
+    }
+
+    // Columns sort largest first unless the caller asks otherwise
+    if ascending.unwrap_or(sort_by.as_deref() == Some("extension")) {
+        stats.reverse();
+    }
+
+    Ok(stats)
+}
+
+fn collect_stats(item: &DiskItem, stats: &mut HashMap<String, ExtensionStats>) {
+    // The hidden entries' node sums files of any extension
+    if item.hidden {
+        return;
+    }
+    // An archive counts as the file on disk, not as its listed contents
+    match item.children.as_ref() {
+        Some(children) if item.is_dir => {
+            for child in children {
+                collect_stats(child, stats);
+            }
+        }
+        _ if !item.is_dir => {
+            let extension = file_extension(&item.name);
+            let entry = stats
+                .entry(extension.clone())
+                .or_insert_with(|| ExtensionStats {
+                    extension,
+                    total_size: 0,
+                    file_count: 0,
+                    largest_file: String::new(),
+                    largest_size: 0,
+                    average_size: 0,
+                });
+
+            entry.total_size += item.size;
+            entry.file_count += 1;
+            if entry.file_count == 1 || item.size > entry.largest_size {
+                entry.largest_file = item.path.clone();
+                entry.largest_size = item.size;
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,177 @@
+use crate::DiskSenseError;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, Manager, State};
+
+// Explorer menu entries for folders, folder backgrounds and drives
+#[cfg(target_os = "windows")]
+const SHELL_KEYS: [(&str, &str); 3] = [
+    (r"HKCU\Software\Classes\Directory\shell\DiskSense", "%1"),
+    (
+        r"HKCU\Software\Classes\Directory\Background\shell\DiskSense",
+        "%V",
+    ),
+    (r"HKCU\Software\Classes\Drive\shell\DiskSense", "%1"),
+];
+
+// Desktop entry that lists DiskSense under "Open With" for folders
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = ".local/share/applications/disksense-analyze.desktop";
+
+// A folder the OS or the command line asked us to analyze
+#[derive(Debug, Serialize, Clone)]
+pub struct ExternalScan {
+    pub(crate) path: String,
+    pub(crate) depth: Option<usize>,
+}
+
+// The latest request, held until the front-end picks it up
+#[derive(Default)]
+pub struct ExternalOpen {
+    pending: Mutex<Option<ExternalScan>>,
+}
+
+// Bring the main window forward and have it scan the folder. The request is
+// also kept for take_external_open, in case the front-end hasn't loaded yet to
+// hear the "external-open" event.
+pub(crate) fn handle_external_open(app: &AppHandle, scan: ExternalScan) {
+    if let Some(state) = app.try_state::<ExternalOpen>() {
+        *state.pending.lock().unwrap_or_else(|e| e.into_inner()) = Some(scan.clone());
+    }
+    focus_main_window(app);
+    let _ = app.emit_to("main", "external-open", scan);
+}
+
+pub(crate) fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+// The folder the app was last asked to analyze from outside, if not yet taken
+#[command]
+pub async fn take_external_open(
+    state: State<'_, ExternalOpen>,
+) -> Result<Option<ExternalScan>, DiskSenseError> {
+    Ok(state
+        .pending
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take())
+}
+
+// Add "Analyze with DiskSense" to the folder menu of Explorer, or to "Open With"
+// on Linux desktops. Only the current user is affected. On macOS the app bundle
+// already lists DiskSense under "Open With" for folders.
+#[command]
+pub async fn register_shell_integration() -> Result<(), DiskSenseError> {
+    let exe = std::env::current_exe()
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to locate DiskSense: {}", e)))?;
+    register(&exe)
+}
+
+#[command]
+pub async fn unregister_shell_integration() -> Result<(), DiskSenseError> {
+    unregister()
+}
+
+#[cfg(target_os = "windows")]
+fn register(exe: &Path) -> Result<(), DiskSenseError> {
+    let exe = exe.to_string_lossy();
+    for (key, argument) in SHELL_KEYS {
+        run_reg(&["add", key, "/ve", "/d", "Analyze with DiskSense", "/f"])?;
+        run_reg(&["add", key, "/v", "Icon", "/d", &exe, "/f"])?;
+        run_reg(&[
+            "add",
+            &format!("{}\\command", key),
+            "/ve",
+            "/d",
+            &format!("\"{}\" \"{}\"", exe, argument),
+            "/f",
+        ])?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn unregister() -> Result<(), DiskSenseError> {
+    for (key, _) in SHELL_KEYS {
+        // A key that was never added is already gone
+        let _ = run_reg(&["delete", key, "/f"]);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn run_reg(args: &[&str]) -> Result<(), DiskSenseError> {
+    let output = std::process::Command::new("reg")
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "reg"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "Failed to update the Explorer menu: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn register(exe: &Path) -> Result<(), DiskSenseError> {
+    let file = desktop_file()?;
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+    }
+    let contents = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Analyze with DiskSense\n\
+         Exec=\"{}\" %f\n\
+         MimeType=inode/directory;\n\
+         NoDisplay=true\n",
+        exe.display()
+    );
+    std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))?;
+
+    // Menus pick up new entries once the desktop database is rebuilt; not every
+    // desktop ships the tool, and most rescan on their own eventually
+    if let Some(dir) = file.parent() {
+        let _ = std::process::Command::new("update-desktop-database")
+            .arg(dir)
+            .output();
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn unregister() -> Result<(), DiskSenseError> {
+    let file = desktop_file()?;
+    match std::fs::remove_file(&file) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(DiskSenseError::from_io(e, &file))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_file() -> Result<std::path::PathBuf, DiskSenseError> {
+    crate::home_dir()
+        .map(|home| home.join(DESKTOP_FILE))
+        .ok_or_else(|| DiskSenseError::Failed("Home directory is unavailable".to_string()))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn register(_exe: &Path) -> Result<(), DiskSenseError> {
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn unregister() -> Result<(), DiskSenseError> {
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use tauri::command;
+
+// Files examined by default; fragment counts only matter for big files
+const DEFAULT_FILE_LIMIT: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FragmentedFile {
+    path: String,
+    size: u64,
+    fragments: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FragmentationReport {
+    // Fragmentation only slows drives with seek penalties (HDDs); None if unknown
+    rotational: Option<bool>,
+    files: Vec<FragmentedFile>,
+}
+
+// Fragment counts for the largest files under `path`, most fragmented first
+#[command]
+pub async fn analyze_fragmentation(
+    path: String,
+    limit: Option<usize>,
+) -> Result<FragmentationReport, DiskSenseError> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::analyze(&path, limit.unwrap_or(DEFAULT_FILE_LIMIT))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, limit, DEFAULT_FILE_LIMIT);
+        Err(DiskSenseError::Unsupported(
+            "Fragmentation analysis is only available on Windows".to_string(),
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::{FragmentationReport, FragmentedFile};
+    use crate::DiskSenseError;
+    use std::mem::size_of;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::AsRawHandle;
+    use std::path::{Component, Path};
+    use std::ptr::null_mut;
+    use walkdir::WalkDir;
+    use winapi::shared::winerror::ERROR_MORE_DATA;
+    use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::fileapi::{CreateFileW, OPEN_EXISTING};
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::ioapiset::DeviceIoControl;
+    use winapi::um::winioctl::{
+        PropertyStandardQuery, StorageDeviceSeekPenaltyProperty, DEVICE_SEEK_PENALTY_DESCRIPTOR,
+        FSCTL_GET_RETRIEVAL_POINTERS, IOCTL_STORAGE_QUERY_PROPERTY, RETRIEVAL_POINTERS_BUFFER,
+        STARTING_VCN_INPUT_BUFFER, STORAGE_PROPERTY_QUERY,
+    };
+    use winapi::um::winnt::{FILE_SHARE_READ, FILE_SHARE_WRITE};
+
+    // Extents fetched per FSCTL_GET_RETRIEVAL_POINTERS call
+    const EXTENTS_PER_CALL: usize = 512;
+
+    pub(super) fn analyze(path: &str, limit: usize) -> Result<FragmentationReport, DiskSenseError> {
+        let root = Path::new(path);
+        if !root.exists() {
+            return Err(DiskSenseError::NotFound {
+                path: path.to_string(),
+            });
+        }
+
+        let mut files: Vec<(std::path::PathBuf, u64)> = WalkDir::new(root)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| Some((entry.path().to_path_buf(), entry.metadata().ok()?.len())))
+            .collect();
+        files.sort_by_key(|item| std::cmp::Reverse(item.1));
+        files.truncate(limit);
+
+        let mut report: Vec<FragmentedFile> = files
+            .into_iter()
+            .filter_map(|(path, size)| {
+                let fragments = count_fragments(&path)?;
+                Some(FragmentedFile {
+                    path: path.to_string_lossy().to_string(),
+                    size,
+                    fragments,
+                })
+            })
+            .collect();
+        report.sort_by_key(|item| std::cmp::Reverse(item.fragments));
+
+        Ok(FragmentationReport {
+            rotational: seek_penalty(root),
+            files: report,
+        })
+    }
+
+    // Number of extents (runs of contiguous clusters) making up a file
+    fn count_fragments(path: &Path) -> Option<u64> {
+        let file = std::fs::File::open(path).ok()?;
+        let handle = file.as_raw_handle() as _;
+
+        // RETRIEVAL_POINTERS_BUFFER is a header followed by (NextVcn, Lcn) pairs
+        let extent_size = 2 * size_of::<i64>();
+        let mut buffer =
+            vec![0u8; size_of::<RETRIEVAL_POINTERS_BUFFER>() + EXTENTS_PER_CALL * extent_size];
+        let mut input: STARTING_VCN_INPUT_BUFFER = unsafe { std::mem::zeroed() };
+        let mut fragments = 0u64;
+
+        loop {
+            let mut returned = 0;
+            let ok = unsafe {
+                DeviceIoControl(
+                    handle,
+                    FSCTL_GET_RETRIEVAL_POINTERS,
+                    &mut input as *mut _ as *mut _,
+                    size_of::<STARTING_VCN_INPUT_BUFFER>() as u32,
+                    buffer.as_mut_ptr() as *mut _,
+                    buffer.len() as u32,
+                    &mut returned,
+                    null_mut(),
+                )
+            };
+            let more = ok == 0 && unsafe { GetLastError() } == ERROR_MORE_DATA;
+            if ok == 0 && !more {
+                // Small files stored inside the MFT have no extents at all
+                return Some(fragments);
+            }
+
+            let pointers = unsafe { &*(buffer.as_ptr() as *const RETRIEVAL_POINTERS_BUFFER) };
+            let count = pointers.ExtentCount as usize;
+            fragments += count as u64;
+            if !more || count == 0 {
+                return Some(fragments);
+            }
+
+            // Continue after the last extent returned
+            let last = unsafe { *pointers.Extents.as_ptr().add(count - 1) };
+            input.StartingVcn = last.NextVcn;
+        }
+    }
+
+    // Whether the volume holding `path` incurs a seek penalty (i.e. is rotational)
+    fn seek_penalty(path: &Path) -> Option<bool> {
+        let letter = match dunce::canonicalize(path).ok()?.components().next()? {
+            Component::Prefix(prefix) => prefix.as_os_str().to_string_lossy().to_string(),
+            _ => return None,
+        };
+        let device: Vec<u16> = std::ffi::OsStr::new(&format!(r"\\.\{}", letter))
+            .encode_wide()
+            .chain(Some(0))
+            .collect();
+
+        unsafe {
+            let handle = CreateFileW(
+                device.as_ptr(),
+                0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                null_mut(),
+                OPEN_EXISTING,
+                0,
+                null_mut(),
+            );
+            if handle == INVALID_HANDLE_VALUE {
+                return None;
+            }
+
+            let mut query: STORAGE_PROPERTY_QUERY = std::mem::zeroed();
+            query.PropertyId = StorageDeviceSeekPenaltyProperty;
+            query.QueryType = PropertyStandardQuery;
+            let mut descriptor: DEVICE_SEEK_PENALTY_DESCRIPTOR = std::mem::zeroed();
+            let mut returned = 0;
+
+            let ok = DeviceIoControl(
+                handle,
+                IOCTL_STORAGE_QUERY_PROPERTY,
+                &mut query as *mut _ as *mut _,
+                size_of::<STORAGE_PROPERTY_QUERY>() as u32,
+                &mut descriptor as *mut _ as *mut _,
+                size_of::<DEVICE_SEEK_PENALTY_DESCRIPTOR>() as u32,
+                &mut returned,
+                null_mut(),
+            );
+            CloseHandle(handle);
+
+            (ok != 0).then(|| descriptor.IncursSeekPenalty != 0)
+        }
+    }
+}
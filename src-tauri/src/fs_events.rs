@@ -0,0 +1,225 @@
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
+use std::time::Duration;
+
+// Longest the FSEvents history may take to replay before the caller rescans instead
+#[cfg(target_os = "macos")]
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Newest FSEvents event id; saved with a snapshot so its changes can be replayed
+#[cfg(target_os = "macos")]
+pub(crate) fn current_event_id() -> Option<u64> {
+    Some(unsafe { ffi::FSEventsGetCurrentEventId() })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn current_event_id() -> Option<u64> {
+    None
+}
+
+// Directories under `root` whose entries changed after event `since`. FSEvents
+// reports a directory, not the file in it, so each one only needs relisting.
+// None when the history can't be trusted (events were dropped or coalesced into
+// "rescan everything", ids wrapped, or the replay took too long).
+#[cfg(target_os = "macos")]
+pub(crate) fn changed_since(root: &Path, since: u64) -> Option<Vec<PathBuf>> {
+    use ffi::*;
+    use std::ffi::{c_void, CStr};
+    use std::os::raw::c_char;
+    use std::os::unix::ffi::OsStrExt;
+    use std::time::Instant;
+
+    #[derive(Default)]
+    struct Replay {
+        dirty: Vec<PathBuf>,
+        done: bool,
+        lost: bool,
+    }
+
+    extern "C" fn on_events(
+        _stream: FSEventStreamRef,
+        info: *mut c_void,
+        count: usize,
+        paths: *mut c_void,
+        flags: *const u32,
+        _ids: *const u64,
+    ) {
+        let replay = unsafe { &mut *(info as *mut Replay) };
+        let paths = paths as *const *const c_char;
+        for i in 0..count {
+            let flag = unsafe { *flags.add(i) };
+            if flag & HISTORY_DONE != 0 {
+                replay.done = true;
+                unsafe { CFRunLoopStop(CFRunLoopGetCurrent()) };
+                continue;
+            }
+            if flag & UNTRUSTED != 0 {
+                replay.lost = true;
+                continue;
+            }
+            let path = unsafe { CStr::from_ptr(*paths.add(i)) }.to_string_lossy();
+            replay.dirty.push(PathBuf::from(path.trim_end_matches('/')));
+        }
+    }
+
+    let root_bytes = root.as_os_str().as_bytes();
+    let mut replay = Replay::default();
+    let context = FSEventStreamContext {
+        version: 0,
+        info: &mut replay as *mut Replay as *mut c_void,
+        retain: None,
+        release: None,
+        copy_description: None,
+    };
+
+    unsafe {
+        let root_string = CFStringCreateWithBytes(
+            std::ptr::null(),
+            root_bytes.as_ptr(),
+            root_bytes.len() as isize,
+            CF_STRING_ENCODING_UTF8,
+            0,
+        );
+        if root_string.is_null() {
+            return None;
+        }
+        let roots = CFArrayCreate(
+            std::ptr::null(),
+            &root_string,
+            1,
+            &kCFTypeArrayCallBacks as *const CFArrayCallBacks as *const c_void,
+        );
+        CFRelease(root_string);
+        if roots.is_null() {
+            return None;
+        }
+
+        let stream = FSEventStreamCreate(
+            std::ptr::null(),
+            on_events,
+            &context,
+            roots,
+            since,
+            0.0,
+            CREATE_FLAG_NONE,
+        );
+        CFRelease(roots);
+        if stream.is_null() {
+            return None;
+        }
+
+        // The callback runs on this thread while the run loop spins
+        FSEventStreamScheduleWithRunLoop(stream, CFRunLoopGetCurrent(), kCFRunLoopDefaultMode);
+        if FSEventStreamStart(stream) != 0 {
+            let deadline = Instant::now() + REPLAY_TIMEOUT;
+            while !replay.done && !replay.lost {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    break;
+                }
+                CFRunLoopRunInMode(kCFRunLoopDefaultMode, left.as_secs_f64(), 0);
+            }
+            FSEventStreamStop(stream);
+        }
+        FSEventStreamInvalidate(stream);
+        FSEventStreamRelease(stream);
+    }
+
+    if !replay.done || replay.lost {
+        return None;
+    }
+    replay.dirty.sort();
+    replay.dirty.dedup();
+    Some(replay.dirty)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn changed_since(_root: &Path, _since: u64) -> Option<Vec<PathBuf>> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+#[allow(non_upper_case_globals, non_snake_case)]
+mod ffi {
+    use std::ffi::c_void;
+
+    pub type FSEventStreamRef = *mut c_void;
+    pub type CFTypeRef = *const c_void;
+    pub type CFRunLoopRef = *mut c_void;
+
+    pub type FSEventStreamCallback = extern "C" fn(
+        stream: FSEventStreamRef,
+        info: *mut c_void,
+        count: usize,
+        paths: *mut c_void,
+        flags: *const u32,
+        ids: *const u64,
+    );
+
+    #[repr(C)]
+    pub struct FSEventStreamContext {
+        pub version: isize,
+        pub info: *mut c_void,
+        pub retain: Option<extern "C" fn(*const c_void) -> *const c_void>,
+        pub release: Option<extern "C" fn(*const c_void)>,
+        pub copy_description: Option<extern "C" fn(*const c_void) -> CFTypeRef>,
+    }
+
+    // Only ever passed by address
+    #[repr(C)]
+    pub struct CFArrayCallBacks {
+        _fields: [usize; 5],
+    }
+
+    pub const CREATE_FLAG_NONE: u32 = 0;
+    pub const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    pub const HISTORY_DONE: u32 = 0x10;
+    // MustScanSubDirs, UserDropped, KernelDropped, EventIdsWrapped, RootChanged
+    pub const UNTRUSTED: u32 = 0x01 | 0x02 | 0x04 | 0x08 | 0x20;
+
+    #[link(name = "CoreServices", kind = "framework")]
+    extern "C" {
+        pub fn FSEventsGetCurrentEventId() -> u64;
+        pub fn FSEventStreamCreate(
+            allocator: CFTypeRef,
+            callback: FSEventStreamCallback,
+            context: *const FSEventStreamContext,
+            paths: CFTypeRef,
+            since_when: u64,
+            latency: f64,
+            flags: u32,
+        ) -> FSEventStreamRef;
+        pub fn FSEventStreamScheduleWithRunLoop(
+            stream: FSEventStreamRef,
+            run_loop: CFRunLoopRef,
+            mode: CFTypeRef,
+        );
+        pub fn FSEventStreamStart(stream: FSEventStreamRef) -> u8;
+        pub fn FSEventStreamStop(stream: FSEventStreamRef);
+        pub fn FSEventStreamInvalidate(stream: FSEventStreamRef);
+        pub fn FSEventStreamRelease(stream: FSEventStreamRef);
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        pub static kCFRunLoopDefaultMode: CFTypeRef;
+        pub static kCFTypeArrayCallBacks: CFArrayCallBacks;
+        pub fn CFRunLoopGetCurrent() -> CFRunLoopRef;
+        pub fn CFRunLoopRunInMode(mode: CFTypeRef, seconds: f64, return_after_source: u8) -> i32;
+        pub fn CFRunLoopStop(run_loop: CFRunLoopRef);
+        pub fn CFStringCreateWithBytes(
+            allocator: CFTypeRef,
+            bytes: *const u8,
+            length: isize,
+            encoding: u32,
+            external: u8,
+        ) -> CFTypeRef;
+        pub fn CFArrayCreate(
+            allocator: CFTypeRef,
+            values: *const CFTypeRef,
+            count: isize,
+            callbacks: *const c_void,
+        ) -> CFTypeRef;
+        pub fn CFRelease(object: CFTypeRef);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{DiskItem, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameInfo {
+    name: String,
+    launcher: String,
+    install_path: String,
+    size: u64,
+}
+
+// A game found in a launcher's records, before its folder is measured
+struct GameInstall {
+    name: String,
+    launcher: &'static str,
+    install_path: PathBuf,
+    // Size the launcher recorded, if any
+    size: Option<u64>,
+}
+
+#[command]
+pub async fn get_game_sizes() -> Result<Vec<GameInfo>, DiskSenseError> {
+    let mut games: Vec<GameInfo> = game_installs()
+        .into_iter()
+        .map(|game| GameInfo {
+            size: game
+                .size
+                .unwrap_or_else(|| get_size(&game.install_path).unwrap_or(0)),
+            name: game.name,
+            launcher: game.launcher.to_string(),
+            install_path: game.install_path.to_string_lossy().to_string(),
+        })
+        .collect();
+
+    // Largest installs first, like the scan tree
+    games.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    Ok(games)
+}
+
+// Label every directory in the tree that is a known game install folder. Runs
+// on every scan, so only the launchers' records are read; no install is measured.
+pub fn mark_games(root: &mut DiskItem) {
+    let mut installs = HashMap::new();
+    for game in game_installs() {
+        installs.insert(
+            normalize_path(&game.install_path.to_string_lossy()),
+            game.name,
+        );
+    }
+
+    if !installs.is_empty() {
+        mark_games_recursive(root, &installs);
+    }
+}
+
+fn mark_games_recursive(item: &mut DiskItem, installs: &HashMap<String, String>) {
+    if !item.is_dir {
+        return;
+    }
+
+    if let Some(name) = installs.get(&normalize_path(&item.path)) {
+        item.game = Some(name.clone());
+    }
+
+    if let Some(children) = item.children.as_mut() {
+        for child in children {
+            mark_games_recursive(child, installs);
+        }
+    }
+}
+
+fn game_installs() -> Vec<GameInstall> {
+    let mut games = steam_games();
+    games.extend(epic_games());
+    games.extend(gog_games());
+    games
+}
+
+// Compare install paths case-insensitively on Windows, without trailing separators
+fn normalize_path(path: &str) -> String {
+    let path = path.trim_end_matches(['/', '\\']);
+    if cfg!(target_os = "windows") {
+        path.replace('/', "\\").to_lowercase()
+    } else {
+        path.to_string()
+    }
+}
+
+// Candidate Steam install roots for each platform
+fn steam_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        roots.push(PathBuf::from(r"C:\Program Files (x86)\Steam"));
+        roots.push(PathBuf::from(r"C:\Program Files\Steam"));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        #[cfg(target_os = "macos")]
+        roots.push(home.join("Library/Application Support/Steam"));
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            roots.push(home.join(".local/share/Steam"));
+            roots.push(home.join(".steam/steam"));
+            roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        }
+    }
+
+    roots
+}
+
+fn steam_games() -> Vec<GameInstall> {
+    let mut libraries: Vec<PathBuf> = Vec::new();
+
+    for root in steam_roots() {
+        let manifest = root.join("steamapps").join("libraryfolders.vdf");
+        let contents = match std::fs::read_to_string(&manifest) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        libraries.push(root.clone());
+        for (key, value) in vdf_pairs(&contents) {
+            if key == "path" {
+                libraries.push(PathBuf::from(value));
+            }
+        }
+    }
+
+    // The same library is often reachable from several roots (e.g. ~/.steam/steam symlink)
+    let mut seen = std::collections::HashSet::new();
+    libraries.retain(|lib| seen.insert(dunce::canonicalize(lib).unwrap_or_else(|_| lib.clone())));
+
+    let mut games = Vec::new();
+    for library in libraries {
+        let steamapps = library.join("steamapps");
+        let entries = match std::fs::read_dir(&steamapps) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.starts_with("appmanifest_") || !file_name.ends_with(".acf") {
+                continue;
+            }
+
+            let contents = match std::fs::read_to_string(entry.path()) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+
+            let fields: HashMap<String, String> = vdf_pairs(&contents).into_iter().collect();
+            let (name, install_dir) = match (fields.get("name"), fields.get("installdir")) {
+                (Some(name), Some(install_dir)) => (name.clone(), install_dir.clone()),
+                _ => continue,
+            };
+
+            games.push(GameInstall {
+                name,
+                launcher: "steam",
+                install_path: steamapps.join("common").join(&install_dir),
+                size: fields
+                    .get("SizeOnDisk")
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .filter(|size| *size > 0),
+            });
+        }
+    }
+
+    games
+}
+
+// Extract `"key" "value"` pairs from Valve's KeyValues (.vdf/.acf) format
+fn vdf_pairs(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split('"').collect();
+            // A pair line looks like: <ws>"key"<ws>"value"
+            if tokens.len() < 5 {
+                return None;
+            }
+            Some((tokens[1].to_string(), tokens[3].replace(r"\\", r"\")))
+        })
+        .collect()
+}
+
+fn epic_games() -> Vec<GameInstall> {
+    let manifests = if cfg!(target_os = "windows") {
+        PathBuf::from(r"C:\ProgramData\Epic\EpicGamesLauncher\Data\Manifests")
+    } else if cfg!(target_os = "macos") {
+        match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home)
+                .join("Library/Application Support/Epic/EpicGamesLauncher/Data/Manifests"),
+            None => return Vec::new(),
+        }
+    } else {
+        // Epic has no native Linux launcher
+        return Vec::new();
+    };
+
+    let entries = match std::fs::read_dir(&manifests) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "item"))
+        .filter_map(|entry| {
+            let contents = std::fs::read_to_string(entry.path()).ok()?;
+            let manifest: serde_json::Value = serde_json::from_str(&contents).ok()?;
+
+            let name = manifest.get("DisplayName")?.as_str()?.to_string();
+            let install_path = manifest.get("InstallLocation")?.as_str()?;
+
+            Some(GameInstall {
+                name,
+                launcher: "epic",
+                install_path: PathBuf::from(install_path),
+                size: manifest.get("InstallSize").and_then(|s| s.as_u64()),
+            })
+        })
+        .collect()
+}
+
+fn gog_games() -> Vec<GameInstall> {
+    let mut roots = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        roots.push(PathBuf::from(r"C:\Program Files (x86)\GOG Galaxy\Games"));
+        roots.push(PathBuf::from(r"C:\GOG Games"));
+    }
+
+    #[cfg(target_os = "macos")]
+    roots.push(PathBuf::from("/Applications"));
+
+    #[cfg(target_os = "linux")]
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        roots.push(home.join("GOG Games"));
+    }
+
+    let mut games = Vec::new();
+    for root in roots {
+        let entries = match std::fs::read_dir(&root) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            // Every GOG install carries a goggame-<id>.info JSON file with its title
+            if let Some(name) = gog_game_name(&path) {
+                // GOG records no size; it is measured when sizes are asked for
+                games.push(GameInstall {
+                    name,
+                    launcher: "gog",
+                    install_path: path,
+                    size: None,
+                });
+            }
+        }
+    }
+
+    games
+}
+
+fn gog_game_name(dir: &Path) -> Option<String> {
+    let info = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("goggame-") && name.ends_with(".info")
+        })?;
+
+    let contents = std::fs::read_to_string(info.path()).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    Some(manifest.get("name")?.as_str()?.to_string())
+}
@@ -0,0 +1,198 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tauri::{command, AppHandle, Emitter, State};
+
+// Pack files above this size are worth a `git gc` to repack
+const LARGE_PACK_THRESHOLD: u64 = 512 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackFileInfo {
+    path: String,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitRepoInfo {
+    path: String,
+    total_size: u64,
+    git_dir_size: u64,
+    working_tree_size: u64,
+    large_packs: Vec<PackFileInfo>,
+    gc_suggested: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitGcProgress {
+    path: String,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitGcResult {
+    size_before: u64,
+    size_after: u64,
+    reclaimed: u64,
+}
+
+#[command]
+pub async fn get_git_repo_info(path: String) -> Result<GitRepoInfo, DiskSenseError> {
+    let repo = Path::new(&path);
+    let git_dir = repo.join(".git");
+
+    if !git_dir.is_dir() {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Not a git repository: {}",
+            repo.display()
+        )));
+    }
+
+    let total_size = get_size(repo).map_err(|e| DiskSenseError::Io {
+        path: Some(repo.to_string_lossy().to_string()),
+        code: None,
+        message: format!("Failed to measure repository: {}", e),
+    })?;
+    let git_dir_size = get_size(&git_dir).unwrap_or(0);
+
+    let mut large_packs = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(git_dir.join("objects").join("pack")) {
+        for entry in entries.filter_map(Result::ok) {
+            let pack_path = entry.path();
+            if pack_path.extension().is_some_and(|ext| ext == "pack") {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                if size >= LARGE_PACK_THRESHOLD {
+                    large_packs.push(PackFileInfo {
+                        path: pack_path.to_string_lossy().to_string(),
+                        size,
+                    });
+                }
+            }
+        }
+    }
+    large_packs.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    // Many loose objects or several big packs both mean gc can reclaim space
+    let loose_objects = count_loose_objects(&git_dir);
+    let gc_suggested = large_packs.len() > 1 || loose_objects > 6700;
+
+    Ok(GitRepoInfo {
+        path: repo.to_string_lossy().to_string(),
+        total_size,
+        git_dir_size,
+        working_tree_size: total_size.saturating_sub(git_dir_size),
+        large_packs,
+        gc_suggested,
+    })
+}
+
+#[command]
+pub async fn git_gc(
+    read_only: State<'_, ReadOnlyMode>,
+    app: AppHandle,
+    path: String,
+    action: Option<String>,
+) -> Result<GitGcResult, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let git_dir = Path::new(&path).join(".git");
+    if !git_dir.is_dir() {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Not a git repository: {}",
+            path
+        )));
+    }
+
+    let result = gc(&app, &path);
+    audit::record(
+        &app,
+        "git_gc",
+        action.as_deref(),
+        vec![path],
+        result.as_ref().map_or(0, |gc| gc.reclaimed),
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn gc(app: &AppHandle, path: &str) -> Result<GitGcResult, DiskSenseError> {
+    let repo = Path::new(path);
+    let git_dir = repo.join(".git");
+    let size_before = get_size(&git_dir).unwrap_or(0);
+
+    let mut child = Command::new("git")
+        .args(["gc", "--aggressive", "--progress"])
+        .current_dir(repo)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| DiskSenseError::from_spawn(e, "git"))?;
+
+    // git writes progress to stderr, redrawing lines with carriage returns
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buffer = [0u8; 4096];
+        let mut line = Vec::new();
+        while let Ok(read) = stderr.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            for &byte in &buffer[..read] {
+                if byte == b'\r' || byte == b'\n' {
+                    if !line.is_empty() {
+                        let _ = app.emit(
+                            "git-gc-progress",
+                            &GitGcProgress {
+                                path: path.to_string(),
+                                message: String::from_utf8_lossy(&line).to_string(),
+                            },
+                        );
+                        line.clear();
+                    }
+                } else {
+                    line.push(byte);
+                }
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| DiskSenseError::from_spawn(e, "git gc"))?;
+    if !status.success() {
+        return Err(DiskSenseError::Failed(format!(
+            "git gc exited with {}",
+            status
+        )));
+    }
+
+    let size_after = get_size(&git_dir).unwrap_or(0);
+
+    Ok(GitGcResult {
+        size_before,
+        size_after,
+        reclaimed: size_before.saturating_sub(size_after),
+    })
+}
+
+fn count_loose_objects(git_dir: &Path) -> usize {
+    let objects = match std::fs::read_dir(git_dir.join("objects")) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    // Loose objects live in two-hex-digit fan-out directories
+    objects
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit())
+        })
+        .map(|entry| {
+            std::fs::read_dir(entry.path())
+                .map(|files| files.count())
+                .unwrap_or(0)
+        })
+        .sum()
+}
@@ -0,0 +1,59 @@
+use crate::delta::node_id;
+use crate::DiskItem;
+
+// Name of the synthetic node that collects a level's small children
+const OTHER_NAME: &str = "(other)";
+
+// Merge children smaller than `percent` of their parent into an expandable "(other)" node
+pub fn group_small_children(item: &mut DiskItem, percent: f64) {
+    let children = match item.children.as_mut() {
+        Some(children) => children,
+        None => return,
+    };
+
+    let threshold = (item.size as f64 * percent / 100.0) as u64;
+    let (small, mut kept): (Vec<DiskItem>, Vec<DiskItem>) = std::mem::take(children)
+        .into_iter()
+        .partition(|child| child.size < threshold);
+
+    for child in kept.iter_mut() {
+        group_small_children(child, percent);
+    }
+
+    // Folding a single child away would only hide it
+    if small.len() > 1 {
+        let allocated_size = small
+            .iter()
+            .filter_map(|child| child.allocated_size)
+            .sum::<u64>();
+        let allocated_partial = small
+            .iter()
+            .any(|child| child.allocated_size.is_none() || child.allocated_partial);
+        let estimated = small.iter().any(|child| child.estimated);
+        kept.push(DiskItem {
+            name: format!("{} ({} items)", OTHER_NAME, small.len()),
+            path: item.path.clone(),
+            size: small.iter().map(|child| child.size).sum(),
+            is_dir: true,
+            children: Some(small),
+            game: None,
+            git_repo: false,
+            id: node_id(&format!("{}\0{}", item.path, OTHER_NAME)),
+            allocated_size: (allocated_size > 0 || !allocated_partial).then_some(allocated_size),
+            sparse: false,
+            link_target: None,
+            hard_links: None,
+            in_archive: false,
+            raw_path: None,
+            estimated,
+            hidden: false,
+            known_folder: None,
+            allocated_partial,
+        });
+    } else {
+        kept.extend(small);
+    }
+
+    kept.sort_by_key(|item| std::cmp::Reverse(item.size));
+    *children = kept;
+}
@@ -0,0 +1,96 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+// Database in the app data dir holding the content hashes of files seen so far
+const HASH_CACHE_FILE: &str = "hash_cache.sqlite";
+
+// A file whose hash is known, as written back after hashing
+pub(crate) struct HashedFile {
+    pub(crate) path: PathBuf,
+    pub(crate) size: u64,
+    pub(crate) modified: i64,
+    pub(crate) hash: String,
+}
+
+// Content hashes keyed by path, trusted only while the file keeps the size and
+// modification time it had when hashed. Repeat duplicate searches then only read
+// new and changed files. Without a usable database every lookup misses.
+pub struct HashCache {
+    db: Option<Mutex<Connection>>,
+}
+
+impl HashCache {
+    pub(crate) fn open(app: &AppHandle) -> Self {
+        let db = app
+            .path()
+            .app_data_dir()
+            .ok()
+            .and_then(|dir| {
+                std::fs::create_dir_all(&dir).ok()?;
+                Connection::open(dir.join(HASH_CACHE_FILE)).ok()
+            })
+            .filter(|db| {
+                db.execute_batch(
+                    "CREATE TABLE IF NOT EXISTS file_hashes (
+                        path TEXT PRIMARY KEY,
+                        size INTEGER NOT NULL,
+                        modified INTEGER NOT NULL,
+                        hash TEXT NOT NULL
+                    )",
+                )
+                .is_ok()
+            });
+
+        HashCache {
+            db: db.map(Mutex::new),
+        }
+    }
+
+    // The stored hash, if the file hasn't changed since it was taken
+    pub(crate) fn get(&self, path: &Path, size: u64, modified: i64) -> Option<String> {
+        let db = self.db.as_ref()?.lock().unwrap_or_else(|e| e.into_inner());
+        db.query_row(
+            "SELECT hash FROM file_hashes WHERE path = ?1 AND size = ?2 AND modified = ?3",
+            params![path.to_string_lossy(), size as i64, modified],
+            |row| row.get(0),
+        )
+        .optional()
+        .ok()
+        .flatten()
+    }
+
+    // Store freshly taken hashes in one transaction, replacing stale ones
+    pub(crate) fn put_all(&self, files: &[HashedFile]) {
+        let mut db = match &self.db {
+            Some(db) => db.lock().unwrap_or_else(|e| e.into_inner()),
+            None => return,
+        };
+        let tx = match db.transaction() {
+            Ok(tx) => tx,
+            Err(_) => return,
+        };
+        if let Ok(mut insert) = tx.prepare_cached(
+            "INSERT OR REPLACE INTO file_hashes (path, size, modified, hash) VALUES (?1, ?2, ?3, ?4)",
+        ) {
+            for file in files {
+                let _ = insert.execute(params![
+                    file.path.to_string_lossy(),
+                    file.size as i64,
+                    file.modified,
+                    file.hash
+                ]);
+            }
+        }
+        let _ = tx.commit();
+    }
+}
+
+// Modification time in nanoseconds since the epoch, the cache's change marker
+pub(crate) fn modified(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+    i64::try_from(since_epoch.as_nanos()).ok()
+}
@@ -0,0 +1,33 @@
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Read buffer for hashing; large enough to keep disks streaming
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+// SHA-256 of a file's contents as lowercase hex
+pub(crate) fn hash_file(path: &Path) -> io::Result<String> {
+    hash_file_with::<Sha256>(path)
+}
+
+// Hash of a file's contents with any RustCrypto digest, as lowercase hex
+pub(crate) fn hash_file_with<D: Digest>(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(to_hex(&hasher.finalize()))
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,244 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir holding the hook settings
+const HOOKS_FILE: &str = "hooks.json";
+
+// Folder in the app data dir where summaries handed to hooks are written
+const SUMMARY_DIR: &str = "hook-summaries";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    ScanCompleted,
+    CleanupCompleted,
+}
+
+// An external command run after an event. `{summary}` in the arguments is replaced
+// with the path of a JSON summary of what happened, `{root}` with the scanned or
+// cleaned path.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Hook {
+    #[serde(default)]
+    id: String,
+    event: HookEvent,
+    program: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HookSettings {
+    // Hooks run arbitrary programs, so nothing runs until the user opts in
+    enabled: bool,
+    hooks: Vec<Hook>,
+}
+
+#[derive(Default)]
+pub struct Hooks {
+    settings: Mutex<HookSettings>,
+}
+
+impl Hooks {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let settings = hooks_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        Hooks {
+            settings: Mutex::new(settings),
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = hooks_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let settings = self.settings.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = serde_json::to_string_pretty(&*settings)
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to save hooks: {}", e)))?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    pub(crate) fn settings(&self) -> HookSettings {
+        self.settings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    // Imported hooks arrive switched off: a shared file must not be able to run
+    // programs here until the user opts in again
+    pub(crate) fn import(
+        &self,
+        app: &AppHandle,
+        mut settings: HookSettings,
+    ) -> Result<(), DiskSenseError> {
+        if settings
+            .hooks
+            .iter()
+            .any(|hook| hook.program.trim().is_empty())
+        {
+            return Err(DiskSenseError::InvalidInput(
+                "A hook needs a program to run".to_string(),
+            ));
+        }
+        settings.enabled = false;
+        *self.settings.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+        self.save(app)
+    }
+}
+
+fn hooks_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(HOOKS_FILE))
+}
+
+#[command]
+pub async fn get_hook_settings(hooks: State<'_, Hooks>) -> Result<HookSettings, DiskSenseError> {
+    Ok(hooks
+        .settings
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone())
+}
+
+#[command]
+pub async fn set_hooks_enabled(
+    app: AppHandle,
+    hooks: State<'_, Hooks>,
+    enabled: bool,
+) -> Result<(), DiskSenseError> {
+    hooks
+        .settings
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .enabled = enabled;
+    hooks.save(&app)
+}
+
+#[command]
+pub async fn add_hook(
+    app: AppHandle,
+    hooks: State<'_, Hooks>,
+    mut hook: Hook,
+) -> Result<String, DiskSenseError> {
+    if hook.program.trim().is_empty() {
+        return Err(DiskSenseError::InvalidInput(
+            "A hook needs a program to run".to_string(),
+        ));
+    }
+
+    hook.id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string();
+    let id = hook.id.clone();
+
+    hooks
+        .settings
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .hooks
+        .push(hook);
+    hooks.save(&app)?;
+
+    Ok(id)
+}
+
+#[command]
+pub async fn remove_hook(
+    app: AppHandle,
+    hooks: State<'_, Hooks>,
+    id: String,
+) -> Result<(), DiskSenseError> {
+    hooks
+        .settings
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .hooks
+        .retain(|hook| hook.id != id);
+    hooks.save(&app)
+}
+
+// Start every hook registered for `event` without waiting for them to finish.
+// Failures are logged; a broken hook must not fail the scan or cleanup itself.
+pub(crate) fn run_hooks(app: &AppHandle, event: HookEvent, root: &str, summary: &impl Serialize) {
+    let hooks = match app.try_state::<Hooks>() {
+        Some(hooks) => hooks,
+        None => return,
+    };
+
+    let selected: Vec<Hook> = {
+        let settings = hooks.settings.lock().unwrap_or_else(|e| e.into_inner());
+        if !settings.enabled {
+            return;
+        }
+        settings
+            .hooks
+            .iter()
+            .filter(|hook| hook.event == event)
+            .cloned()
+            .collect()
+    };
+    if selected.is_empty() {
+        return;
+    }
+
+    let summary_path = match write_summary(app, summary) {
+        Ok(path) => path,
+        Err(e) => {
+            log::error!("Failed to write hook summary: {}", e);
+            return;
+        }
+    };
+    let summary_path = summary_path.to_string_lossy().to_string();
+
+    for hook in selected {
+        let args: Vec<String> = hook
+            .args
+            .iter()
+            .map(|arg| {
+                arg.replace("{summary}", &summary_path)
+                    .replace("{root}", root)
+            })
+            .collect();
+
+        if let Err(e) = Command::new(&hook.program).args(&args).spawn() {
+            log::error!("Failed to run hook {}: {}", hook.program, e);
+        }
+    }
+}
+
+fn write_summary(app: &AppHandle, summary: &impl Serialize) -> Result<PathBuf, DiskSenseError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| DiskSenseError::Failed("App data directory is unavailable".to_string()))?
+        .join(SUMMARY_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| DiskSenseError::from_io(e, &dir))?;
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let file = dir.join(format!("{}.json", stamp));
+    let contents = serde_json::to_string_pretty(summary)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to write summary: {}", e)))?;
+    std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))?;
+
+    Ok(file)
+}
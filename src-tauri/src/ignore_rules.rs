@@ -0,0 +1,63 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// Ignore files honored in every directory of the scan
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".disksenseignore"];
+
+// .gitignore / .disksenseignore rules for a scan, read lazily per directory
+pub(crate) struct IgnoreRules {
+    root: PathBuf,
+    matchers: Mutex<HashMap<PathBuf, Arc<Gitignore>>>,
+}
+
+impl IgnoreRules {
+    pub(crate) fn new(root: &Path) -> Self {
+        IgnoreRules {
+            root: root.to_path_buf(),
+            matchers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Like git, the rules closest to the path win, and `!pattern` re-includes
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        // Git's own metadata is never part of what it tracks
+        if is_dir && path.file_name().is_some_and(|name| name == ".git") {
+            return true;
+        }
+
+        for dir in path.ancestors().skip(1) {
+            match self.matcher(dir).matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+
+            if dir == self.root {
+                break;
+            }
+        }
+
+        false
+    }
+
+    fn matcher(&self, dir: &Path) -> Arc<Gitignore> {
+        let mut matchers = self.matchers.lock().unwrap_or_else(|e| e.into_inner());
+        matchers
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let mut builder = GitignoreBuilder::new(dir);
+                for file in IGNORE_FILES {
+                    let path = dir.join(file);
+                    if path.is_file() {
+                        // Malformed lines are skipped; the remaining rules still apply
+                        let _ = builder.add(path);
+                    }
+                }
+                Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty()))
+            })
+            .clone()
+    }
+}
@@ -0,0 +1,193 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::command;
+
+// Limit how many files inside a folder are checked, to keep the query fast
+#[cfg(target_os = "windows")]
+const MAX_FILES_PER_FOLDER: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockingProcess {
+    pid: u32,
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InUseReport {
+    path: String,
+    processes: Vec<LockingProcess>,
+}
+
+// Report which processes hold each path (or files under it) open.
+// Paths that nobody holds open are left out of the result.
+#[command]
+pub async fn check_in_use(paths: Vec<String>) -> Result<Vec<InUseReport>, DiskSenseError> {
+    let mut reports = Vec::new();
+
+    for path in paths {
+        if !Path::new(&path).exists() {
+            return Err(DiskSenseError::NotFound { path });
+        }
+
+        let processes = locking_processes(Path::new(&path))?;
+        if !processes.is_empty() {
+            reports.push(InUseReport { path, processes });
+        }
+    }
+
+    Ok(reports)
+}
+
+// Files to check for a path: the file itself, or a bounded sample of files in a folder
+#[cfg(target_os = "windows")]
+fn files_to_check(path: &Path) -> Vec<std::path::PathBuf> {
+    if path.is_dir() {
+        walkdir::WalkDir::new(path)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .take(MAX_FILES_PER_FOLDER)
+            .map(|entry| entry.into_path())
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn locking_processes(path: &Path) -> Result<Vec<LockingProcess>, DiskSenseError> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::shared::minwindef::{DWORD, UINT};
+    use winapi::shared::winerror::{ERROR_MORE_DATA, ERROR_SUCCESS};
+    use winapi::um::restartmanager::{
+        RmEndSession, RmGetList, RmRegisterResources, RmStartSession, CCH_RM_SESSION_KEY,
+        RM_PROCESS_INFO,
+    };
+
+    let files = files_to_check(path);
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Restart Manager wants NUL-terminated wide strings
+    let wide_files: Vec<Vec<u16>> = files
+        .iter()
+        .map(|file| file.as_os_str().encode_wide().chain(Some(0)).collect())
+        .collect();
+    let mut file_ptrs: Vec<*const u16> = wide_files.iter().map(|w| w.as_ptr()).collect();
+
+    unsafe {
+        let mut session: DWORD = 0;
+        let mut session_key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+        if RmStartSession(&mut session, 0, session_key.as_mut_ptr()) != ERROR_SUCCESS {
+            return Err(DiskSenseError::Failed(
+                "Failed to start a Restart Manager session".to_string(),
+            ));
+        }
+
+        let result = (|| {
+            if RmRegisterResources(
+                session,
+                file_ptrs.len() as UINT,
+                file_ptrs.as_mut_ptr(),
+                0,
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+            ) != ERROR_SUCCESS
+            {
+                return Err(DiskSenseError::Failed(
+                    "Failed to register files with Restart Manager".to_string(),
+                ));
+            }
+
+            // Ask for the count first, then retry with a buffer of that size
+            let mut needed: UINT = 0;
+            let mut count: UINT = 0;
+            let mut reasons: DWORD = 0;
+            let mut infos: Vec<RM_PROCESS_INFO> = Vec::new();
+            loop {
+                let status = RmGetList(
+                    session,
+                    &mut needed,
+                    &mut count,
+                    infos.as_mut_ptr(),
+                    &mut reasons,
+                );
+                if status == ERROR_SUCCESS {
+                    infos.truncate(count as usize);
+                    break;
+                }
+                if status != ERROR_MORE_DATA {
+                    return Err(DiskSenseError::Failed(format!(
+                        "Restart Manager query failed with code {}",
+                        status
+                    )));
+                }
+                infos = vec![std::mem::zeroed(); needed as usize];
+                count = needed;
+            }
+
+            let mut processes: Vec<LockingProcess> = infos
+                .iter()
+                .map(|info| {
+                    let name_len = info
+                        .strAppName
+                        .iter()
+                        .position(|&c| c == 0)
+                        .unwrap_or(info.strAppName.len());
+                    LockingProcess {
+                        pid: info.Process.dwProcessId,
+                        name: String::from_utf16_lossy(&info.strAppName[..name_len]),
+                    }
+                })
+                .collect();
+            processes.dedup();
+            Ok(processes)
+        })();
+
+        RmEndSession(session);
+        result
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn locking_processes(path: &Path) -> Result<Vec<LockingProcess>, DiskSenseError> {
+    use std::process::Command;
+
+    // -F pc prints one "p<pid>" line followed by a "c<command>" line per process;
+    // +D checks everything below a folder
+    let mut command = Command::new("lsof");
+    command.args(["-F", "pc"]);
+    if path.is_dir() {
+        command.arg("+D");
+    } else {
+        command.arg("--");
+    }
+    command.arg(path);
+
+    let output = command
+        .output()
+        .map_err(|e| DiskSenseError::Unsupported(format!("Failed to run lsof: {}", e)))?;
+
+    // lsof exits with 1 when no process has the path open
+    let mut processes: Vec<LockingProcess> = Vec::new();
+    let mut pid = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.parse().ok();
+        } else if let (Some(value), Some(current)) = (line.strip_prefix('c'), pid) {
+            let process = LockingProcess {
+                pid: current,
+                name: value.to_string(),
+            };
+            if !processes.contains(&process) {
+                processes.push(process);
+            }
+        }
+    }
+
+    Ok(processes)
+}
@@ -0,0 +1,40 @@
+use crate::{perform_scan, DiskItem, DiskSenseError, ScanOptions, ScanStore};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Bring a saved tree up to date by relisting only the `dirty` directories and
+// the folders above them; every other folder is reused from `tree` as it is
+pub(crate) fn refresh(
+    app: &AppHandle,
+    store: &ScanStore,
+    tree: &DiskItem,
+    depth: usize,
+    options: ScanOptions,
+    dirty: &[PathBuf],
+) -> Result<DiskItem, DiskSenseError> {
+    let mut unchanged = HashMap::new();
+    collect_unchanged(tree, dirty, &mut unchanged);
+    perform_scan(app, store, Path::new(&tree.path), depth, options, unchanged)
+}
+
+// A folder can be reused when no dirty directory is at or below it. Folders at
+// the depth limit only hold a total, so they are reused whole or not at all.
+fn collect_unchanged(
+    item: &DiskItem,
+    dirty: &[PathBuf],
+    unchanged: &mut HashMap<String, DiskItem>,
+) {
+    if !item.is_dir || item.in_archive || item.hidden {
+        return;
+    }
+
+    if !dirty.iter().any(|path| path.starts_with(&item.path)) {
+        unchanged.insert(item.path.clone(), item.clone());
+        return;
+    }
+
+    for child in item.children.iter().flatten() {
+        collect_unchanged(child, dirty, unchanged);
+    }
+}
@@ -0,0 +1,485 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{
+    audit, delete_and_report, hashing, os_path, DiskSenseError, ProtectedPaths, ScanStore,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Emitter, Manager, State};
+use walkdir::WalkDir;
+
+// Finished jobs kept for list_jobs before the oldest are dropped
+const MAX_FINISHED_JOBS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobKind {
+    Delete {
+        paths: Vec<String>,
+        staged: bool,
+    },
+    // Moves each path into the destination folder
+    Move {
+        paths: Vec<String>,
+        destination: String,
+    },
+    // Zips the paths into a new archive
+    Compress {
+        paths: Vec<String>,
+        archive: String,
+    },
+    // SHA-256 of every file under the paths
+    Hash {
+        paths: Vec<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Job {
+    id: u64,
+    job: JobKind,
+    status: JobStatus,
+    // 0 to 1
+    progress: f32,
+    current_path: Option<String>,
+    result: Option<serde_json::Value>,
+    error: Option<DiskSenseError>,
+    created_at: u64,
+    // The UI action that queued it, for the audit log
+    action: Option<String>,
+}
+
+// Long operations queued to run one after another on a worker thread, so they
+// never hold up scans or each other. Progress goes out as "job-progress" events.
+#[derive(Default)]
+pub struct JobManager {
+    next_id: AtomicU64,
+    jobs: Mutex<BTreeMap<u64, Job>>,
+    queue: Mutex<VecDeque<u64>>,
+    queued: Condvar,
+    cancel_flags: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+impl JobManager {
+    // Run queued jobs for as long as the app runs
+    pub(crate) fn start_worker(app: AppHandle) {
+        std::thread::Builder::new()
+            .name("disksense-jobs".to_string())
+            .spawn(move || {
+                let manager = app.state::<JobManager>();
+                loop {
+                    let id = {
+                        let mut queue = manager.queue.lock().unwrap_or_else(|e| e.into_inner());
+                        loop {
+                            match queue.pop_front() {
+                                Some(id) => break id,
+                                None => {
+                                    queue = manager
+                                        .queued
+                                        .wait(queue)
+                                        .unwrap_or_else(|e| e.into_inner())
+                                }
+                            }
+                        }
+                    };
+                    manager.run(&app, id);
+                }
+            })
+            .ok();
+    }
+
+    fn run(&self, app: &AppHandle, id: u64) {
+        let cancelled = self.cancel_flag(id);
+        let (kind, action) = match self.update(app, id, |job| {
+            // Cancelled while still queued
+            if job.status != JobStatus::Queued {
+                return;
+            }
+            job.status = JobStatus::Running;
+        }) {
+            Some(job) if job.status == JobStatus::Running => (job.job, job.action),
+            _ => return,
+        };
+        // Copies into an archive and hashes leave the originals untouched
+        let audited = match &kind {
+            JobKind::Delete { paths, .. } => Some(("delete_job", paths.clone())),
+            JobKind::Move { paths, .. } => Some(("move_job", paths.clone())),
+            JobKind::Compress { .. } | JobKind::Hash { .. } => None,
+        };
+
+        let report = |done: usize, total: usize, path: &str| {
+            self.update(app, id, |job| {
+                job.progress = done as f32 / total.max(1) as f32;
+                job.current_path = Some(path.to_string());
+            });
+        };
+        let result = match kind {
+            JobKind::Delete { paths, staged } => {
+                run_delete(app, &paths, staged, &cancelled, report)
+            }
+            JobKind::Move { paths, destination } => {
+                run_move(&paths, &destination, &cancelled, report)
+            }
+            JobKind::Compress { paths, archive } => {
+                run_compress(&paths, &archive, &cancelled, report)
+            }
+            JobKind::Hash { paths } => run_hash(&paths, &cancelled, report),
+        };
+
+        if let Some((command, paths)) = audited {
+            let bytes = result
+                .as_ref()
+                .ok()
+                .and_then(|value| value.get("bytes_reclaimed"))
+                .and_then(|bytes| bytes.as_u64())
+                .unwrap_or(0);
+            audit::record(
+                app,
+                command,
+                action.as_deref(),
+                paths,
+                bytes,
+                result.as_ref().err(),
+            );
+        }
+
+        self.update(app, id, |job| match result {
+            Ok(value) => {
+                job.status = JobStatus::Done;
+                job.progress = 1.0;
+                job.result = Some(value);
+            }
+            Err(DiskSenseError::Cancelled) => job.status = JobStatus::Cancelled,
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        });
+        self.cancel_flags
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+        self.prune();
+    }
+
+    // Change a job and tell the front-end, returning the updated job
+    fn update(&self, app: &AppHandle, id: u64, change: impl FnOnce(&mut Job)) -> Option<Job> {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+            let job = jobs.get_mut(&id)?;
+            change(job);
+            job.clone()
+        };
+        let _ = app.emit("job-progress", &job);
+        Some(job)
+    }
+
+    fn cancel_flag(&self, id: u64) -> Arc<AtomicBool> {
+        self.cancel_flags
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(id)
+            .or_default()
+            .clone()
+    }
+
+    fn prune(&self) {
+        let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        let finished: Vec<u64> = jobs
+            .values()
+            .filter(|job| !matches!(job.status, JobStatus::Queued | JobStatus::Running))
+            .map(|job| job.id)
+            .collect();
+        for id in finished
+            .iter()
+            .take(finished.len().saturating_sub(MAX_FINISHED_JOBS))
+        {
+            jobs.remove(id);
+        }
+    }
+}
+
+#[command]
+pub async fn enqueue_job(
+    app: AppHandle,
+    manager: State<'_, JobManager>,
+    read_only: State<'_, ReadOnlyMode>,
+    job: JobKind,
+    action: Option<String>,
+) -> Result<u64, DiskSenseError> {
+    // Hashing only reads; every other job changes files
+    if !matches!(job, JobKind::Hash { .. }) {
+        read_only.ensure_writable()?;
+    }
+    let paths = match &job {
+        JobKind::Delete { paths, .. }
+        | JobKind::Move { paths, .. }
+        | JobKind::Compress { paths, .. }
+        | JobKind::Hash { paths } => paths,
+    };
+    if paths.is_empty() {
+        return Err(DiskSenseError::InvalidInput("Job has no paths".to_string()));
+    }
+
+    let id = manager.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let queued = Job {
+        id,
+        job,
+        status: JobStatus::Queued,
+        progress: 0.0,
+        current_path: None,
+        result: None,
+        error: None,
+        created_at,
+        action,
+    };
+
+    manager
+        .jobs
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(id, queued.clone());
+    let _ = app.emit("job-progress", &queued);
+
+    manager
+        .queue
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push_back(id);
+    manager.queued.notify_one();
+
+    Ok(id)
+}
+
+#[command]
+pub async fn list_jobs(manager: State<'_, JobManager>) -> Result<Vec<Job>, DiskSenseError> {
+    Ok(manager
+        .jobs
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect())
+}
+
+// Cancel a queued job, or stop a running one after the item it is working on
+#[command]
+pub async fn cancel_job(
+    app: AppHandle,
+    manager: State<'_, JobManager>,
+    id: u64,
+) -> Result<(), DiskSenseError> {
+    let job = manager
+        .update(&app, id, |job| {
+            if job.status == JobStatus::Queued {
+                job.status = JobStatus::Cancelled;
+            }
+        })
+        .ok_or_else(|| DiskSenseError::NotFound {
+            path: format!("job {}", id),
+        })?;
+
+    match job.status {
+        JobStatus::Cancelled => {
+            manager
+                .queue
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .retain(|queued| *queued != id);
+        }
+        JobStatus::Running => manager.cancel_flag(id).store(true, Ordering::SeqCst),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn check_cancelled(cancelled: &AtomicBool) -> Result<(), DiskSenseError> {
+    match cancelled.load(Ordering::SeqCst) {
+        true => Err(DiskSenseError::Cancelled),
+        false => Ok(()),
+    }
+}
+
+fn run_delete(
+    app: &AppHandle,
+    paths: &[String],
+    staged: bool,
+    cancelled: &AtomicBool,
+    report: impl Fn(usize, usize, &str),
+) -> Result<serde_json::Value, DiskSenseError> {
+    let protected = app.state::<ProtectedPaths>();
+    let store = app.state::<ScanStore>();
+    let mut deleted = crate::deletion::DeletionReport::default();
+
+    for (index, path) in paths.iter().enumerate() {
+        check_cancelled(cancelled)?;
+        report(index, paths.len(), path);
+        match delete_and_report(app, cancelled, &protected, &store, path, false, staged) {
+            // The stored scans already reflect what went before a cancel
+            Ok(result) if result.is_cancelled() => return Err(DiskSenseError::Cancelled),
+            Ok(result) => deleted.merge(result),
+            Err(e) => deleted.fail(e),
+        }
+    }
+
+    serde_json::to_value(&deleted).map_err(|e| DiskSenseError::Failed(e.to_string()))
+}
+
+fn run_move(
+    paths: &[String],
+    destination: &str,
+    cancelled: &AtomicBool,
+    report: impl Fn(usize, usize, &str),
+) -> Result<serde_json::Value, DiskSenseError> {
+    let destination = os_path::decode(destination);
+    std::fs::create_dir_all(&destination).map_err(|e| DiskSenseError::from_io(e, &destination))?;
+
+    let mut moved = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        check_cancelled(cancelled)?;
+        report(index, paths.len(), path);
+
+        let source = os_path::decode(path);
+        let name = source
+            .file_name()
+            .ok_or_else(|| DiskSenseError::InvalidInput(format!("Cannot move {}", path)))?;
+        let target = destination.join(name);
+        if target.exists() {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "{} already exists",
+                target.display()
+            )));
+        }
+
+        // Renames are instant on the same volume; anything else is copied then removed
+        if std::fs::rename(&source, &target).is_err() {
+            let options = fs_extra::dir::CopyOptions::new();
+            fs_extra::move_items(&[&source], &destination, &options)
+                .map_err(|e| DiskSenseError::Failed(format!("Failed to move {}: {}", path, e)))?;
+        }
+        moved.push(target.to_string_lossy().to_string());
+    }
+
+    Ok(serde_json::json!({ "moved": moved }))
+}
+
+fn run_compress(
+    paths: &[String],
+    archive: &str,
+    cancelled: &AtomicBool,
+    report: impl Fn(usize, usize, &str),
+) -> Result<serde_json::Value, DiskSenseError> {
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    let archive_path = os_path::decode(archive);
+    if archive_path.exists() {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "{} already exists",
+            archive
+        )));
+    }
+
+    // Entries are named relative to each path's parent, so folders keep their name
+    let files: Vec<(std::path::PathBuf, String)> = paths
+        .iter()
+        .map(|path| os_path::decode(path))
+        .flat_map(|root| {
+            let base = root.parent().map(Path::to_path_buf).unwrap_or_default();
+            WalkDir::new(&root)
+                .follow_links(false)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .map(move |entry| {
+                    let name = entry
+                        .path()
+                        .strip_prefix(&base)
+                        .unwrap_or(entry.path())
+                        .to_string_lossy()
+                        .replace('\\', "/");
+                    (entry.into_path(), name)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let file =
+        File::create(&archive_path).map_err(|e| DiskSenseError::from_io(e, &archive_path))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_error = |e: zip::result::ZipError| {
+        DiskSenseError::Failed(format!("Failed to write {}: {}", archive, e))
+    };
+
+    let result = (|| {
+        for (index, (path, name)) in files.iter().enumerate() {
+            check_cancelled(cancelled)?;
+            report(index, files.len(), &path.to_string_lossy());
+
+            zip.start_file(name.as_str(), options).map_err(zip_error)?;
+            let mut source = File::open(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+            io::copy(&mut source, &mut zip).map_err(|e| DiskSenseError::from_io(e, path))?;
+        }
+        zip.finish().map_err(zip_error)
+    })();
+
+    // Don't leave a half-written archive behind
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&archive_path);
+        return Err(e);
+    }
+
+    let size = std::fs::metadata(&archive_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+    Ok(serde_json::json!({ "archive": archive, "files": files.len(), "size": size }))
+}
+
+fn run_hash(
+    paths: &[String],
+    cancelled: &AtomicBool,
+    report: impl Fn(usize, usize, &str),
+) -> Result<serde_json::Value, DiskSenseError> {
+    let files: Vec<std::path::PathBuf> = paths
+        .iter()
+        .flat_map(|path| {
+            WalkDir::new(os_path::decode(path))
+                .follow_links(false)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.into_path())
+        })
+        .collect();
+
+    let mut hashes = BTreeMap::new();
+    for (index, path) in files.iter().enumerate() {
+        check_cancelled(cancelled)?;
+        let display = path.to_string_lossy().to_string();
+        report(index, files.len(), &display);
+        let hash = hashing::hash_file(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+        hashes.insert(display, hash);
+    }
+
+    Ok(serde_json::json!({ "hashes": hashes }))
+}
@@ -0,0 +1,202 @@
+use crate::{DiskItem, DiskSenseError};
+use serde::Serialize;
+use std::path::Path;
+use tauri::command;
+
+// Where Explorer looks up the current location of each known folder
+#[cfg(target_os = "windows")]
+const USER_SHELL_FOLDERS_KEY: &str =
+    r"HKCU\Software\Microsoft\Windows\CurrentVersion\Explorer\User Shell Folders";
+
+// Registry value names of the folders users commonly redirect, with the name
+// of their default folder in the profile
+#[cfg(target_os = "windows")]
+const FOLDERS: [(&str, &str); 6] = [
+    ("Desktop", "Desktop"),
+    ("Personal", "Documents"),
+    ("My Pictures", "Pictures"),
+    ("My Music", "Music"),
+    ("My Video", "Videos"),
+    ("{374DE290-123F-4565-9164-39C4925E467B}", "Downloads"),
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct KnownFolder {
+    name: String,
+    // Where the folder lives now
+    path: String,
+    // Its place in the profile when nothing redirects it
+    default_path: String,
+    redirected: bool,
+    // Redirected into a folder OneDrive syncs
+    onedrive: bool,
+}
+
+// Known folders of the current user and where they point. Only Windows
+// redirects them; other platforms return nothing.
+#[command]
+pub async fn get_known_folders() -> Result<Vec<KnownFolder>, DiskSenseError> {
+    Ok(known_folders())
+}
+
+// Label known folders in a scan tree, and keep a redirected folder from being
+// counted twice: Windows can leave a junction at the old location that points
+// to the new one, and a scan following it would list the same files under both
+// paths. Such a junction keeps its label but none of the size.
+pub(crate) fn mark_known_folders(root: &mut DiskItem) {
+    for folder in known_folders() {
+        let label = match (folder.onedrive, folder.redirected) {
+            (true, _) => format!("{} (OneDrive)", folder.name),
+            (false, true) => format!("{} (redirected)", folder.name),
+            (false, false) => folder.name.clone(),
+        };
+        if let Some(item) = find_dir(root, Path::new(&folder.path)) {
+            item.known_folder = Some(label);
+        }
+        if !folder.redirected {
+            continue;
+        }
+
+        let same_content = dunce::canonicalize(&folder.default_path).ok()
+            == dunce::canonicalize(&folder.path).ok();
+        let old_label = if same_content {
+            format!("{} (link to {})", folder.name, folder.path)
+        } else {
+            format!("{} (old location)", folder.name)
+        };
+        let old_path = Path::new(&folder.default_path);
+        if let Some(item) = find_dir(root, old_path) {
+            item.known_folder = Some(old_label);
+        }
+        if same_content && counted_elsewhere(root, old_path, Path::new(&folder.path)) {
+            drop_size(root, old_path);
+        }
+    }
+}
+
+// Whether the tree lists the redirected folder under its own path too. A scan of
+// the old location itself, or one that doesn't reach the new one, has no other
+// copy of the files.
+fn counted_elsewhere(root: &DiskItem, old_path: &Path, new_path: &Path) -> bool {
+    let root_path = Path::new(&root.path);
+    !root_path.starts_with(old_path) && new_path.starts_with(root_path)
+}
+
+fn find_dir<'a>(item: &'a mut DiskItem, path: &Path) -> Option<&'a mut DiskItem> {
+    if !item.is_dir || !path.starts_with(&item.path) {
+        return None;
+    }
+    if Path::new(&item.path) == path {
+        return Some(item);
+    }
+    item.children
+        .as_mut()?
+        .iter_mut()
+        .find_map(|child| find_dir(child, path))
+}
+
+// Empty the node at `path` and take its size off every folder above it,
+// returning the bytes removed
+fn drop_size(item: &mut DiskItem, path: &Path) -> u64 {
+    let removed = if Path::new(&item.path) == path {
+        item.children = Some(Vec::new());
+        item.allocated_size = None;
+        item.size
+    } else {
+        item.children
+            .iter_mut()
+            .flatten()
+            .filter(|child| child.is_dir && path.starts_with(&child.path))
+            .map(|child| drop_size(child, path))
+            .sum()
+    };
+    item.size = item.size.saturating_sub(removed);
+    removed
+}
+
+#[cfg(target_os = "windows")]
+fn known_folders() -> Vec<KnownFolder> {
+    let profile = match crate::home_dir() {
+        Some(profile) => profile,
+        None => return Vec::new(),
+    };
+    let output = match std::process::Command::new("reg")
+        .args(["query", USER_SHELL_FOLDERS_KEY])
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+    let values = String::from_utf8_lossy(&output.stdout).to_string();
+    let onedrive_roots: Vec<std::path::PathBuf> =
+        ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"]
+            .iter()
+            .filter_map(|var| std::env::var_os(var))
+            .map(std::path::PathBuf::from)
+            .collect();
+
+    FOLDERS
+        .iter()
+        .filter_map(|(value_name, name)| {
+            let value = registry_value(&values, value_name)?;
+            let path = std::path::PathBuf::from(expand_env(&value));
+            let default_path = profile.join(name);
+            Some(KnownFolder {
+                name: name.to_string(),
+                redirected: !same_path(&path, &default_path),
+                onedrive: onedrive_roots.iter().any(|root| path.starts_with(root)),
+                path: path.to_string_lossy().to_string(),
+                default_path: default_path.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn known_folders() -> Vec<KnownFolder> {
+    Vec::new()
+}
+
+// Lines of `reg query` look like "    Personal    REG_EXPAND_SZ    %USERPROFILE%\Documents"
+#[cfg(target_os = "windows")]
+fn registry_value(output: &str, name: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        ["REG_EXPAND_SZ", "REG_SZ"].iter().find_map(|kind| {
+            let (value_name, value) = line.split_once(kind)?;
+            (value_name.trim() == name).then(|| value.trim().to_string())
+        })
+    })
+}
+
+// Replace %VAR% references; unknown variables are left as they are
+#[cfg(target_os = "windows")]
+fn expand_env(value: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        expanded.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(var) => expanded.push_str(&var),
+            Err(_) => expanded.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(target_os = "windows")]
+fn same_path(a: &Path, b: &Path) -> bool {
+    let normalize = |path: &Path| {
+        path.to_string_lossy()
+            .trim_end_matches('\\')
+            .replace('/', "\\")
+            .to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
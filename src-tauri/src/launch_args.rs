@@ -0,0 +1,169 @@
+use crate::external_open::{self, ExternalScan};
+use crate::{default_scan_options, perform_scan, DiskSenseError, ScanStore};
+use std::collections::HashMap;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+// Depth used for --export when --depth isn't given, as for scans started in the UI
+const DEFAULT_EXPORT_DEPTH: usize = 2;
+
+const USAGE: &str = "Usage: disksense [PATH] [--depth N] [--export FILE] [--exit]";
+
+// `disksense <path> --depth N --export out.json --exit`: scan a folder on launch,
+// optionally write the tree to a JSON file and quit once it is written
+#[derive(Debug, Default)]
+pub(crate) struct LaunchArgs {
+    path: Option<String>,
+    depth: Option<usize>,
+    export: Option<PathBuf>,
+    exit: bool,
+}
+
+// Relative paths are resolved against `cwd`, the directory the app was started from
+pub(crate) fn parse(args: &[String], cwd: &Path) -> Result<LaunchArgs, String> {
+    let mut parsed = LaunchArgs::default();
+    let mut args = args.iter().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth" => {
+                let value = args.next().ok_or("--depth needs a number")?;
+                parsed.depth = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid depth: {}", value))?,
+                );
+            }
+            "--export" => {
+                let file = args.next().ok_or("--export needs a file")?;
+                parsed.export = Some(cwd.join(file));
+            }
+            "--exit" => parsed.exit = true,
+            // macOS adds a process serial number when launched from Finder
+            flag if flag.starts_with("-psn_") => {}
+            flag if flag.starts_with('-') => return Err(format!("Unknown option {}", flag)),
+            path if parsed.path.is_none() => {
+                let path = cwd.join(path);
+                if !path.exists() {
+                    return Err(format!("{} does not exist", path.display()));
+                }
+                parsed.path = Some(
+                    dunce::canonicalize(&path)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .to_string(),
+                );
+            }
+            extra => return Err(format!("Unexpected argument {}", extra)),
+        }
+    }
+
+    if parsed.path.is_none() && (parsed.export.is_some() || parsed.depth.is_some()) {
+        return Err("--depth and --export need a path to scan".to_string());
+    }
+    if parsed.exit && parsed.export.is_none() {
+        return Err("--exit is only used with --export".to_string());
+    }
+    Ok(parsed)
+}
+
+// Parse the arguments of this launch, quitting with a usage message when they
+// don't make sense and the caller asked for an unattended run
+pub(crate) fn parse_or_exit(args: &[String], cwd: &Path) -> LaunchArgs {
+    match parse(args, cwd) {
+        Ok(parsed) => parsed,
+        Err(e) if args.iter().any(|arg| arg == "--exit") => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+        Err(e) => {
+            log::error!("Ignoring launch arguments: {}", e);
+            LaunchArgs::default()
+        }
+    }
+}
+
+// Start what the arguments ask for. `first_launch` is false for arguments a
+// second launch handed over to the running app, which never quits for them.
+pub(crate) fn apply(app: &AppHandle, args: LaunchArgs, first_launch: bool) {
+    let path = match args.path {
+        Some(path) => path,
+        None => {
+            if !first_launch {
+                external_open::focus_main_window(app);
+            }
+            return;
+        }
+    };
+    let file = match args.export {
+        Some(file) => file,
+        None => {
+            external_open::handle_external_open(
+                app,
+                ExternalScan {
+                    path,
+                    depth: args.depth,
+                },
+            );
+            return;
+        }
+    };
+
+    let exit = args.exit && first_launch;
+    if exit {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.hide();
+        }
+    }
+
+    let app = app.clone();
+    let depth = args.depth.unwrap_or(DEFAULT_EXPORT_DEPTH);
+    std::thread::spawn(move || {
+        let result = export_scan(&app, &path, depth, &file);
+        match &result {
+            Ok(()) => log::info!("Exported the scan of {} to {}", path, file.display()),
+            Err(e) => {
+                log::error!("Failed to export the scan of {}: {}", path, e);
+                eprintln!("Failed to export the scan of {}: {}", path, e);
+            }
+        }
+
+        if exit {
+            app.exit(if result.is_ok() { 0 } else { 1 });
+        } else {
+            // The tree is stored, but the window still shows it through its own scan
+            external_open::handle_external_open(
+                &app,
+                ExternalScan {
+                    path,
+                    depth: Some(depth),
+                },
+            );
+        }
+    });
+}
+
+fn export_scan(
+    app: &AppHandle,
+    path: &str,
+    depth: usize,
+    file: &Path,
+) -> Result<(), DiskSenseError> {
+    let store = app.state::<ScanStore>();
+    let tree = perform_scan(
+        app,
+        &store,
+        Path::new(path),
+        depth,
+        default_scan_options(),
+        HashMap::new(),
+    )?;
+
+    let out = std::fs::File::create(file).map_err(|e| DiskSenseError::from_io(e, file))?;
+    let mut writer = BufWriter::new(out);
+    serde_json::to_writer(&mut writer, &tree).map_err(|e| {
+        DiskSenseError::Failed(format!("Failed to write {}: {}", file.display(), e))
+    })?;
+    writer.flush().map_err(|e| DiskSenseError::from_io(e, file))
+}
@@ -1,20 +1,121 @@
 use dunce::canonicalize;
 use fs_extra::dir::get_size;
 use log::error;
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
-use sysinfo::{Components, Disks, Networks, System};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::Disks;
 use tauri::command;
-use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder};
+use tauri::menu::{MenuBuilder, MenuItemBuilder};
 use tauri::{AppHandle, Emitter, Listener, Manager};
-use tauri_plugin_fs;
-use tauri_plugin_opener;
 use walkdir::WalkDir;
 
+mod appdata;
+mod archive;
+mod audit;
+mod baselines;
+mod benchmark;
+mod bucket;
+mod budgets;
+mod change_recorder;
+mod checkpoint;
+mod children;
+mod cleanup_plan;
+mod cleanup_rules;
+mod clipboard;
+mod component_store;
+mod config;
+mod copy;
+mod deletion;
+mod delta;
+mod dir_count;
+mod docker;
+mod drive_stats;
+mod drive_watch;
+mod duplicates;
+mod eject;
+mod electron_cache;
+mod error;
+mod export;
+mod extension_colors;
+mod extension_stats;
+mod external_open;
+mod fragmentation;
+mod fs_events;
+mod games;
+mod git;
+mod grouping;
+mod hash_cache;
+mod hashing;
+mod hooks;
+mod ignore_rules;
+mod in_use;
+mod incremental;
+mod jobs;
+mod known_folders;
+mod launch_args;
+mod linux_logs;
+mod linux_packages;
+mod macos;
+mod mail;
+mod manifest;
+mod media;
+mod metrics;
+mod mobile_storage;
+mod mtp;
+mod name_sort;
+mod offload;
+mod os_path;
+mod package_cache;
+mod partial_sizes;
+mod photos;
+mod preview;
+mod priority;
+mod process_io;
+mod properties;
+mod protected;
+mod read_only;
+mod reflink;
+mod remote;
+mod report;
+mod reserved_space;
+mod revalidate;
+mod scan_log;
+mod scan_queue;
+mod scan_validation;
+mod scan_windows;
+mod search;
+mod session;
+mod sftp;
+mod sparse;
+mod staging;
+mod structure_scan;
+mod system_files;
+mod tags;
+mod tree_hash;
+mod tree_stream;
+mod treemap_image;
+mod unicode_names;
+mod user_profiles;
+mod visibility;
+mod vm_disk;
+mod volume;
+mod vss;
+mod wildcard;
+mod windows_cleanup;
+
+use checkpoint::ScanCheckpoint;
+pub use error::DiskSenseError;
+use ignore_rules::IgnoreRules;
+use partial_sizes::PartialSizeTracker;
+use protected::ProtectedPaths;
+use scan_log::ScanLog;
+use visibility::HiddenTally;
+use volume::EncryptionStatus;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DiskItem {
     name: String,
@@ -22,6 +123,32 @@ pub struct DiskItem {
     size: u64,
     is_dir: bool,
     children: Option<Vec<DiskItem>>,
+    game: Option<String>,
+    git_repo: bool,
+    id: String,
+    allocated_size: Option<u64>,
+    sparse: bool,
+    link_target: Option<String>,
+    hard_links: Option<u64>,
+    // Virtual node listing the contents of an archive; it has no path on disk
+    in_archive: bool,
+    // Exact path for names that aren't valid Unicode, which `path` can only show
+    // lossily; pass it instead of `path` to commands
+    raw_path: Option<String>,
+    // Size is a quick estimate (or includes one) that a later pass will replace
+    #[serde(default)]
+    estimated: bool,
+    // Stands in for the hidden entries of its folder; its path is made up and
+    // resolves to nothing on disk
+    #[serde(default)]
+    hidden: bool,
+    // Desktop, Documents and the like, with where they were redirected to
+    #[serde(default)]
+    known_folder: Option<String>,
+    // Some contents have no allocation known (folders past the depth limit,
+    // estimated or hidden ones), so allocated_size only covers the rest
+    #[serde(default)]
+    allocated_partial: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,12 +159,158 @@ pub struct ScanProgress {
     percent: f32,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SkippedPath {
+    path: String,
+    reason: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ScanOptions {
     fast_mode: bool,
     skip_hidden: bool,
+    #[serde(default)]
+    background_priority: bool,
+    #[serde(default)]
+    max_threads: Option<usize>,
+    #[serde(default)]
+    max_concurrent_dir_reads: Option<usize>,
+    #[serde(default)]
+    timeout_seconds: Option<u64>,
+    #[serde(default)]
+    stall_timeout_seconds: Option<u64>,
+    #[serde(default)]
+    respect_ignore_files: bool,
+    #[serde(default)]
+    group_below_percent: Option<f64>,
+    #[serde(default)]
+    expand_archives_over: Option<u64>,
+    // Estimate the size of large folders and folders past the depth limit
+    // instead of counting them; quick but only a rough first picture
+    #[serde(default)]
+    instant_preview: bool,
+    // Emit an instant preview tree first, then refine it to exact sizes
+    // through a "scan-delta" once the full scan completes
+    #[serde(default)]
+    two_pass: bool,
+}
+
+// Completed scans, keyed by the path of their root directory (the scan id)
+#[derive(Default)]
+pub struct ScanStore {
+    scans: Mutex<HashMap<String, DiskItem>>,
+}
+
+impl ScanStore {
+    pub(crate) fn get(&self, scan_id: &str) -> Result<DiskItem, DiskSenseError> {
+        self.scans
+            .lock()
+            .map_err(|_| DiskSenseError::Failed("Scan store is unavailable".to_string()))?
+            .get(scan_id)
+            .cloned()
+            .ok_or_else(|| DiskSenseError::NotFound {
+                path: scan_id.to_string(),
+            })
+    }
+
+    // Root paths of every stored scan
+    pub(crate) fn root_paths(&self) -> Vec<String> {
+        self.scans
+            .lock()
+            .map(|scans| scans.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // Take a deleted path out of every stored scan containing it, returning the
+    // changes for the front-end
+    pub(crate) fn apply_deletion(
+        &self,
+        path: &str,
+        bytes: u64,
+        removed: bool,
+    ) -> Vec<delta::ScanDelta> {
+        let mut scans = match self.scans.lock() {
+            Ok(scans) => scans,
+            Err(_) => return Vec::new(),
+        };
+
+        scans
+            .iter_mut()
+            .filter_map(|(scan_id, root)| delta::shrink(scan_id, root, path, bytes, removed))
+            .collect()
+    }
+
+    pub(crate) fn remove(&self, scan_id: &str) {
+        if let Ok(mut scans) = self.scans.lock() {
+            scans.remove(scan_id);
+        }
+    }
+
+    // Set a file's size in every stored scan containing it, returning the
+    // changes for the front-end
+    pub(crate) fn apply_resize(&self, path: &str, size: u64) -> Vec<delta::ScanDelta> {
+        let mut scans = match self.scans.lock() {
+            Ok(scans) => scans,
+            Err(_) => return Vec::new(),
+        };
+
+        scans
+            .iter_mut()
+            .filter_map(|(scan_id, root)| delta::resize(scan_id, root, path, size))
+            .collect()
+    }
+
+    // Attach listed children, e.g. an archive's contents, to the node at `path`
+    // in every stored scan containing it, returning the changes for the front-end
+    pub(crate) fn apply_expansion(
+        &self,
+        path: &str,
+        children: &[DiskItem],
+    ) -> Vec<delta::ScanDelta> {
+        let mut scans = match self.scans.lock() {
+            Ok(scans) => scans,
+            Err(_) => return Vec::new(),
+        };
+
+        scans
+            .iter_mut()
+            .filter_map(|(scan_id, root)| delta::attach(scan_id, root, path, children))
+            .collect()
+    }
+
+    // Look up any node of a stored scan by its path (not its id)
+    pub(crate) fn find_node(&self, path: &str) -> Result<DiskItem, DiskSenseError> {
+        let scans = self
+            .scans
+            .lock()
+            .map_err(|_| DiskSenseError::Failed("Scan store is unavailable".to_string()))?;
+
+        scans
+            .values()
+            .filter(|root| Path::new(path).starts_with(&root.path))
+            .find_map(|root| find_descendant(root, path))
+            .cloned()
+            .ok_or_else(|| DiskSenseError::NotFound {
+                path: path.to_string(),
+            })
+    }
+}
+
+fn find_descendant<'a>(item: &'a DiskItem, path: &str) -> Option<&'a DiskItem> {
+    if item.path == path {
+        return Some(item);
+    }
+
+    item.children
+        .as_ref()?
+        .iter()
+        .filter(|child| !child.hidden && Path::new(path).starts_with(&child.path))
+        .find_map(|child| find_descendant(child, path))
 }
 
+// Number of threads used when scanning with background priority
+const BACKGROUND_SCAN_THREADS: usize = 2;
+
 // Directories to skip on Windows to avoid permission issues
 #[cfg(target_os = "windows")]
 const SKIP_DIRS: [&str; 8] = [
@@ -54,31 +327,74 @@ const SKIP_DIRS: [&str; 8] = [
 #[command]
 async fn scan_directory(
     app: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    store: tauri::State<'_, ScanStore>,
+    windows: tauri::State<'_, scan_windows::ScanWindows>,
     path: String,
     depth: Option<usize>,
     options: Option<ScanOptions>,
-) -> Result<DiskItem, String> {
+) -> Result<DiskItem, DiskSenseError> {
     let max_depth = depth.unwrap_or(2);
-    let options = options.unwrap_or(ScanOptions {
+    let options = options.unwrap_or_else(default_scan_options);
+
+    // Mobile pickers hand out content:// or file:// URIs rather than paths
+    let path = mobile_storage::resolve_storage_path(&path)?;
+
+    // Progress and deltas of this scan go to the window that started it
+    if let Ok(root) = canonicalize(&path) {
+        windows.claim(window.label(), &root.to_string_lossy());
+    }
+
+    perform_scan(&app, &store, &path, max_depth, options, HashMap::new())
+}
+
+// Options for scans whose caller doesn't choose any
+pub(crate) fn default_scan_options() -> ScanOptions {
+    ScanOptions {
         fast_mode: true,
         skip_hidden: true,
-    });
-
-    let path = Path::new(&path);
+        background_priority: false,
+        max_threads: None,
+        max_concurrent_dir_reads: None,
+        timeout_seconds: None,
+        stall_timeout_seconds: None,
+        respect_ignore_files: false,
+        group_below_percent: None,
+        expand_archives_over: None,
+        instant_preview: false,
+        two_pass: false,
+    }
+}
 
+// Scan `path`, reusing any subtrees an interrupted scan already finished
+fn perform_scan(
+    app: &tauri::AppHandle,
+    store: &ScanStore,
+    path: &Path,
+    max_depth: usize,
+    options: ScanOptions,
+    resumed: HashMap<String, DiskItem>,
+) -> Result<DiskItem, DiskSenseError> {
     if !path.exists() {
-        return Err(format!("Path does not exist: {}", path.display()));
+        return Err(DiskSenseError::NotFound {
+            path: path.to_string_lossy().to_string(),
+        });
     }
 
     let canonical_path = match canonicalize(path) {
         Ok(p) => p,
-        Err(e) => return Err(format!("Failed to canonicalize path: {}", e)),
+        Err(e) => return Err(DiskSenseError::from_io(e, path)),
     };
 
-    let name = canonical_path
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| canonical_path.to_string_lossy().to_string());
+    // Storing the preview makes the exact pass below report every refined
+    // node as a delta against it
+    if options.two_pass {
+        let preview = preview::instant_tree(&canonical_path, max_depth, &options);
+        scan_windows::emit_for(app, &preview.path, "scan-preview", &preview);
+        if let Ok(mut scans) = store.scans.lock() {
+            scans.insert(preview.path.clone(), preview);
+        }
+    }
 
     // Create progress tracking
     let processed_items = Arc::new(AtomicUsize::new(0));
@@ -86,241 +402,304 @@ async fn scan_directory(
 
     // Initial progress report
     emit_progress(
-        &app,
+        app,
         &canonical_path,
         processed_items.load(Ordering::SeqCst),
         total_items,
     );
 
-    // Perform the actual scan using new efficient algorithm
-    let result = if options.fast_mode {
-        // Fast scan - parallel processing with estimation for large dirs
-        fast_scan(
-            &canonical_path,
-            max_depth,
-            &app,
-            &processed_items,
-            total_items,
-            &options,
-        )
-    } else {
-        // Comprehensive scan - accurate sizes but slower
-        comprehensive_scan(
-            &canonical_path,
-            max_depth,
-            &app,
-            &processed_items,
-            total_items,
-            &options,
-        )
+    let dir_reads = DirReadLimiter::new(options.max_concurrent_dir_reads);
+    let scan_log = ScanLog::create(app);
+    let checkpoint = ScanCheckpoint::new(app, &canonical_path, max_depth, &options, resumed);
+    scan_log.info(format_args!(
+        "Scanning {} (depth {}, {:?})",
+        canonical_path.display(),
+        max_depth,
+        options
+    ));
+    let started = Instant::now();
+
+    let ctx = ScanContext {
+        app,
+        processed_items: &processed_items,
+        total_items,
+        options: &options,
+        dir_reads: &dir_reads,
+        log: &scan_log,
+        checkpoint: &checkpoint,
+        deadline: options
+            .timeout_seconds
+            .map(|secs| started + Duration::from_secs(secs)),
+        stall_timeout: options.stall_timeout_seconds.map(Duration::from_secs),
+        skipped: Mutex::new(Vec::new()),
+        ignore_rules: options
+            .respect_ignore_files
+            .then(|| IgnoreRules::new(&canonical_path)),
+        partial_sizes: PartialSizeTracker::new(&canonical_path),
     };
 
-    // Final progress report
-    emit_progress(&app, &canonical_path, total_items, total_items);
-
-    Ok(result)
-}
-
-// Helper function to emit progress updates
-fn emit_progress(app: &tauri::AppHandle, path: &Path, processed: usize, total: usize) {
-    let percent = if total > 0 {
-        (processed as f32 / total as f32) * 100.0
-    } else {
-        0.0
+    // Perform the actual scan using new efficient algorithm
+    let run_scan = || {
+        if options.fast_mode {
+            // Fast scan - parallel processing with estimation for large dirs
+            scan_queue::fast_scan(&canonical_path, max_depth, &ctx)
+        } else {
+            // Comprehensive scan - accurate sizes but slower
+            comprehensive_scan(&canonical_path, max_depth, &ctx)
+        }
     };
 
-    let progress = ScanProgress {
-        current_path: path.to_string_lossy().to_string(),
-        processed_items: processed,
-        total_items: total,
-        percent,
+    // Each scan gets its own pool so its parallelism can be tuned to the device;
+    // background scans default to a small pool of low-priority threads, others to
+    // what benchmark_drive found suits the drive
+    let benchmarked = app
+        .try_state::<benchmark::ScanThreads>()
+        .and_then(|threads| threads.for_path(&canonical_path));
+    let num_threads = match options.max_threads {
+        Some(threads) => threads,
+        None if options.background_priority => BACKGROUND_SCAN_THREADS,
+        None => benchmarked.unwrap_or(0), // 0 lets rayon pick one thread per CPU
     };
+    let background_priority = options.background_priority;
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .thread_name(|index| format!("disksense-scan-{}", index))
+        .start_handler(move |_| {
+            if background_priority {
+                priority::lower_current_thread_priority();
+            }
+        })
+        .build()
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to start scan threads: {}", e)))?;
+
+    let mut result = pool.install(run_scan);
+    checkpoint.finish();
+
+    // Report directories that were left out because of the timeout or a stall
+    let skipped = std::mem::take(&mut *ctx.skipped.lock().unwrap_or_else(|e| e.into_inner()));
+    if !skipped.is_empty() {
+        scan_windows::emit_for(
+            app,
+            &canonical_path.to_string_lossy(),
+            "scan-skipped",
+            &skipped,
+        );
+    }
+    scan_log.info(format_args!(
+        "Finished in {:.1}s: {} items, {} bytes",
+        started.elapsed().as_secs_f64(),
+        processed_items.load(Ordering::SeqCst),
+        result.size
+    ));
 
-    let _ = app.emit("scan-progress", &progress);
-}
+    metrics::record_scan(
+        app,
+        &result.path,
+        result.size,
+        processed_items.load(Ordering::SeqCst),
+        started.elapsed(),
+    );
+    hooks::run_hooks(
+        app,
+        hooks::HookEvent::ScanCompleted,
+        &result.path,
+        &serde_json::json!({
+            "root": result.path,
+            "total_size": result.size,
+            "items": processed_items.load(Ordering::SeqCst),
+            "duration_seconds": started.elapsed().as_secs_f64(),
+            "skipped": skipped.len(),
+        }),
+    );
 
-// Fast scan uses parallel processing and estimates sizes for large directories
-fn fast_scan(
-    dir_path: &Path,
-    max_depth: usize,
-    app: &tauri::AppHandle,
-    processed_items: &Arc<AtomicUsize>,
-    total_items: usize,
-    options: &ScanOptions,
-) -> DiskItem {
-    let mut root = DiskItem {
-        name: dir_path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| dir_path.to_string_lossy().to_string()),
-        path: dir_path.to_string_lossy().to_string(),
-        size: 0,
-        is_dir: true,
-        children: Some(Vec::new()),
-    };
+    // Label game installs so they stand out in the tree; git repositories were
+    // flagged while their folders were listed
+    games::mark_games(&mut result);
+    known_folders::mark_known_folders(&mut result);
+    if let Some(min_size) = options.expand_archives_over {
+        archive::expand_archives(&mut result, min_size);
+    }
+    delta::assign_ids(&mut result);
+    sparse::sum_allocated(&mut result);
+
+    // Keep the result so reports and exports can be generated from it later;
+    // on a rescan, tell the front-end what changed so it can patch its tree
+    if let Ok(mut scans) = store.scans.lock() {
+        if let Some(previous) = scans.get(&result.path) {
+            delta::carry_ids(previous, &mut result);
+        }
+        if let Some(previous) = scans.insert(result.path.clone(), result.clone()) {
+            let changes = delta::diff(&result.path, &previous, &result);
+            if !changes.is_empty() {
+                scan_windows::emit_for(app, &result.path, "scan-delta", &changes);
+            }
+        }
+    }
 
-    // Process all entries in the directory
-    if let Ok(entries) = std::fs::read_dir(dir_path) {
-        let entries: Vec<_> = entries.filter_map(Result::ok).collect();
+    session::record_scan(app, &result.path, max_depth, &options);
+    change_recorder::record_scan(app, &result.path, max_depth, &options);
 
-        // Extract file entries first (quick to process)
-        let mut children: Vec<DiskItem> = entries
-            .iter()
-            .filter_map(|entry| {
-                let path = entry.path();
-                let name = entry.file_name().to_string_lossy().to_string();
+    // Final progress report
+    emit_progress(app, &canonical_path, total_items, total_items);
 
-                // Skip hidden files if configured
-                if options.skip_hidden && name.starts_with(".") {
-                    return None;
-                }
+    // Only the returned payload is grouped; the stored tree keeps every node
+    if let Some(percent) = options.group_below_percent.filter(|p| *p > 0.0) {
+        grouping::group_small_children(&mut result, percent);
+    }
 
-                if path.is_file() {
-                    // Update progress
-                    let current = processed_items.fetch_add(1, Ordering::SeqCst) + 1;
-                    if current % 100 == 0 || current < 100 {
-                        emit_progress(app, &path, current, total_items);
-                    }
+    Ok(result)
+}
 
-                    // Get file size
-                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+// Shared state handed down through a scan
+struct ScanContext<'a> {
+    app: &'a tauri::AppHandle,
+    processed_items: &'a Arc<AtomicUsize>,
+    total_items: usize,
+    options: &'a ScanOptions,
+    dir_reads: &'a DirReadLimiter,
+    log: &'a ScanLog,
+    checkpoint: &'a ScanCheckpoint,
+    deadline: Option<Instant>,
+    stall_timeout: Option<Duration>,
+    skipped: Mutex<Vec<SkippedPath>>,
+    ignore_rules: Option<IgnoreRules>,
+    partial_sizes: PartialSizeTracker,
+}
 
-                    Some(DiskItem {
-                        name,
-                        path: path.to_string_lossy().to_string(),
-                        size,
-                        is_dir: false,
-                        children: None,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
+impl ScanContext<'_> {
+    // Record a directory that was left out of the tree and why
+    fn skip(&self, dir_path: &Path, reason: &str) -> DiskItem {
+        self.log.skipped(dir_path, reason);
+        self.skipped
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(SkippedPath {
+                path: dir_path.to_string_lossy().to_string(),
+                reason: reason.to_string(),
+            });
+
+        DiskItem {
+            name: format!(
+                "{} ({})",
+                dir_path.file_name().unwrap_or_default().to_string_lossy(),
+                reason
+            ),
+            path: dir_path.to_string_lossy().to_string(),
+            size: 0,
+            is_dir: true,
+            children: None,
+            game: None,
+            git_repo: false,
+            id: String::new(),
+            allocated_size: None,
+            sparse: false,
+            link_target: None,
+            hard_links: None,
+            in_archive: false,
+            raw_path: os_path::encode(dir_path),
+            estimated: false,
+            hidden: false,
+            known_folder: None,
+            allocated_partial: false,
+        }
+    }
 
-        // Then process directories in parallel
-        let dirs: Vec<_> = entries
-            .iter()
-            .filter(|entry| entry.path().is_dir())
-            .collect();
-
-        // Skip system directories that cause permission issues
-        #[cfg(target_os = "windows")]
-        let dirs: Vec<_> = dirs
-            .into_iter()
-            .filter(|entry| {
-                let path = entry.path().to_string_lossy().to_lowercase();
-                !SKIP_DIRS.iter().any(|skip| path.starts_with(skip))
-            })
-            .collect();
-
-        // Process directories in parallel if we're not at max depth
-        if max_depth > 0 {
-            let dir_items: Vec<DiskItem> = if dirs.len() > 0 {
-                // Use Rayon's parallel iterator for directories
-                dirs.par_iter()
-                    .filter_map(|entry| {
-                        let path = entry.path();
-                        let name = entry.file_name().to_string_lossy().to_string();
-
-                        // Skip hidden directories if configured
-                        if options.skip_hidden && name.starts_with(".") {
-                            return None;
-                        }
-
-                        // Update progress
-                        let current = processed_items.fetch_add(1, Ordering::SeqCst) + 1;
-                        if current % 20 == 0 || current < 100 {
-                            emit_progress(app, &path, current, total_items);
-                        }
-
-                        // For large directories with many files, we might skip full scan in fast mode
-                        let skip_full_scan = options.fast_mode && is_large_directory(&path);
-
-                        if skip_full_scan && max_depth > 1 {
-                            // For large directories, just estimate size rather than scan fully
-                            let size = estimate_dir_size(&path);
-                            Some(DiskItem {
-                                name,
-                                path: path.to_string_lossy().to_string(),
-                                size,
-                                is_dir: true,
-                                children: Some(vec![]), // Empty children since we're skipping full scan
-                            })
-                        } else {
-                            // Regular recursive scan for normal directories
-                            Some(fast_scan(
-                                &path,
-                                max_depth - 1,
-                                app,
-                                processed_items,
-                                total_items,
-                                options,
-                            ))
-                        }
-                    })
-                    .collect()
-            } else {
-                Vec::new()
-            };
+    // Whether .gitignore/.disksenseignore rules exclude this path from the scan
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.ignore_rules
+            .as_ref()
+            .is_some_and(|rules| rules.is_ignored(path, is_dir))
+    }
 
-            // Combine file and directory results
-            children.extend(dir_items);
-        } else {
-            // At max depth, just add directories as leaves without children
-            for entry in dirs {
-                let path = entry.path();
-                let name = entry.file_name().to_string_lossy().to_string();
-
-                // Skip hidden directories if configured
-                if options.skip_hidden && name.starts_with(".") {
-                    continue;
-                }
+    fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
 
-                // Update progress
-                let current = processed_items.fetch_add(1, Ordering::SeqCst) + 1;
-                if current % 20 == 0 || current < 100 {
-                    emit_progress(app, &path, current, total_items);
-                }
+    // Run a directory read on a helper thread so a hung mount can't block the scan.
+    // Returns None if it doesn't answer in time; the helper is left to finish on its own.
+    fn read_with_stall_guard<T: Send + 'static>(
+        &self,
+        read: impl FnOnce() -> T + Send + 'static,
+    ) -> Option<T> {
+        let stall_timeout = match self.stall_timeout {
+            Some(timeout) => timeout,
+            None => return Some(read()),
+        };
 
-                // Estimate size without recursing
-                let size = estimate_dir_size(&path);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(read());
+        });
+        receiver.recv_timeout(stall_timeout).ok()
+    }
+}
 
-                children.push(DiskItem {
-                    name,
-                    path: path.to_string_lossy().to_string(),
-                    size,
-                    is_dir: true,
-                    children: Some(Vec::new()),
-                });
-            }
-        }
+// Caps how many directory reads run at once across all scan threads
+struct DirReadLimiter {
+    available: Mutex<usize>,
+    released: Condvar,
+}
 
-        // Sort children by size (largest first)
-        children.sort_by(|a, b| b.size.cmp(&a.size));
+struct DirReadPermit<'a> {
+    limiter: &'a DirReadLimiter,
+}
 
-        // Set children and calculate root size as sum of children
-        root.children = Some(children);
-        if let Some(children) = &root.children {
-            root.size = children.iter().map(|child| child.size).sum();
+impl DirReadLimiter {
+    fn new(limit: Option<usize>) -> Self {
+        DirReadLimiter {
+            available: Mutex::new(limit.unwrap_or(usize::MAX).max(1)),
+            released: Condvar::new(),
         }
     }
 
-    root
+    // Block until a read slot is free; the slot is returned when the permit drops
+    fn acquire(&self) -> DirReadPermit<'_> {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+        DirReadPermit { limiter: self }
+    }
+}
+
+impl Drop for DirReadPermit<'_> {
+    fn drop(&mut self) {
+        let mut available = self
+            .limiter
+            .available
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *available += 1;
+        self.limiter.released.notify_one();
+    }
+}
+
+// Helper function to emit progress updates
+fn emit_progress(app: &tauri::AppHandle, path: &Path, processed: usize, total: usize) {
+    let percent = if total > 0 {
+        (processed as f32 / total as f32) * 100.0
+    } else {
+        0.0
+    };
+
+    let progress = ScanProgress {
+        current_path: path.to_string_lossy().to_string(),
+        processed_items: processed,
+        total_items: total,
+        percent,
+    };
+
+    scan_windows::emit_for(app, &progress.current_path, "scan-progress", &progress);
 }
 
 // Check if a directory is "large" (contains many files)
 fn is_large_directory(path: &Path) -> bool {
-    let mut count = 0;
-    if let Ok(entries) = std::fs::read_dir(path) {
-        for _ in entries.take(1000) {
-            count += 1;
-            if count >= 1000 {
-                return true;
-            }
-        }
-    }
-    false
+    dir_count::count_entries(path, 1000).is_ok_and(|count| count >= 1000)
 }
 
 // Quickly estimate directory size (faster than full scan)
@@ -341,23 +720,17 @@ fn estimate_dir_size(path: &Path) -> u64 {
 
     if let Ok(entries) = std::fs::read_dir(path) {
         // First pass: count entries and take size samples
-        for entry_result in entries.take(100) {
-            if let Ok(entry) = entry_result {
-                count += 1;
-                if let Ok(metadata) = entry.metadata() {
-                    size += metadata.len();
-                    sample_count += 1;
-                }
+        for entry in entries.take(100).flatten() {
+            count += 1;
+            if let Ok(metadata) = entry.metadata() {
+                size += metadata.len();
+                sample_count += 1;
             }
         }
     }
 
     // Try to count all entries but limit to prevent slow performance
-    let total_count = if let Ok(entries) = std::fs::read_dir(path) {
-        entries.count().min(10000)
-    } else {
-        count
-    };
+    let total_count = dir_count::count_entries(path, 10000).unwrap_or(count);
 
     // If we have samples, extrapolate total size
     if sample_count > 0 && total_count > sample_count {
@@ -370,20 +743,24 @@ fn estimate_dir_size(path: &Path) -> u64 {
 }
 
 // Comprehensive scan - more accurate but slower
-fn comprehensive_scan(
-    dir_path: &Path,
-    max_depth: usize,
-    app: &tauri::AppHandle,
-    processed_items: &Arc<AtomicUsize>,
-    total_items: usize,
-    options: &ScanOptions,
-) -> DiskItem {
-    let path_str = dir_path.to_string_lossy().to_lowercase();
+fn comprehensive_scan(dir_path: &Path, max_depth: usize, ctx: &ScanContext) -> DiskItem {
+    // Reuse the subtree if an interrupted scan already finished this directory
+    if let Some(item) = ctx.checkpoint.resumed(dir_path) {
+        ctx.partial_sizes.add(ctx.app, dir_path, item.size);
+        return item;
+    }
+
+    if ctx.timed_out() {
+        return ctx.skip(dir_path, "timed out");
+    }
 
     // Skip certain system directories that typically cause "Access denied" errors
     #[cfg(target_os = "windows")]
+    let path_str = dir_path.to_string_lossy().to_lowercase();
+    #[cfg(target_os = "windows")]
     for skip_dir in &SKIP_DIRS {
         if path_str.starts_with(skip_dir) {
+            ctx.log.skipped(dir_path, "system directory");
             return DiskItem {
                 name: format!(
                     "{} (access denied)",
@@ -393,6 +770,19 @@ fn comprehensive_scan(
                 size: 0,
                 is_dir: true,
                 children: None,
+                game: None,
+                git_repo: false,
+                id: String::new(),
+                allocated_size: None,
+                sparse: false,
+                link_target: None,
+                hard_links: None,
+                in_archive: false,
+                raw_path: os_path::encode(dir_path),
+                estimated: false,
+                hidden: false,
+                known_folder: None,
+                allocated_partial: false,
             };
         }
     }
@@ -406,23 +796,51 @@ fn comprehensive_scan(
         size: 0,
         is_dir: true,
         children: Some(Vec::new()),
+        game: None,
+        git_repo: false,
+        id: String::new(),
+        allocated_size: None,
+        sparse: false,
+        link_target: None,
+        hard_links: None,
+        in_archive: false,
+        raw_path: os_path::encode(dir_path),
+        estimated: false,
+        hidden: false,
+        known_folder: None,
+        allocated_partial: false,
     };
 
     // Update progress
-    let current = processed_items.fetch_add(1, Ordering::SeqCst) + 1;
+    let current = ctx.processed_items.fetch_add(1, Ordering::SeqCst) + 1;
     if current % 20 == 0 || current < 100 {
-        emit_progress(app, dir_path, current, total_items);
+        emit_progress(ctx.app, dir_path, current, ctx.total_items);
     }
 
-    // Create a walkdir iterator with error handling
-    let walker = WalkDir::new(dir_path)
-        .min_depth(1)
-        .max_depth(1)
-        .follow_links(false);
-
     let mut children = Vec::new();
 
-    for entry_result in walker {
+    // Read the listing up front so the read slot is released before recursing
+    let entries: Option<Vec<_>> = {
+        let _permit = ctx.dir_reads.acquire();
+        let dir = dir_path.to_path_buf();
+        ctx.read_with_stall_guard(move || {
+            // Create a walkdir iterator with error handling
+            WalkDir::new(dir)
+                .min_depth(1)
+                .max_depth(1)
+                .follow_links(false)
+                .into_iter()
+                .collect()
+        })
+    };
+    let entries = match entries {
+        Some(entries) => entries,
+        None => return ctx.skip(dir_path, "unresponsive"),
+    };
+
+    let mut own_bytes = 0;
+    let mut hidden = HiddenTally::default();
+    for entry_result in entries {
         let entry = match entry_result {
             Ok(entry) => entry,
             Err(e) => {
@@ -432,6 +850,7 @@ fn comprehensive_scan(
                 } else {
                     error!("Error accessing entry: {}", e);
                 }
+                ctx.log.error(e.path().unwrap_or(dir_path), &e);
                 continue;
             }
         };
@@ -440,26 +859,47 @@ fn comprehensive_scan(
         let name = entry.file_name().to_string_lossy().to_string();
         let is_dir = entry.file_type().is_dir();
 
-        // Skip hidden files/dirs if configured
-        if options.skip_hidden && name.starts_with(".") {
+        // Checked before hidden entries are set aside, since .git is one
+        if is_dir && name == ".git" {
+            root.git_repo = true;
+        }
+
+        // Hidden files/dirs are left out of the listing if configured, but
+        // still counted
+        if ctx.options.skip_hidden && visibility::is_hidden(&name, path) {
+            let _permit = ctx.dir_reads.acquire();
+            own_bytes += hidden.add(path);
             continue;
         }
 
+        if ctx.is_ignored(path, is_dir) {
+            continue;
+        }
+
+        let file_metadata = if is_dir { None } else { path.metadata().ok() };
         let size = if is_dir {
             // For directories, calculate size based on accurate method for comprehensive scan
+            let _permit = ctx.dir_reads.acquire();
             match get_size(path) {
                 Ok(size) => size,
-                Err(_) => {
+                Err(e) => {
                     // If we can't get the size, set it to 0 and continue
+                    ctx.log.error(path, e);
                     0
                 }
             }
         } else {
-            match path.metadata() {
-                Ok(metadata) => metadata.len(),
-                Err(_) => 0,
-            }
+            file_metadata.as_ref().map_or(0, |m| m.len())
+        };
+        let (allocated_size, sparse) = match &file_metadata {
+            Some(metadata) => sparse::allocation(path, metadata),
+            None => (None, false),
         };
+        let link_target = entry
+            .path_is_symlink()
+            .then(|| properties::link_target(path))
+            .flatten();
+        let hard_links = file_metadata.as_ref().and_then(scan_link_count);
 
         let mut child = DiskItem {
             name,
@@ -467,31 +907,43 @@ fn comprehensive_scan(
             size,
             is_dir,
             children: if is_dir { Some(Vec::new()) } else { None },
+            game: None,
+            git_repo: false,
+            id: String::new(),
+            allocated_size,
+            sparse,
+            link_target,
+            hard_links,
+            in_archive: false,
+            raw_path: os_path::encode(path),
+            estimated: false,
+            hidden: false,
+            known_folder: None,
+            allocated_partial: false,
         };
 
         // Update progress for this entry
-        let current = processed_items.fetch_add(1, Ordering::SeqCst) + 1;
+        let current = ctx.processed_items.fetch_add(1, Ordering::SeqCst) + 1;
         if current % 20 == 0 || current < 100 {
-            emit_progress(app, path, current, total_items);
+            emit_progress(ctx.app, path, current, ctx.total_items);
         }
 
         if is_dir && max_depth > 0 {
             // Recursively scan subdirectory
-            child = comprehensive_scan(
-                path,
-                max_depth - 1,
-                app,
-                processed_items,
-                total_items,
-                options,
-            );
+            child = comprehensive_scan(path, max_depth - 1, ctx);
+        } else {
+            // Subfolders count their own bytes towards the partial sizes
+            own_bytes += child.size;
         }
 
         children.push(child);
     }
 
+    ctx.partial_sizes.add(ctx.app, dir_path, own_bytes);
+    children.extend(hidden.into_node(dir_path));
+
     // Sort children by size (largest first)
-    children.sort_by(|a, b| b.size.cmp(&a.size));
+    children.sort_by_key(|item| std::cmp::Reverse(item.size));
 
     // Set children and calculate root size as sum of children
     if !children.is_empty() {
@@ -499,9 +951,26 @@ fn comprehensive_scan(
         root.children = Some(children);
     }
 
+    ctx.checkpoint.record(&root);
     root
 }
 
+// Hard link count where the listing already provides it; on Windows it would
+// cost opening every file, so it is left to get_item_properties
+fn scan_link_count(metadata: &std::fs::Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(metadata.nlink())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
 // Function to estimate the total number of items to scan
 fn estimate_item_count(path: &Path, max_depth: usize) -> usize {
     if !path.is_dir() {
@@ -517,16 +986,14 @@ fn estimate_item_count(path: &Path, max_depth: usize) -> usize {
 
     // Only count immediate children for estimation to avoid too much overhead
     if let Ok(entries) = path.read_dir() {
-        for entry_result in entries {
-            if let Ok(entry) = entry_result {
-                count += 1;
-
-                // Only recurse for a limited depth to keep estimation fast
-                if max_depth > 0 && entry.path().is_dir() {
-                    // Avoid estimating too deeply to keep it responsive
-                    let subdepth = if max_depth > 2 { 0 } else { max_depth - 1 };
-                    count += estimate_item_count(&entry.path(), subdepth);
-                }
+        for entry in entries.flatten() {
+            count += 1;
+
+            // Only recurse for a limited depth to keep estimation fast
+            if max_depth > 0 && entry.path().is_dir() {
+                // Avoid estimating too deeply to keep it responsive
+                let subdepth = if max_depth > 2 { 0 } else { max_depth - 1 };
+                count += estimate_item_count(&entry.path(), subdepth);
             }
         }
     }
@@ -534,44 +1001,164 @@ fn estimate_item_count(path: &Path, max_depth: usize) -> usize {
     count
 }
 
-#[derive(Debug, Serialize)]
+// Current user's home directory, used to locate per-user application data
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let var = "USERPROFILE";
+    #[cfg(not(target_os = "windows"))]
+    let var = "HOME";
+
+    std::env::var_os(var).map(PathBuf::from)
+}
+
+// Format a byte count the same way the front-end's formatBytes does
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 7] = ["Bytes", "KB", "MB", "GB", "TB", "PB", "EB"];
+
+    if bytes == 0 {
+        return "0 Bytes".to_string();
+    }
+
+    let exponent = ((bytes as f64).ln() / 1024f64.ln()).floor() as usize;
+    let exponent = exponent.min(UNITS.len() - 1);
+    let value = bytes as f64 / 1024f64.powi(exponent as i32);
+
+    // Match parseFloat(toFixed(2)), which drops trailing zeros
+    let formatted = format!("{:.2}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    format!("{} {}", formatted, UNITS[exponent])
+}
+
+// Parse human-readable sizes such as "1.2GB", "56.0M" or "512.3MB (45%)"
+pub(crate) fn parse_human_size(value: &str, base: f64) -> u64 {
+    let value = value.split('(').next().unwrap_or("").trim();
+    let split_at = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split_at);
+
+    let number: f64 = match number.parse() {
+        Ok(n) => n,
+        Err(_) => return 0,
+    };
+
+    let exponent = match unit.trim().to_uppercase().chars().next() {
+        Some('K') => 1,
+        Some('M') => 2,
+        Some('G') => 3,
+        Some('T') => 4,
+        Some('P') => 5,
+        _ => 0,
+    };
+
+    (number * base.powi(exponent)) as u64
+}
+
+#[derive(Debug, Serialize, Clone)]
 pub struct DriveInfo {
     name: String,
     mount_point: String,
     total_space: u64,
     available_space: u64,
     used_space: u64,
+    purgeable_space: Option<u64>,
+    file_system: String,
+    label: Option<String>,
+    serial: Option<String>,
+    encryption_status: EncryptionStatus,
+    // Explains why used + free doesn't add up to what a scan finds
+    reserved: reserved_space::ReservedSpace,
 }
 
 #[command]
-async fn get_drive_info() -> Result<Vec<DriveInfo>, String> {
-    let mut drives = Disks::new_with_refreshed_list();
+async fn get_drive_info() -> Result<Vec<DriveInfo>, DiskSenseError> {
+    Ok(collect_drive_info())
+}
+
+pub(crate) fn collect_drive_info() -> Vec<DriveInfo> {
+    let drives = Disks::new_with_refreshed_list();
     let mut drive_infos = Vec::new();
 
     for disk in drives.iter() {
+        let mount_point = disk.mount_point().to_string_lossy().to_string();
+
+        let (available_space, purgeable_space) = drive_space(disk);
+
+        let name = disk.name().to_string_lossy().to_string();
+        let details = volume::volume_details(&name, disk.mount_point());
+
         drive_infos.push(DriveInfo {
-            name: disk.name().to_string_lossy().to_string(),
-            mount_point: disk.mount_point().to_string_lossy().to_string(),
+            name,
+            mount_point,
             total_space: disk.total_space(),
-            available_space: disk.available_space(),
-            used_space: disk.total_space() - disk.available_space(),
+            available_space,
+            used_space: disk.total_space().saturating_sub(available_space),
+            purgeable_space,
+            file_system: disk.file_system().to_string_lossy().to_string(),
+            label: details.label,
+            serial: details.serial,
+            encryption_status: details.encryption_status,
+            reserved: reserved_space::reserved_space(disk.mount_point()),
         });
     }
 
-    Ok(drive_infos)
+    drive_infos
+}
+
+// Available and purgeable bytes of a drive. On macOS, purgeable space counts as
+// available so the numbers match Finder.
+fn drive_space(disk: &sysinfo::Disk) -> (u64, Option<u64>) {
+    let free_space = disk.available_space();
+    let available_space = macos::finder_available_space(&disk.mount_point().to_string_lossy())
+        .map(|space| space.max(free_space))
+        .unwrap_or(free_space);
+    let purgeable_space = if cfg!(target_os = "macos") {
+        Some(available_space - free_space)
+    } else {
+        None
+    };
+
+    (available_space, purgeable_space)
+}
+
+// Refresh only the space figures of already collected drives; the slower volume
+// details are kept. Returns false when drives were added or removed.
+pub(crate) fn refresh_drive_space(drive_infos: &mut [DriveInfo]) -> bool {
+    let drives = Disks::new_with_refreshed_list();
+    if drives.list().len() != drive_infos.len() {
+        return false;
+    }
+
+    for info in drive_infos.iter_mut() {
+        let disk = match drives
+            .iter()
+            .find(|disk| disk.mount_point() == Path::new(&info.mount_point))
+        {
+            Some(disk) => disk,
+            None => return false,
+        };
+
+        let (available_space, purgeable_space) = drive_space(disk);
+        info.total_space = disk.total_space();
+        info.available_space = available_space;
+        info.used_space = disk.total_space().saturating_sub(available_space);
+        info.purgeable_space = purgeable_space;
+    }
+
+    true
 }
 
 #[command]
 async fn show_file_context_menu(
     app: AppHandle,
     path: String,
-    file_name: String,
+    _file_name: String,
     is_dir: bool,
     window_label: String,
-) -> Result<(), String> {
-    let window = app
-        .get_webview_window(&window_label)
-        .ok_or_else(|| "Window not found".to_string())?;
+) -> Result<(), DiskSenseError> {
+    app.get_webview_window(&window_label).ok_or_else(|| {
+        DiskSenseError::InvalidInput(format!("Window not found: {}", window_label))
+    })?;
 
     // Create menu items
     let open_item = MenuItemBuilder::with_id(
@@ -579,24 +1166,24 @@ async fn show_file_context_menu(
         format!("Open {}", if is_dir { "Folder" } else { "File" }),
     )
     .build(&app)
-    .map_err(|e| format!("Failed to build menu: {}", e))?;
+    .map_err(|e| DiskSenseError::Failed(format!("Failed to build menu: {}", e)))?;
 
     let delete_item = MenuItemBuilder::with_id(
         "delete",
         format!("Delete {}", if is_dir { "Folder" } else { "File" }),
     )
     .build(&app)
-    .map_err(|e| format!("Failed to build menu: {}", e))?;
+    .map_err(|e| DiskSenseError::Failed(format!("Failed to build menu: {}", e)))?;
 
     let properties_item = MenuItemBuilder::with_id("properties", "Properties")
         .build(&app)
-        .map_err(|e| format!("Failed to build menu: {}", e))?;
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to build menu: {}", e)))?;
 
-    // Build the menu
-    let menu = MenuBuilder::new(&app)
+    // Build the menu; it is not shown yet
+    let _menu = MenuBuilder::new(&app)
         .items(&[&open_item, &delete_item, &properties_item])
         .build()
-        .map_err(|e| format!("Failed to build menu: {}", e))?;
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to build menu: {}", e)))?;
 
     // Clone path to use in the closure
     let path_clone = path.clone();
@@ -643,33 +1230,166 @@ async fn show_file_context_menu(
 }
 
 #[command]
-async fn open_path(path: String) -> Result<(), String> {
-    match tauri_plugin_opener::open_path(path, None::<&str>) {
+async fn open_path(path: String) -> Result<(), DiskSenseError> {
+    let target = os_path::decode(&path);
+    if !target.exists() {
+        return Err(DiskSenseError::NotFound { path });
+    }
+
+    match tauri_plugin_opener::open_path(target, None::<&str>) {
         Ok(_) => Ok(()),
-        Err(e) => Err(format!("Failed to open path: {}", e)),
+        Err(e) => Err(DiskSenseError::Failed(format!(
+            "Failed to open path: {}",
+            e
+        ))),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[command]
-async fn delete_path(path: String) -> Result<(), String> {
-    let path = Path::new(&path);
+async fn delete_path(
+    read_only: tauri::State<'_, read_only::ReadOnlyMode>,
+    app: tauri::AppHandle,
+    cancel: tauri::State<'_, deletion::DeleteCancel>,
+    protected: tauri::State<'_, ProtectedPaths>,
+    store: tauri::State<'_, ScanStore>,
+    path: String,
+    force: Option<bool>,
+    staged: Option<bool>,
+    action: Option<String>,
+) -> Result<deletion::DeletionReport, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let result = delete_and_report(
+        &app,
+        cancel.reset(),
+        &protected,
+        &store,
+        &path,
+        force.unwrap_or(false),
+        staged.unwrap_or(false),
+    );
+    audit::record(
+        &app,
+        "delete_path",
+        action.as_deref(),
+        vec![path],
+        result.as_ref().map_or(0, |report| report.bytes_reclaimed()),
+        result
+            .as_ref()
+            .map_or_else(Some, deletion::DeletionReport::cancel_error),
+    );
+    result
+}
 
-    if path.is_dir() {
-        match std::fs::remove_dir_all(path) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to delete directory: {}", e)),
+// Delete several paths, carrying on past any that fail, and return one combined
+// report. A cancel stops the batch and returns what was removed up to then.
+#[allow(clippy::too_many_arguments)]
+#[command]
+async fn delete_paths(
+    read_only: tauri::State<'_, read_only::ReadOnlyMode>,
+    app: tauri::AppHandle,
+    cancel: tauri::State<'_, deletion::DeleteCancel>,
+    protected: tauri::State<'_, ProtectedPaths>,
+    store: tauri::State<'_, ScanStore>,
+    paths: Vec<String>,
+    force: Option<bool>,
+    staged: Option<bool>,
+    action: Option<String>,
+) -> Result<deletion::DeletionReport, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let cancelled = cancel.reset();
+    let mut report = deletion::DeletionReport::default();
+
+    for path in &paths {
+        match delete_and_report(
+            &app,
+            cancelled,
+            &protected,
+            &store,
+            path,
+            force.unwrap_or(false),
+            staged.unwrap_or(false),
+        ) {
+            Ok(deleted) => report.merge(deleted),
+            Err(e) => report.fail(e),
         }
-    } else {
-        match std::fs::remove_file(path) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to delete file: {}", e)),
+        if report.is_cancelled() {
+            break;
         }
     }
+
+    // A cancelled batch is recorded with what it deleted before the cancel
+    audit::record(
+        &app,
+        "delete_paths",
+        action.as_deref(),
+        paths,
+        report.bytes_reclaimed(),
+        report.cancel_error(),
+    );
+    Ok(report)
+}
+
+fn delete_and_report(
+    app: &tauri::AppHandle,
+    cancelled: &std::sync::atomic::AtomicBool,
+    protected: &ProtectedPaths,
+    store: &ScanStore,
+    path: &str,
+    force: bool,
+    staged: bool,
+) -> Result<deletion::DeletionReport, DiskSenseError> {
+    // `path` may be a raw_path for names that aren't valid Unicode
+    let target = &os_path::decode(path);
+    let tree_path = target.to_string_lossy();
+
+    // Refuse system folders, drive roots, scan roots and user-protected paths
+    protected::ensure_deletable(target, protected, store, force)?;
+
+    let report = if staged {
+        // Staged deletes are moved aside so they can be restored until purged
+        let is_dir = std::fs::symlink_metadata(target).is_ok_and(|m| m.is_dir());
+        staging::stage_path(target)?;
+        deletion::DeletionReport::staged(is_dir)
+    } else {
+        // Symlinks and junctions are removed themselves, never followed.
+        // Large trees report "delete-progress" and can be stopped with
+        // cancel_delete; what was removed before the cancel is patched below.
+        deletion::remove_path_with_progress(target, cancelled, |progress| {
+            let _ = app.emit("delete-progress", progress);
+        })?
+    };
+
+    // Patch stored scans and the front-end's tree, and let drive gauges refresh,
+    // without waiting for a rescan
+    for change in store.apply_deletion(&tree_path, report.bytes_reclaimed(), report.is_complete()) {
+        scan_windows::emit_for(app, &tree_path, "scan-delta", &change);
+    }
+    if report.bytes_reclaimed() > 0 {
+        let _ = app.emit("drive-space-changed", &tree_path);
+    }
+
+    Ok(report)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+    // A second launch, e.g. from the folder menu, hands its folder to the
+    // running app instead of opening another one
+    #[cfg(desktop)]
+    let builder =
+        builder.plugin(tauri_plugin_single_instance::init(
+            |app, args, cwd| match launch_args::parse(&args, Path::new(&cwd)) {
+                Ok(args) => launch_args::apply(app, args, false),
+                Err(e) => {
+                    log::error!("Ignoring arguments of a second launch: {}", e);
+                    external_open::focus_main_window(app);
+                }
+            },
+        ));
+
+    builder
         .plugin(tauri_plugin_shell::init())
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -679,17 +1399,198 @@ pub fn run() {
                         .build(),
                 )?;
             }
+            app.manage(ProtectedPaths::load(app.handle()));
+            app.manage(read_only::ReadOnlyMode::load(app.handle()));
+            app.manage(drive_watch::DriveWatcher::start(app.handle().clone()));
+            app.manage(change_recorder::ChangeRecorder::start(app.handle().clone()));
+            app.manage(drive_stats::DriveStatsTimer::default());
+            app.manage(budgets::DirBudgets::load(app.handle()));
+            budgets::start_checker(app.handle().clone());
+            app.manage(cleanup_rules::CleanupRules::load(app.handle()));
+            app.manage(hooks::Hooks::load(app.handle()));
+            app.manage(metrics::MetricsServer::from_env());
+            app.manage(tags::ItemTags::load(app.handle()));
+            app.manage(search::SavedSearches::load(app.handle()));
+            app.manage(hash_cache::HashCache::open(app.handle()));
+            app.manage(audit::AuditLog::open(app.handle()));
+            jobs::JobManager::start_worker(app.handle().clone());
+            // `disksense <path> --depth N --export out.json --exit`
+            let args: Vec<String> = std::env::args().collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            launch_args::apply(app.handle(), launch_args::parse_or_exit(&args, &cwd), true);
             Ok(())
         })
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
+        .manage(ScanStore::default())
+        .manage(process_io::ProcessIoMonitor::default())
+        .manage(deletion::DeleteCancel::default())
+        .manage(session::Session::default())
+        .manage(duplicates::DuplicateResults::default())
+        .manage(jobs::JobManager::default())
+        .manage(scan_windows::ScanWindows::default())
+        .manage(benchmark::ScanThreads::default())
+        .manage(external_open::ExternalOpen::default())
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                scan_windows::window_closed(window.app_handle(), window.label());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             scan_directory,
+            scan_windows::open_scan_window,
+            revalidate::revalidate_visible,
+            structure_scan::scan_structure,
+            known_folders::get_known_folders,
+            appdata::analyze_appdata,
+            electron_cache::find_electron_caches,
+            electron_cache::clean_electron_caches,
+            external_open::take_external_open,
+            external_open::register_shell_integration,
+            external_open::unregister_shell_integration,
             get_drive_info,
             open_path,
             delete_path,
-            show_file_context_menu
+            delete_paths,
+            show_file_context_menu,
+            docker::analyze_docker,
+            docker::prune_docker,
+            games::get_game_sizes,
+            git::get_git_repo_info,
+            git::git_gc,
+            mail::get_mail_stores,
+            package_cache::get_package_cache_sizes,
+            package_cache::clean_package_cache,
+            linux_logs::analyze_linux_logs,
+            linux_logs::vacuum_journal,
+            linux_packages::get_sandboxed_package_sizes,
+            linux_packages::remove_snap_revision,
+            linux_packages::remove_unused_flatpaks,
+            macos::get_local_snapshots,
+            macos::delete_local_snapshot,
+            macos::thin_local_snapshots,
+            macos::quick_look,
+            report::export_report_html,
+            treemap_image::export_treemap_image,
+            benchmark::benchmark_drive,
+            in_use::check_in_use,
+            protected::get_protected_paths,
+            protected::add_protected_path,
+            protected::remove_protected_path,
+            staging::list_staged,
+            staging::purge_staged,
+            staging::restore_staged,
+            scan_log::export_diagnostics,
+            checkpoint::resume_last_scan,
+            extension_colors::get_extension_colors,
+            extension_stats::get_extension_stats,
+            tree_stream::stream_scan_tree,
+            scan_validation::validate_scan_target,
+            properties::get_item_properties,
+            drive_watch::rescan_drives,
+            eject::eject_drive,
+            drive_stats::subscribe_drive_stats,
+            drive_stats::unsubscribe_drive_stats,
+            budgets::set_dir_budget,
+            budgets::remove_dir_budget,
+            budgets::list_dir_budgets,
+            budgets::check_dir_budgets,
+            baselines::set_baseline,
+            baselines::compare_to_baseline,
+            baselines::remove_baseline,
+            baselines::list_baselines,
+            cleanup_rules::add_rule,
+            cleanup_rules::list_rules,
+            cleanup_rules::remove_rule,
+            cleanup_rules::run_rules,
+            config::export_config,
+            config::import_config,
+            hooks::get_hook_settings,
+            hooks::set_hooks_enabled,
+            hooks::add_hook,
+            hooks::remove_hook,
+            export::export_selection,
+            manifest::create_manifest,
+            manifest::verify_manifest,
+            archive::inspect_archive,
+            archive::expand_archive,
+            vm_disk::analyze_vm_disk,
+            vm_disk::compact_vhd,
+            media::analyze_media,
+            photos::analyze_photos,
+            duplicates::find_duplicates,
+            duplicates::find_duplicates_across,
+            duplicates::get_duplicate_overlay,
+            reflink::dedupe_reflink,
+            fragmentation::analyze_fragmentation,
+            process_io::get_process_disk_activity,
+            bucket::scan_bucket,
+            sftp::scan_remote_sftp,
+            mtp::list_mtp_devices,
+            mtp::scan_mtp_device,
+            mobile_storage::list_storage_volumes,
+            metrics::start_metrics_server,
+            metrics::stop_metrics_server,
+            deletion::cancel_delete,
+            children::get_children_page,
+            cleanup_plan::get_cleanup_plan,
+            cleanup_plan::run_cleanup_step,
+            tags::tag_item,
+            tags::untag_item,
+            tags::set_item_note,
+            tags::get_item_annotation,
+            tags::get_items_by_tag,
+            tags::list_tags,
+            search::query_scan,
+            search::save_search,
+            search::list_searches,
+            search::remove_search,
+            search::run_saved_search,
+            session::set_session_view,
+            session::close_session_scan,
+            session::restore_session,
+            jobs::enqueue_job,
+            jobs::list_jobs,
+            jobs::cancel_job,
+            wildcard::delete_matching,
+            offload::archive_item,
+            windows_cleanup::get_windows_cleanup_categories,
+            windows_cleanup::run_windows_cleanup,
+            vss::get_shadow_storage,
+            vss::resize_shadow_storage,
+            vss::delete_oldest_shadow_copy,
+            component_store::analyze_component_store,
+            component_store::clean_component_store,
+            system_files::get_system_file_sizes,
+            system_files::disable_hibernation,
+            system_files::set_pagefile_size,
+            read_only::get_read_only_mode,
+            read_only::set_read_only_mode,
+            audit::get_audit_log,
+            user_profiles::get_user_profiles_usage,
+            tree_hash::hash_tree,
+            copy::copy_path,
+            clipboard::clipboard_copy_files,
+            clipboard::clipboard_cut_files
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| match event {
+            // Remember open scans so the next launch can restore them
+            tauri::RunEvent::Exit => session::save_on_exit(app),
+            // Folders opened with DiskSense from Finder
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                if let Some(path) = urls.iter().find_map(|url| url.to_file_path().ok()) {
+                    external_open::handle_external_open(
+                        app,
+                        external_open::ExternalScan {
+                            path: path.to_string_lossy().to_string(),
+                            depth: None,
+                        },
+                    );
+                }
+            }
+            _ => {}
+        });
 }
@@ -0,0 +1,198 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, parse_human_size, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+use tauri::{command, AppHandle, State};
+use walkdir::WalkDir;
+
+// How many of the largest files under /var/log to report
+const TOP_LOG_FILES: usize = 20;
+
+// Default directory systemd-coredump writes to
+const COREDUMP_DIR: &str = "/var/lib/systemd/coredump";
+
+// Size the journal is shrunk to when no explicit size is given
+const VACUUM_TARGET: u64 = 500 * 1024 * 1024;
+const VACUUM_TARGET_ARG: &str = "500M";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogFileInfo {
+    path: String,
+    size: u64,
+    last_modified: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleanupSuggestion {
+    description: String,
+    command: String,
+    reclaimable: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinuxLogReport {
+    var_log_size: u64,
+    largest_logs: Vec<LogFileInfo>,
+    pub(crate) journal_size: Option<u64>,
+    core_pattern: Option<String>,
+    core_dumps: Vec<LogFileInfo>,
+    core_dumps_size: u64,
+    suggestions: Vec<CleanupSuggestion>,
+}
+
+#[command]
+pub async fn analyze_linux_logs() -> Result<LinuxLogReport, DiskSenseError> {
+    if !cfg!(target_os = "linux") {
+        return Err(DiskSenseError::Unsupported(
+            "Log analysis is only available on Linux".to_string(),
+        ));
+    }
+
+    let var_log_size = get_size("/var/log").unwrap_or(0);
+
+    let mut largest_logs: Vec<LogFileInfo> = WalkDir::new("/var/log")
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| file_info(entry.path()))
+        .collect();
+    largest_logs.sort_by_key(|item| std::cmp::Reverse(item.size));
+    largest_logs.truncate(TOP_LOG_FILES);
+
+    let journal_size = journal_disk_usage();
+
+    let core_pattern = std::fs::read_to_string("/proc/sys/kernel/core_pattern")
+        .ok()
+        .map(|pattern| pattern.trim().to_string());
+
+    let mut core_dumps: Vec<LogFileInfo> = match std::fs::read_dir(COREDUMP_DIR) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| file_info(&entry.path()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    core_dumps.sort_by_key(|item| std::cmp::Reverse(item.size));
+    let core_dumps_size = core_dumps.iter().map(|dump| dump.size).sum();
+
+    let mut suggestions = Vec::new();
+    if let Some(size) = journal_size {
+        if size > VACUUM_TARGET {
+            suggestions.push(CleanupSuggestion {
+                description: "Shrink the systemd journal".to_string(),
+                command: format!("journalctl --vacuum-size={}", VACUUM_TARGET_ARG),
+                reclaimable: size - VACUUM_TARGET,
+            });
+        }
+    }
+    if core_dumps_size > 0 {
+        suggestions.push(CleanupSuggestion {
+            description: "Remove stored core dumps".to_string(),
+            command: format!("rm {}/*", COREDUMP_DIR),
+            reclaimable: core_dumps_size,
+        });
+    }
+
+    Ok(LinuxLogReport {
+        var_log_size,
+        largest_logs,
+        journal_size,
+        core_pattern,
+        core_dumps,
+        core_dumps_size,
+        suggestions,
+    })
+}
+
+#[command]
+pub async fn vacuum_journal(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    max_size: Option<String>,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    // Vacuuming deletes archived journal files, so require explicit confirmation
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Journal vacuum must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    let before = journal_disk_usage();
+    let result = vacuum(max_size);
+    let after = journal_disk_usage();
+    audit::record(
+        &app,
+        "vacuum_journal",
+        action.as_deref(),
+        vec!["/var/log/journal".to_string()],
+        before
+            .zip(after)
+            .map_or(0, |(before, after)| before.saturating_sub(after)),
+        result.as_ref().err(),
+    );
+    result
+}
+
+pub(crate) fn vacuum(max_size: Option<String>) -> Result<String, DiskSenseError> {
+    let max_size = max_size.unwrap_or_else(|| VACUUM_TARGET_ARG.to_string());
+    if parse_human_size(&max_size, 1024.0) == 0 {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Invalid journal size: {}",
+            max_size
+        )));
+    }
+
+    let output = Command::new("journalctl")
+        .arg(format!("--vacuum-size={}", max_size))
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "journalctl"))?;
+
+    // journalctl reports what it removed on stderr
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "Journal vacuum failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+// Parse "Archived and active journals take up 1.2G in the file system."
+fn journal_disk_usage() -> Option<u64> {
+    let output = Command::new("journalctl")
+        .arg("--disk-usage")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let size = text.split("take up").nth(1)?.split_whitespace().next()?;
+    Some(parse_human_size(size, 1024.0))
+}
+
+fn file_info(path: &Path) -> Option<LogFileInfo> {
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    Some(LogFileInfo {
+        path: path.to_string_lossy().to_string(),
+        size: metadata.len(),
+        last_modified: metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+    })
+}
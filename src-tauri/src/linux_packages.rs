@@ -0,0 +1,214 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, home_dir, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+// Where snapd keeps the squashfs image of every installed revision
+const SNAP_STORE_DIR: &str = "/var/lib/snapd/snaps";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapRevision {
+    name: String,
+    revision: String,
+    size: u64,
+    current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FlatpakRef {
+    id: String,
+    kind: String,
+    installation: String,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SandboxedPackageReport {
+    snap_revisions: Vec<SnapRevision>,
+    snap_reclaimable: u64,
+    flatpak_refs: Vec<FlatpakRef>,
+    flatpak_app_data: Vec<FlatpakRef>,
+}
+
+#[command]
+pub async fn get_sandboxed_package_sizes() -> Result<SandboxedPackageReport, DiskSenseError> {
+    if !cfg!(target_os = "linux") {
+        return Err(DiskSenseError::Unsupported(
+            "Snap and Flatpak reporting is only available on Linux".to_string(),
+        ));
+    }
+
+    let snap_revisions = snap_revisions();
+    let snap_reclaimable = snap_revisions
+        .iter()
+        .filter(|revision| !revision.current)
+        .map(|revision| revision.size)
+        .sum();
+
+    let mut flatpak_refs = Vec::new();
+    let mut installations = vec![("system", PathBuf::from("/var/lib/flatpak"))];
+    if let Some(home) = home_dir() {
+        installations.push(("user", home.join(".local/share/flatpak")));
+    }
+    for (installation, root) in installations {
+        for kind in ["app", "runtime"] {
+            flatpak_refs.extend(flatpak_refs_in(&root.join(kind), kind, installation));
+        }
+    }
+    flatpak_refs.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    // Per-app user data lives in ~/.var/app/<id>, separate from the install
+    let mut flatpak_app_data = match home_dir() {
+        Some(home) => flatpak_refs_in(&home.join(".var/app"), "data", "user"),
+        None => Vec::new(),
+    };
+    flatpak_app_data.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    Ok(SandboxedPackageReport {
+        snap_revisions,
+        snap_reclaimable,
+        flatpak_refs,
+        flatpak_app_data,
+    })
+}
+
+#[command]
+pub async fn remove_snap_revision(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    name: String,
+    revision: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Snap removal must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    // Never remove the revision that is currently mounted
+    if current_snap_revision(&name).as_deref() == Some(revision.as_str()) {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Revision {} is the active revision of {}",
+            revision, name
+        )));
+    }
+
+    let image = Path::new(SNAP_STORE_DIR).join(format!("{}_{}.snap", name, revision));
+    let size = std::fs::metadata(&image).map_or(0, |m| m.len());
+    let result = run_tool(
+        "snap",
+        &["remove", &name, &format!("--revision={}", revision)],
+    );
+    audit::record(
+        &app,
+        "remove_snap_revision",
+        action.as_deref(),
+        vec![image.to_string_lossy().to_string()],
+        if result.is_ok() { size } else { 0 },
+        result.as_ref().err(),
+    );
+    result
+}
+
+#[command]
+pub async fn remove_unused_flatpaks(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Flatpak cleanup must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    let result = run_tool("flatpak", &["uninstall", "--unused", "-y"]);
+    audit::record(
+        &app,
+        "remove_unused_flatpaks",
+        action.as_deref(),
+        Vec::new(),
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn run_tool(program: &str, args: &[&str]) -> Result<String, DiskSenseError> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, program))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+// Snap images are named <name>_<revision>.snap
+fn snap_revisions() -> Vec<SnapRevision> {
+    let entries = match std::fs::read_dir(SNAP_STORE_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut revisions: Vec<SnapRevision> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let stem = file_name.strip_suffix(".snap")?;
+            let (name, revision) = stem.rsplit_once('_')?;
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+
+            Some(SnapRevision {
+                name: name.to_string(),
+                revision: revision.to_string(),
+                size,
+                current: current_snap_revision(name).as_deref() == Some(revision),
+            })
+        })
+        .collect();
+
+    revisions.sort_by(|a, b| a.name.cmp(&b.name).then(b.size.cmp(&a.size)));
+    revisions
+}
+
+// /snap/<name>/current is a symlink to the active revision
+fn current_snap_revision(name: &str) -> Option<String> {
+    let target = std::fs::read_link(Path::new("/snap").join(name).join("current")).ok()?;
+    Some(target.file_name()?.to_string_lossy().to_string())
+}
+
+// Each child directory of a flatpak app/runtime dir is one installed ref.
+// Deployments are hardlinked from the shared ostree repo, so sizes can overlap.
+fn flatpak_refs_in(dir: &Path, kind: &str, installation: &str) -> Vec<FlatpakRef> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| FlatpakRef {
+            id: entry.file_name().to_string_lossy().to_string(),
+            kind: kind.to_string(),
+            installation: installation.to_string(),
+            size: get_size(entry.path()).unwrap_or(0),
+        })
+        .collect()
+}
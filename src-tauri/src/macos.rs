@@ -0,0 +1,227 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, DiskSenseError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, State};
+
+// JXA snippet returning the capacity Finder reports as "Available" (free + purgeable)
+const IMPORTANT_USAGE_SCRIPT: &str = r#"
+function run(argv) {
+    ObjC.import('Foundation');
+    var key = 'NSURLVolumeAvailableCapacityForImportantUsageKey';
+    var url = $.NSURL.fileURLWithPath(argv[0]);
+    var values = url.resourceValuesForKeysError($([key]), null);
+    return values.objectForKey(key).js;
+}
+"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocalSnapshot {
+    name: String,
+    date: String,
+    size: Option<u64>,
+}
+
+// How long a Finder reading is reused; drive lists refresh every few seconds and
+// each reading starts osascript
+const FINDER_SPACE_TTL: Duration = Duration::from_secs(10);
+
+// When each mount point was last read, and what Finder reported (failures included)
+type FinderSpaceCache = HashMap<String, (Instant, Option<u64>)>;
+
+static FINDER_SPACE: Mutex<Option<FinderSpaceCache>> = Mutex::new(None);
+
+// Free space including purgeable data, as shown by Finder
+pub(crate) fn finder_available_space(mount_point: &str) -> Option<u64> {
+    if !cfg!(target_os = "macos") {
+        return None;
+    }
+
+    let mut cache = FINDER_SPACE.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if let Some((read_at, space)) = cache.get(mount_point) {
+        if read_at.elapsed() < FINDER_SPACE_TTL {
+            return *space;
+        }
+    }
+    let space = read_finder_available_space(mount_point);
+    cache.insert(mount_point.to_string(), (Instant::now(), space));
+    space
+}
+
+fn read_finder_available_space(mount_point: &str) -> Option<u64> {
+    let output = Command::new("osascript")
+        .args([
+            "-l",
+            "JavaScript",
+            "-e",
+            IMPORTANT_USAGE_SCRIPT,
+            mount_point,
+        ])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(|bytes| bytes as u64)
+}
+
+#[command]
+pub async fn get_local_snapshots(
+    mount_point: Option<String>,
+) -> Result<Vec<LocalSnapshot>, DiskSenseError> {
+    if !cfg!(target_os = "macos") {
+        return Err(DiskSenseError::Unsupported(
+            "Local snapshots are only available on macOS".to_string(),
+        ));
+    }
+
+    let mount_point = mount_point.unwrap_or_else(|| "/".to_string());
+    let output = Command::new("tmutil")
+        .args(["listlocalsnapshots", &mount_point])
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "tmutil"))?;
+
+    if !output.status.success() {
+        return Err(DiskSenseError::Failed(format!(
+            "Failed to list local snapshots: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    // Lines look like: com.apple.TimeMachine.2024-01-31-101500.local
+    let snapshots = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let name = line.trim();
+            let date = name
+                .strip_prefix("com.apple.TimeMachine.")?
+                .strip_suffix(".local")?;
+
+            Some(LocalSnapshot {
+                name: name.to_string(),
+                date: date.to_string(),
+                // APFS does not expose per-snapshot sizes; their total is purgeable space
+                size: None,
+            })
+        })
+        .collect();
+
+    Ok(snapshots)
+}
+
+#[command]
+pub async fn delete_local_snapshot(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    date: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Snapshot deletion must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    // Reject anything that is not a snapshot date to keep tmutil arguments well-formed
+    if date.is_empty() || !date.chars().all(|c| c.is_ascii_digit() || c == '-') {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Invalid snapshot date: {}",
+            date
+        )));
+    }
+
+    let result = run_tmutil(&["deletelocalsnapshots", &date]);
+    audit::record(
+        &app,
+        "delete_local_snapshot",
+        action.as_deref(),
+        vec![date],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+#[command]
+pub async fn thin_local_snapshots(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    mount_point: Option<String>,
+    bytes: u64,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Snapshot thinning must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    let mount_point = mount_point.unwrap_or_else(|| "/".to_string());
+    // Urgency 4 is the highest, asking tmutil to reclaim as much as it can
+    let result = run_tmutil(&["thinlocalsnapshots", &mount_point, &bytes.to_string(), "4"]);
+    audit::record(
+        &app,
+        "thin_local_snapshots",
+        action.as_deref(),
+        vec![mount_point],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+// Open the Quick Look panel on a file, the same preview Space gives in Finder
+#[command]
+pub async fn quick_look(path: String) -> Result<(), DiskSenseError> {
+    if !cfg!(target_os = "macos") {
+        return Err(DiskSenseError::Unsupported(
+            "Quick Look is only available on macOS".to_string(),
+        ));
+    }
+
+    let target = crate::os_path::decode(&path);
+    if !target.exists() {
+        return Err(DiskSenseError::NotFound { path });
+    }
+
+    // qlmanage stays open with the panel, so it isn't waited on
+    Command::new("qlmanage")
+        .arg("-p")
+        .arg(&target)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| DiskSenseError::from_spawn(e, "qlmanage"))?;
+
+    Ok(())
+}
+
+fn run_tmutil(args: &[&str]) -> Result<(), DiskSenseError> {
+    let output = Command::new("tmutil")
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "tmutil"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "tmutil failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
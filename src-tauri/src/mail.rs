@@ -0,0 +1,209 @@
+use crate::{home_dir, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MailStore {
+    client: String,
+    profile: String,
+    mailbox: String,
+    path: String,
+    size: u64,
+    last_modified: Option<u64>,
+}
+
+#[command]
+pub async fn get_mail_stores() -> Result<Vec<MailStore>, DiskSenseError> {
+    let home = home_dir().ok_or_else(|| {
+        DiskSenseError::Unsupported("Could not determine home directory".to_string())
+    })?;
+
+    let mut stores = Vec::new();
+    stores.extend(outlook_stores(&home));
+    stores.extend(thunderbird_stores(&home));
+    stores.extend(apple_mail_stores(&home));
+
+    // Largest mailboxes first, like the scan tree
+    stores.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    Ok(stores)
+}
+
+fn outlook_stores(home: &Path) -> Vec<MailStore> {
+    let dirs: Vec<PathBuf> = if cfg!(target_os = "windows") {
+        vec![
+            home.join(r"AppData\Local\Microsoft\Outlook"),
+            home.join(r"Documents\Outlook Files"),
+        ]
+    } else if cfg!(target_os = "macos") {
+        vec![home.join("Library/Group Containers/UBF8T346G9.Office/Outlook/Outlook 15 Profiles")]
+    } else {
+        Vec::new()
+    };
+
+    let mut stores = Vec::new();
+    for dir in dirs {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            if path.is_dir() {
+                // New Outlook for Mac keeps one folder per profile
+                if cfg!(target_os = "macos") {
+                    stores.push(mail_store("outlook", &file_name, &file_name, &path));
+                }
+                continue;
+            }
+
+            let extension = path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if extension == "pst" || extension == "ost" {
+                stores.push(mail_store("outlook", "Outlook", &file_name, &path));
+            }
+        }
+    }
+
+    stores
+}
+
+fn thunderbird_stores(home: &Path) -> Vec<MailStore> {
+    let profiles_dir = if cfg!(target_os = "windows") {
+        home.join(r"AppData\Roaming\Thunderbird\Profiles")
+    } else if cfg!(target_os = "macos") {
+        home.join("Library/Thunderbird/Profiles")
+    } else {
+        home.join(".thunderbird")
+    };
+
+    let profiles = match std::fs::read_dir(&profiles_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut stores = Vec::new();
+    for profile in profiles.filter_map(Result::ok) {
+        let profile_path = profile.path();
+        if !profile_path.is_dir() {
+            continue;
+        }
+        let profile_name = profile.file_name().to_string_lossy().to_string();
+
+        // Local folders live under Mail/, IMAP caches under ImapMail/, one dir per account
+        for store_dir in ["Mail", "ImapMail"] {
+            let accounts = match std::fs::read_dir(profile_path.join(store_dir)) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            for account in accounts.filter_map(Result::ok) {
+                let account_path = account.path();
+                if !account_path.is_dir() {
+                    continue;
+                }
+                let account_name = account.file_name().to_string_lossy().to_string();
+                collect_mbox_files(&account_path, &profile_name, &account_name, &mut stores);
+            }
+        }
+    }
+
+    stores
+}
+
+// Thunderbird mailboxes are extension-less mbox files, with `.sbd` subfolders
+fn collect_mbox_files(dir: &Path, profile: &str, account: &str, stores: &mut Vec<MailStore>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            if path.extension().is_some_and(|ext| ext == "sbd") {
+                collect_mbox_files(&path, profile, account, stores);
+            }
+        } else if path.extension().is_none() {
+            let mailbox = format!("{}/{}", account, entry.file_name().to_string_lossy());
+            stores.push(mail_store("thunderbird", profile, &mailbox, &path));
+        }
+    }
+}
+
+fn apple_mail_stores(home: &Path) -> Vec<MailStore> {
+    if !cfg!(target_os = "macos") {
+        return Vec::new();
+    }
+
+    // ~/Library/Mail/V<n>/<account-uuid>/<mailbox>.mbox
+    let versions = match std::fs::read_dir(home.join("Library/Mail")) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut stores = Vec::new();
+    for version in versions.filter_map(Result::ok) {
+        if !version.file_name().to_string_lossy().starts_with('V') {
+            continue;
+        }
+
+        let accounts = match std::fs::read_dir(version.path()) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for account in accounts.filter_map(Result::ok) {
+            let account_name = account.file_name().to_string_lossy().to_string();
+            let mailboxes = match std::fs::read_dir(account.path()) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            for mailbox in mailboxes.filter_map(Result::ok) {
+                let path = mailbox.path();
+                if path.is_dir() && path.extension().is_some_and(|ext| ext == "mbox") {
+                    let mailbox_name = mailbox.file_name().to_string_lossy().to_string();
+                    stores.push(mail_store(
+                        "apple-mail",
+                        &account_name,
+                        &mailbox_name,
+                        &path,
+                    ));
+                }
+            }
+        }
+    }
+
+    stores
+}
+
+fn mail_store(client: &str, profile: &str, mailbox: &str, path: &Path) -> MailStore {
+    let metadata = std::fs::metadata(path).ok();
+    let size = match &metadata {
+        Some(m) if m.is_dir() => get_size(path).unwrap_or(0),
+        Some(m) => m.len(),
+        None => 0,
+    };
+    let last_modified = metadata
+        .and_then(|m| m.modified().ok())
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    MailStore {
+        client: client.to_string(),
+        profile: profile.to_string(),
+        mailbox: mailbox.to_string(),
+        path: path.to_string_lossy().to_string(),
+        size,
+        last_modified,
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{hashing, DiskSenseError};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::command;
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ManifestEntry {
+    // Relative to the manifest root, with '/' separators so manifests move between platforms
+    path: String,
+    size: u64,
+    modified: u64,
+    sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Manifest {
+    root: String,
+    created: u64,
+    files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManifestVerification {
+    verified: usize,
+    missing: Vec<String>,
+    // Size or modification time differ: the file was edited since the manifest was made
+    changed: Vec<String>,
+    // Same size and modification time but different contents: silent corruption
+    corrupted: Vec<String>,
+    added: Vec<String>,
+    unreadable: Vec<String>,
+}
+
+// Hash every file under `root` into a manifest written to `file`
+#[command]
+pub async fn create_manifest(root: String, file: String) -> Result<usize, DiskSenseError> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(DiskSenseError::NotFound { path: root });
+    }
+
+    let files: Vec<ManifestEntry> = list_files(root_path)
+        .into_par_iter()
+        .filter_map(|(relative, size, modified)| {
+            let sha256 = hashing::hash_file(&root_path.join(&relative)).ok()?;
+            Some(ManifestEntry {
+                path: relative,
+                size,
+                modified,
+                sha256,
+            })
+        })
+        .collect();
+
+    let manifest = Manifest {
+        root: root.clone(),
+        created: unix_seconds(SystemTime::now()),
+        files,
+    };
+    let contents = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to build manifest: {}", e)))?;
+    std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))?;
+
+    Ok(manifest.files.len())
+}
+
+// Compare the files under `root` (e.g. a restored backup) with a manifest
+#[command]
+pub async fn verify_manifest(
+    root: String,
+    file: String,
+) -> Result<ManifestVerification, DiskSenseError> {
+    let root_path = Path::new(&root);
+    if !root_path.is_dir() {
+        return Err(DiskSenseError::NotFound { path: root });
+    }
+
+    let contents = std::fs::read_to_string(&file).map_err(|e| DiskSenseError::from_io(e, &file))?;
+    let manifest: Manifest = serde_json::from_str(&contents)
+        .map_err(|e| DiskSenseError::InvalidInput(format!("Invalid manifest {}: {}", file, e)))?;
+
+    let on_disk: HashMap<String, (u64, u64)> = list_files(root_path)
+        .into_iter()
+        .map(|(relative, size, modified)| (relative, (size, modified)))
+        .collect();
+
+    let mut verification = ManifestVerification {
+        verified: 0,
+        missing: Vec::new(),
+        changed: Vec::new(),
+        corrupted: Vec::new(),
+        added: Vec::new(),
+        unreadable: Vec::new(),
+    };
+
+    let expected: HashSet<&str> = manifest.files.iter().map(|e| e.path.as_str()).collect();
+    verification.added = on_disk
+        .keys()
+        .filter(|path| !expected.contains(path.as_str()))
+        .cloned()
+        .collect();
+
+    // Hash in parallel; only files that look unchanged need their contents checked
+    let outcomes: Vec<(&ManifestEntry, Outcome)> = manifest
+        .files
+        .par_iter()
+        .map(|entry| {
+            let outcome = match on_disk.get(&entry.path) {
+                None => Outcome::Missing,
+                Some((size, modified)) if *size != entry.size || *modified != entry.modified => {
+                    Outcome::Changed
+                }
+                Some(_) => match hashing::hash_file(&root_path.join(&entry.path)) {
+                    Ok(hash) if hash == entry.sha256 => Outcome::Verified,
+                    Ok(_) => Outcome::Corrupted,
+                    Err(_) => Outcome::Unreadable,
+                },
+            };
+            (entry, outcome)
+        })
+        .collect();
+
+    for (entry, outcome) in outcomes {
+        let path = entry.path.clone();
+        match outcome {
+            Outcome::Verified => verification.verified += 1,
+            Outcome::Missing => verification.missing.push(path),
+            Outcome::Changed => verification.changed.push(path),
+            Outcome::Corrupted => verification.corrupted.push(path),
+            Outcome::Unreadable => verification.unreadable.push(path),
+        }
+    }
+
+    verification.added.sort();
+    Ok(verification)
+}
+
+enum Outcome {
+    Verified,
+    Missing,
+    Changed,
+    Corrupted,
+    Unreadable,
+}
+
+// Every regular file under `root` as (relative path, size, modified)
+fn list_files(root: &Path) -> Vec<(String, u64, u64)> {
+    WalkDir::new(root)
+        .min_depth(1)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let relative = entry.path().strip_prefix(root).ok()?;
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let modified = metadata.modified().map(unix_seconds).unwrap_or(0);
+            Some((relative, metadata.len(), modified))
+        })
+        .collect()
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
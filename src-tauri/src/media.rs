@@ -0,0 +1,188 @@
+use crate::DiskSenseError;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tauri::command;
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MediaFile {
+    path: String,
+    size: u64,
+    container: String,
+    codec: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    bitrate: Option<u32>,
+    duration_seconds: Option<f64>,
+    estimated_savings: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MediaReport {
+    target_codec: String,
+    total_size: u64,
+    estimated_savings: u64,
+    files: Vec<MediaFile>,
+}
+
+// Video containers we look at
+const VIDEO_EXTENSIONS: [&str; 11] = [
+    "mp4", "m4v", "mov", "mkv", "webm", "avi", "wmv", "flv", "mpg", "mpeg", "ts",
+];
+
+// Find videos under `root` and estimate what re-encoding legacy codecs to HEVC or
+// AV1 would save, largest savings first
+#[command]
+pub async fn analyze_media(
+    root: String,
+    target_codec: Option<String>,
+) -> Result<MediaReport, DiskSenseError> {
+    let target_codec = target_codec.unwrap_or_else(|| "hevc".to_string());
+    if target_codec != "hevc" && target_codec != "av1" {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Unsupported target codec: {}",
+            target_codec
+        )));
+    }
+    if !Path::new(&root).is_dir() {
+        return Err(DiskSenseError::NotFound { path: root });
+    }
+
+    let videos: Vec<_> = WalkDir::new(&root)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            entry.path().extension().is_some_and(|ext| {
+                VIDEO_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+            })
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let mut files: Vec<MediaFile> = videos
+        .par_iter()
+        .filter_map(|path| probe(path, &target_codec))
+        .collect();
+    files.sort_by_key(|item| std::cmp::Reverse(item.estimated_savings));
+
+    Ok(MediaReport {
+        total_size: files.iter().map(|file| file.size).sum(),
+        estimated_savings: files.iter().map(|file| file.estimated_savings).sum(),
+        target_codec,
+        files,
+    })
+}
+
+fn probe(path: &Path, target_codec: &str) -> Option<MediaFile> {
+    let size = std::fs::metadata(path).ok()?.len();
+    let container = path.extension()?.to_string_lossy().to_lowercase();
+
+    let mut file = MediaFile {
+        path: path.to_string_lossy().to_string(),
+        size,
+        container: container.clone(),
+        codec: None,
+        width: None,
+        height: None,
+        bitrate: None,
+        duration_seconds: None,
+        estimated_savings: 0,
+    };
+
+    // ISO media (MP4/MOV) headers are cheap to read and name the codec exactly
+    if matches!(container.as_str(), "mp4" | "m4v" | "mov") {
+        probe_mp4(path, size, &mut file);
+    }
+
+    // Containers that (almost) only ever hold legacy codecs
+    if file.codec.is_none() {
+        file.codec = match container.as_str() {
+            "avi" => Some("mpeg4".to_string()),
+            "wmv" => Some("wmv".to_string()),
+            "flv" => Some("h263".to_string()),
+            "mpg" | "mpeg" | "ts" => Some("mpeg2".to_string()),
+            _ => None,
+        };
+    }
+
+    let ratio = savings_ratio(file.codec.as_deref(), target_codec);
+    file.estimated_savings = (size as f64 * ratio) as u64;
+    Some(file)
+}
+
+fn probe_mp4(path: &Path, size: u64, file: &mut MediaFile) {
+    let reader = match File::open(path) {
+        Ok(handle) => BufReader::new(handle),
+        Err(_) => return,
+    };
+    let mp4 = match mp4::Mp4Reader::read_header(reader, size) {
+        Ok(mp4) => mp4,
+        Err(_) => return,
+    };
+
+    file.duration_seconds = Some(mp4.duration().as_secs_f64());
+
+    let video = mp4
+        .tracks()
+        .values()
+        .find(|track| matches!(track.track_type(), Ok(mp4::TrackType::Video)));
+    if let Some(track) = video {
+        file.codec = track
+            .box_type()
+            .ok()
+            .map(|fourcc| codec_name(&fourcc.to_string()));
+        file.width = Some(u32::from(track.width()));
+        file.height = Some(u32::from(track.height()));
+        file.bitrate = Some(track.bitrate()).filter(|rate| *rate > 0);
+    }
+}
+
+// Common codec name for an ISO media sample entry
+fn codec_name(fourcc: &str) -> String {
+    match fourcc {
+        "avc1" | "avc3" => "h264",
+        "hvc1" | "hev1" => "hevc",
+        "av01" => "av1",
+        "vp09" => "vp9",
+        "mp4v" => "mpeg4",
+        "s263" => "h263",
+        other => other,
+    }
+    .to_string()
+}
+
+// Typical size reduction when re-encoding at similar visual quality
+fn savings_ratio(codec: Option<&str>, target_codec: &str) -> f64 {
+    let av1 = target_codec == "av1";
+    match codec {
+        Some("mpeg2") | Some("h263") | Some("wmv") => {
+            if av1 {
+                0.75
+            } else {
+                0.65
+            }
+        }
+        Some("mpeg4") => {
+            if av1 {
+                0.65
+            } else {
+                0.55
+            }
+        }
+        Some("h264") => {
+            if av1 {
+                0.5
+            } else {
+                0.4
+            }
+        }
+        Some("hevc") | Some("vp9") if av1 => 0.2,
+        // Already modern, or unknown: don't promise savings we can't back up
+        _ => 0.0,
+    }
+}
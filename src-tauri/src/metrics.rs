@@ -0,0 +1,232 @@
+use crate::DiskSenseError;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::Disks;
+use tauri::{command, AppHandle, Manager, State};
+
+// Setting this starts the endpoint on launch, for machines running unattended
+const METRICS_ADDRESS_ENV: &str = "DISKSENSE_METRICS_ADDR";
+
+// How often the accept loop checks whether it has been stopped
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+struct ScanMetrics {
+    total_size: u64,
+    items: usize,
+    duration_seconds: f64,
+    completed_at: u64,
+}
+
+// Serves Prometheus metrics over plain HTTP. Starting the server bumps the
+// generation, which stops any previous listener.
+#[derive(Default)]
+pub struct MetricsServer {
+    generation: Arc<AtomicU64>,
+    scans: Arc<Mutex<HashMap<String, ScanMetrics>>>,
+}
+
+impl MetricsServer {
+    // Start listening straight away when the environment asks for it
+    pub fn from_env() -> Self {
+        let server = MetricsServer::default();
+        if let Ok(address) = std::env::var(METRICS_ADDRESS_ENV) {
+            if let Err(e) = server.listen(&address) {
+                log::error!("Failed to start metrics endpoint on {}: {}", address, e);
+            }
+        }
+        server
+    }
+
+    fn listen(&self, address: &str) -> Result<(), DiskSenseError> {
+        let listener = TcpListener::bind(address).map_err(|e| {
+            DiskSenseError::Failed(format!("Failed to listen on {}: {}", address, e))
+        })?;
+        listener.set_nonblocking(true).map_err(|e| {
+            DiskSenseError::Failed(format!("Failed to listen on {}: {}", address, e))
+        })?;
+        if let Ok(local) = listener.local_addr() {
+            if !local.ip().is_loopback() {
+                log::warn!(
+                    "Metrics endpoint on {} is reachable from other machines and has no authentication",
+                    local
+                );
+            }
+        }
+
+        let generation = self.generation.clone();
+        let listening = generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let scans = self.scans.clone();
+
+        std::thread::Builder::new()
+            .name("disksense-metrics".to_string())
+            .spawn(move || {
+                while generation.load(Ordering::SeqCst) == listening {
+                    match listener.accept() {
+                        Ok((stream, _)) => serve(stream, &scans),
+                        Err(_) => std::thread::sleep(ACCEPT_POLL_INTERVAL),
+                    }
+                }
+            })
+            .map_err(|e| {
+                DiskSenseError::Failed(format!("Failed to start metrics endpoint: {}", e))
+            })?;
+
+        Ok(())
+    }
+}
+
+// Record a finished scan so its totals show up in the metrics
+pub(crate) fn record_scan(
+    app: &AppHandle,
+    root: &str,
+    total_size: u64,
+    items: usize,
+    duration: Duration,
+) {
+    let server = match app.try_state::<MetricsServer>() {
+        Some(server) => server,
+        None => return,
+    };
+    let completed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    server
+        .scans
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            root.to_string(),
+            ScanMetrics {
+                total_size,
+                items,
+                duration_seconds: duration.as_secs_f64(),
+                completed_at,
+            },
+        );
+}
+
+// Start serving /metrics on `address` (e.g. "127.0.0.1:9184"). There is no
+// authentication, so any other address exposes mount paths and sizes to the network.
+#[command]
+pub async fn start_metrics_server(
+    server: State<'_, MetricsServer>,
+    address: String,
+) -> Result<(), DiskSenseError> {
+    server.listen(&address)
+}
+
+#[command]
+pub async fn stop_metrics_server(server: State<'_, MetricsServer>) -> Result<(), DiskSenseError> {
+    server.generation.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+// Answer one request. Every path gets the metrics, which is all a scraper needs.
+fn serve(mut stream: TcpStream, scans: &Mutex<HashMap<String, ScanMetrics>>) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request);
+
+    let body = render(&scans.lock().unwrap_or_else(|e| e.into_inner()));
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+// Name, help text and value of one metric
+type Metric<T, V> = (&'static str, &'static str, fn(&T) -> V);
+
+// Prometheus text exposition format
+fn render(scans: &HashMap<String, ScanMetrics>) -> String {
+    let disks = Disks::new_with_refreshed_list();
+    let mut body = String::new();
+
+    let mount_metrics: [Metric<sysinfo::Disk, u64>; 3] = [
+        (
+            "disksense_mount_total_bytes",
+            "Size of the mounted filesystem",
+            |disk| disk.total_space(),
+        ),
+        (
+            "disksense_mount_available_bytes",
+            "Free space on the mounted filesystem",
+            |disk| disk.available_space(),
+        ),
+        (
+            "disksense_mount_used_bytes",
+            "Used space on the mounted filesystem",
+            |disk| disk.total_space().saturating_sub(disk.available_space()),
+        ),
+    ];
+    for (name, help, value) in mount_metrics {
+        let _ = writeln!(body, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+        for disk in disks.list() {
+            let _ = writeln!(
+                body,
+                "{}{{mount=\"{}\",device=\"{}\",fstype=\"{}\"}} {}",
+                name,
+                escape(&disk.mount_point().to_string_lossy()),
+                escape(&disk.name().to_string_lossy()),
+                escape(&disk.file_system().to_string_lossy()),
+                value(disk)
+            );
+        }
+    }
+
+    let scan_metrics: [Metric<ScanMetrics, String>; 4] = [
+        (
+            "disksense_scan_total_bytes",
+            "Total size found by the last scan",
+            |scan| scan.total_size.to_string(),
+        ),
+        (
+            "disksense_scan_items",
+            "Items visited by the last scan",
+            |scan| scan.items.to_string(),
+        ),
+        (
+            "disksense_scan_duration_seconds",
+            "Duration of the last scan",
+            |scan| scan.duration_seconds.to_string(),
+        ),
+        (
+            "disksense_scan_completed_timestamp_seconds",
+            "Unix time the last scan finished",
+            |scan| scan.completed_at.to_string(),
+        ),
+    ];
+    for (name, help, value) in scan_metrics {
+        let _ = writeln!(body, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+        for (root, scan) in scans {
+            let _ = writeln!(
+                body,
+                "{}{{root=\"{}\"}} {}",
+                name,
+                escape(root),
+                value(scan)
+            );
+        }
+    }
+
+    body
+}
+
+// Label values escape backslashes (Windows paths), quotes and newlines
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
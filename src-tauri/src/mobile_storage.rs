@@ -0,0 +1,152 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{command, Manager};
+
+// Authority of Android's built-in document provider for shared and SD card storage
+const EXTERNAL_STORAGE_AUTHORITY: &str = "com.android.externalstorage.documents";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StorageVolume {
+    name: String,
+    path: String,
+    removable: bool,
+    // Not reported for every volume, e.g. before storage permission is granted
+    total_space: Option<u64>,
+    available_space: Option<u64>,
+}
+
+// Storage the app can read on Android and iOS: its own sandbox plus, on Android,
+// shared storage and SD cards. Desktop builds only report the sandbox folders.
+#[command]
+pub async fn list_storage_volumes(
+    app: tauri::AppHandle,
+) -> Result<Vec<StorageVolume>, DiskSenseError> {
+    let paths = app.path();
+    let mut candidates: Vec<(String, Option<PathBuf>, bool)> = vec![
+        ("App data".to_string(), paths.app_data_dir().ok(), false),
+        ("Cache".to_string(), paths.app_cache_dir().ok(), false),
+    ];
+
+    if cfg!(target_os = "ios") {
+        candidates.push(("Documents".to_string(), paths.document_dir().ok(), false));
+        candidates.push(("Temporary".to_string(), Some(std::env::temp_dir()), false));
+    }
+
+    if cfg!(target_os = "android") {
+        candidates.push((
+            "Internal shared storage".to_string(),
+            Some(PathBuf::from("/storage/emulated/0")),
+            false,
+        ));
+        // SD cards and USB drives are mounted as /storage/<volume id>
+        if let Ok(entries) = std::fs::read_dir("/storage") {
+            for entry in entries.filter_map(Result::ok) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name != "emulated" && name != "self" {
+                    candidates.push((name, Some(entry.path()), true));
+                }
+            }
+        }
+    }
+
+    Ok(candidates
+        .into_iter()
+        .filter_map(|(name, path, removable)| {
+            let path = path.filter(|path| path.is_dir())?;
+            let (total_space, available_space) = volume_quota(&path).unzip();
+            Some(StorageVolume {
+                name,
+                path: path.to_string_lossy().to_string(),
+                removable,
+                total_space,
+                available_space,
+            })
+        })
+        .collect())
+}
+
+// Turn a path the front-end got from a picker into one the scanner can walk.
+// Plain paths may also be a raw_path from an earlier scan.
+// Android's document picker hands out content:// URIs from the external storage
+// provider, whose document ids map directly onto /storage paths.
+pub(crate) fn resolve_storage_path(path: &str) -> Result<PathBuf, DiskSenseError> {
+    if let Some(file_path) = path.strip_prefix("file://") {
+        return Ok(PathBuf::from(percent_decode(file_path)));
+    }
+
+    let rest = match path.strip_prefix("content://") {
+        Some(rest) => rest,
+        None => return Ok(crate::os_path::decode(path)),
+    };
+
+    let unsupported = || {
+        DiskSenseError::Unsupported(format!(
+            "Only local storage documents can be scanned, not {}",
+            path
+        ))
+    };
+    let (authority, rest) = rest.split_once('/').ok_or_else(unsupported)?;
+    if authority != EXTERNAL_STORAGE_AUTHORITY {
+        return Err(unsupported());
+    }
+
+    // tree/<id> for folder grants, document/<id> or tree/<id>/document/<id> otherwise
+    let document_id = rest.rsplit('/').next().ok_or_else(unsupported)?;
+    let document_id = percent_decode(document_id);
+    let (volume, relative) = document_id.split_once(':').ok_or_else(unsupported)?;
+
+    let root = if volume == "primary" {
+        PathBuf::from("/storage/emulated/0")
+    } else {
+        Path::new("/storage").join(volume)
+    };
+    Ok(root.join(relative))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// Total and available bytes of the filesystem holding `path`. Inside an iOS
+// sandbox this is the device's free space, which is the app's effective quota.
+#[cfg(unix)]
+fn volume_quota(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    let block_size = stat.f_frsize as u64;
+    Some((
+        stat.f_blocks as u64 * block_size,
+        stat.f_bavail as u64 * block_size,
+    ))
+}
+
+#[cfg(not(unix))]
+fn volume_quota(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,183 @@
+use crate::remote::{build_tree, RemoteEntry, RemoteScan};
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "windows")]
+use std::process::Command;
+use tauri::command;
+
+// Lists portable devices under "This PC" through the shell, which goes through
+// WPD for MTP phones and cameras; prints one "name<TAB>shell path" per device
+#[cfg(target_os = "windows")]
+const LIST_DEVICES_SCRIPT: &str = r#"
+$shell = New-Object -ComObject Shell.Application
+foreach ($item in $shell.NameSpace(17).Items()) {
+    if (-not $item.IsFileSystem) { "$($item.Name)`t$($item.Path)" }
+}
+"#;
+
+// Walks the device in DISKSENSE_MTP_DEVICE, printing "F<TAB>size<TAB>path" per
+// file and "U<TAB>path" per folder that could not be opened
+#[cfg(target_os = "windows")]
+const WALK_DEVICE_SCRIPT: &str = r#"
+$shell = New-Object -ComObject Shell.Application
+function Walk($folder, $prefix) {
+    foreach ($item in $folder.Items()) {
+        $path = if ($prefix) { "$prefix/$($item.Name)" } else { $item.Name }
+        if ($item.IsFolder) {
+            $sub = $item.GetFolder
+            if ($sub) { Walk $sub $path } else { "U`t$path" }
+        } else {
+            "F`t$([uint64]$item.ExtendedProperty('System.Size'))`t$path"
+        }
+    }
+}
+$device = $shell.NameSpace(17).Items() | Where-Object { $_.Path -eq $env:DISKSENSE_MTP_DEVICE }
+if (-not $device) { exit 2 }
+Walk $device.GetFolder ""
+"#;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MtpDevice {
+    name: String,
+    // Pass back to scan_mtp_device: a shell path on Windows, the gvfs mount on Linux
+    id: String,
+}
+
+// Phones and cameras connected over USB in MTP mode. Windows never gives them a
+// drive letter; on Linux they show up once the desktop has mounted them with gvfs.
+#[command]
+pub async fn list_mtp_devices() -> Result<Vec<MtpDevice>, DiskSenseError> {
+    list_devices()
+}
+
+#[command]
+pub async fn scan_mtp_device(id: String) -> Result<RemoteScan, DiskSenseError> {
+    let name = list_devices()?
+        .into_iter()
+        .find(|device| device.id == id)
+        .map(|device| device.name)
+        .ok_or_else(|| DiskSenseError::NotFound { path: id.clone() })?;
+
+    let (entries, unreadable) = walk_device(&id)?;
+    Ok(RemoteScan {
+        file_count: entries.len() as u64,
+        root: build_tree(&format!("mtp://{}", name.replace('/', "_")), entries),
+        unreadable,
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn list_devices() -> Result<Vec<MtpDevice>, DiskSenseError> {
+    let stdout = run_powershell(LIST_DEVICES_SCRIPT, None)?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(name, id)| MtpDevice {
+            name: name.trim().to_string(),
+            id: id.trim().to_string(),
+        })
+        .collect())
+}
+
+#[cfg(target_os = "windows")]
+fn walk_device(id: &str) -> Result<(Vec<RemoteEntry>, Vec<String>), DiskSenseError> {
+    let stdout = run_powershell(WALK_DEVICE_SCRIPT, Some(id))?;
+    let mut entries = Vec::new();
+    let mut unreadable = Vec::new();
+    for line in stdout.lines() {
+        let mut fields = line.splitn(3, '\t');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some("F"), Some(size), Some(path)) => entries.push(RemoteEntry {
+                path: path.to_string(),
+                size: size.trim().parse().unwrap_or(0),
+            }),
+            (Some("U"), Some(path), None) => unreadable.push(path.to_string()),
+            _ => {}
+        }
+    }
+    Ok((entries, unreadable))
+}
+
+#[cfg(target_os = "windows")]
+fn run_powershell(script: &str, device: Option<&str>) -> Result<String, DiskSenseError> {
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+    if let Some(device) = device {
+        command.env("DISKSENSE_MTP_DEVICE", device);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to run powershell: {}", e)))?;
+    if !output.status.success() {
+        return Err(DiskSenseError::NotFound {
+            path: device.unwrap_or("portable devices").to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// gvfs mounts MTP devices under /run/user/<uid>/gvfs as "mtp:host=<device>"
+#[cfg(target_os = "linux")]
+fn list_devices() -> Result<Vec<MtpDevice>, DiskSenseError> {
+    let gvfs = format!("/run/user/{}/gvfs", unsafe { libc::geteuid() });
+    let entries = match std::fs::read_dir(&gvfs) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let host = file_name.strip_prefix("mtp:host=")?;
+            Some(MtpDevice {
+                name: host.replace('_', " "),
+                id: entry.path().to_string_lossy().to_string(),
+            })
+        })
+        .collect())
+}
+
+// The gvfs mount is a normal directory tree, just slow, so it is walked once
+// rather than through the full scanner
+#[cfg(target_os = "linux")]
+fn walk_device(id: &str) -> Result<(Vec<RemoteEntry>, Vec<String>), DiskSenseError> {
+    let root = std::path::Path::new(id);
+    let mut entries = Vec::new();
+    let mut unreadable = Vec::new();
+    for entry in walkdir::WalkDir::new(root).min_depth(1) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                if let Some(path) = e.path() {
+                    unreadable.push(path.to_string_lossy().to_string());
+                }
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        entries.push(RemoteEntry {
+            path: relative.to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+    Ok((entries, unreadable))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn list_devices() -> Result<Vec<MtpDevice>, DiskSenseError> {
+    Ok(Vec::new())
+}
+
+// macOS has no system MTP support
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn walk_device(_id: &str) -> Result<(Vec<RemoteEntry>, Vec<String>), DiskSenseError> {
+    Err(DiskSenseError::Unsupported(
+        "MTP devices are not supported on this platform".to_string(),
+    ))
+}
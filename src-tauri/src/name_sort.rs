@@ -0,0 +1,100 @@
+use crate::unicode_names::nfc;
+use crate::DiskSenseError;
+use icu_collator::{Collator, CollatorOptions, Numeric};
+use icu_locid::Locale;
+use std::cmp::Ordering;
+
+// How names are compared when a listing is sorted by name
+pub(crate) enum NameCollation {
+    // Plain byte order, the old behaviour
+    Ordinal,
+    // Case-insensitive, with runs of digits compared as numbers ("file2" < "file10")
+    Natural,
+    // The locale's collation rules, also with numeric ordering
+    Locale(Box<Collator>),
+}
+
+impl NameCollation {
+    // Accepts "ordinal", "natural" or a BCP 47 locale tag such as "de" or "sv-SE";
+    // no value means natural ordering
+    pub(crate) fn parse(collation: Option<&str>) -> Result<Self, DiskSenseError> {
+        match collation.unwrap_or("natural") {
+            "ordinal" => Ok(NameCollation::Ordinal),
+            "natural" => Ok(NameCollation::Natural),
+            tag => {
+                let invalid =
+                    || DiskSenseError::InvalidInput(format!("Unsupported collation: {}", tag));
+                let locale: Locale = tag.parse().map_err(|_| invalid())?;
+                let mut options = CollatorOptions::new();
+                options.numeric = Some(Numeric::On);
+                let collator =
+                    Collator::try_new(&(&locale).into(), options).map_err(|_| invalid())?;
+                Ok(NameCollation::Locale(Box::new(collator)))
+            }
+        }
+    }
+
+    // Names are compared in NFC form, so decomposed names from macOS sort with
+    // their composed twins
+    pub(crate) fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (a, b) = (&*nfc(a), &*nfc(b));
+        match self {
+            NameCollation::Ordinal => a.cmp(b),
+            NameCollation::Natural => natural_cmp(a, b),
+            NameCollation::Locale(collator) => collator.compare(a, b).then_with(|| a.cmp(b)),
+        }
+    }
+}
+
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+
+    loop {
+        let ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match (is_number(x), is_number(y)) {
+                (true, true) => number_cmp(x, y),
+                _ => x
+                    .chars()
+                    .flat_map(char::to_lowercase)
+                    .cmp(y.chars().flat_map(char::to_lowercase)),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+// Split a name into alternating runs of digits and non-digits
+fn chunks(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest = name;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let end = rest
+            .find(|c: char| c.is_ascii_digit() != first.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
+
+fn is_number(chunk: &str) -> bool {
+    chunk.starts_with(|c: char| c.is_ascii_digit())
+}
+
+// Digit runs of any length, without overflowing: fewer significant digits is smaller
+fn number_cmp(a: &str, b: &str) -> Ordering {
+    let a_digits = a.trim_start_matches('0');
+    let b_digits = b.trim_start_matches('0');
+    a_digits
+        .len()
+        .cmp(&b_digits.len())
+        .then_with(|| a_digits.cmp(b_digits))
+        // "01" after "1" so equal values still have a stable order
+        .then_with(|| a.len().cmp(&b.len()))
+}
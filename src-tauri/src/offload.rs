@@ -0,0 +1,334 @@
+use crate::deletion::{self, DeleteCancel};
+use crate::read_only::ReadOnlyMode;
+use crate::{
+    audit, delete_and_report, hashing, os_path, protected, DiskSenseError, ProtectedPaths,
+    ScanStore,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{command, AppHandle, Emitter, State};
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveProgress {
+    files_done: u64,
+    files_total: u64,
+    current_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveResult {
+    archived_to: String,
+    files: u64,
+    bytes: u64,
+    // True when a symlink or junction now stands where the item was
+    linked: bool,
+}
+
+// Move a cold folder or file to another drive or NAS. Everything is copied
+// first and each file is checked by SHA-256 against its original; only then is
+// the original removed. With `leave_link` a symlink (a junction on Windows)
+// pointing at the copy is left in its place so existing paths keep working.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn archive_item(
+    read_only: State<'_, ReadOnlyMode>,
+    app: AppHandle,
+    cancel: State<'_, DeleteCancel>,
+    protected: State<'_, ProtectedPaths>,
+    store: State<'_, ScanStore>,
+    path: String,
+    dest: String,
+    leave_link: Option<bool>,
+    action: Option<String>,
+) -> Result<ArchiveResult, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let source = os_path::decode(&path);
+    let name = source
+        .file_name()
+        .ok_or_else(|| DiskSenseError::InvalidInput(format!("Cannot archive {}", path)))?;
+    // Checked up front so nothing is copied for an item that can't be removed
+    protected::ensure_deletable(&source, &protected, &store, false)?;
+
+    let dest_dir = os_path::decode(&dest);
+    ensure_outside(&source, &dest_dir)?;
+    std::fs::create_dir_all(&dest_dir).map_err(|e| DiskSenseError::from_io(e, &dest_dir))?;
+    let target = dest_dir.join(name);
+    if std::fs::symlink_metadata(&target).is_ok() {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "{} already exists",
+            target.display()
+        )));
+    }
+
+    let cancelled = cancel.reset();
+    let (files, bytes) = match copy_verified(&app, &source, &target, cancelled) {
+        Ok(copied) => copied,
+        Err(e) => {
+            // Leave nothing half-copied behind
+            let _ = deletion::remove_path(&target);
+            return Err(e);
+        }
+    };
+
+    // The link is made under a temporary name before the original goes, so a
+    // link that can't be created stops the move with the original untouched
+    let linked = leave_link.unwrap_or(false);
+    let pending_link = source.with_file_name(link_name(name));
+    if linked {
+        if let Err(e) = create_link(&target, &pending_link) {
+            let _ = deletion::remove_path(&target);
+            return Err(e);
+        }
+    }
+
+    let removed = delete_and_report(&app, cancelled, &protected, &store, &path, false, false);
+    audit::record(
+        &app,
+        "archive_item",
+        action.as_deref(),
+        vec![path.clone(), target.to_string_lossy().to_string()],
+        removed
+            .as_ref()
+            .map_or(0, |report| report.bytes_reclaimed()),
+        removed
+            .as_ref()
+            .map_or_else(Some, deletion::DeletionReport::cancel_error),
+    );
+    if !removed.as_ref().is_ok_and(|report| report.is_complete()) {
+        if linked {
+            let _ = deletion::remove_path(&pending_link);
+        }
+        if removed?.is_cancelled() {
+            return Err(DiskSenseError::Cancelled);
+        }
+        return Err(DiskSenseError::Failed(format!(
+            "{} was copied to {} but could not be fully removed",
+            source.display(),
+            target.display()
+        )));
+    }
+
+    if linked {
+        std::fs::rename(&pending_link, &source).map_err(|e| DiskSenseError::from_io(e, &source))?;
+    }
+
+    Ok(ArchiveResult {
+        archived_to: target.to_string_lossy().to_string(),
+        files,
+        bytes,
+        linked,
+    })
+}
+
+// Removing the original would take a copy made inside it along, so the
+// destination must not be the item or lie within it, whichever links lead there
+fn ensure_outside(source: &Path, dest_dir: &Path) -> Result<(), DiskSenseError> {
+    if resolve(dest_dir).starts_with(resolve(source)) {
+        return Err(DiskSenseError::InvalidInput(
+            "Cannot archive a folder into itself".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// The path with links resolved. A path that doesn't exist yet is resolved up
+// to its nearest existing folder.
+fn resolve(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find_map(|existing| {
+            let resolved = dunce::canonicalize(existing).ok()?;
+            let rest = path.strip_prefix(existing).ok()?;
+            Some(resolved.join(rest))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+// Name of the link while the original is still in place
+fn link_name(name: &std::ffi::OsStr) -> std::ffi::OsString {
+    let mut link = std::ffi::OsString::from(".");
+    link.push(name);
+    link.push(".disksense-link");
+    link
+}
+
+// Copy `source` to `target` file by file, comparing hashes as it goes.
+// Returns the number of files and bytes copied.
+fn copy_verified(
+    app: &AppHandle,
+    source: &Path,
+    target: &Path,
+    cancelled: &AtomicBool,
+) -> Result<(u64, u64), DiskSenseError> {
+    let entries: Vec<_> = WalkDir::new(source)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .map_err(|e| {
+            DiskSenseError::Failed(format!("Failed to read {}: {}", source.display(), e))
+        })?;
+    let files_total = entries.iter().filter(|e| e.file_type().is_file()).count() as u64;
+
+    let (mut files, mut bytes) = (0, 0);
+    for entry in entries {
+        if cancelled.load(Ordering::SeqCst) {
+            return Err(DiskSenseError::Cancelled);
+        }
+        let relative = entry.path().strip_prefix(source).unwrap_or(Path::new(""));
+        let copy: PathBuf = target.join(relative);
+
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&copy).map_err(|e| DiskSenseError::from_io(e, &copy))?;
+        } else if entry.file_type().is_file() {
+            let _ = app.emit(
+                "archive-progress",
+                ArchiveProgress {
+                    files_done: files,
+                    files_total,
+                    current_path: entry.path().to_string_lossy().to_string(),
+                },
+            );
+            bytes += std::fs::copy(entry.path(), &copy)
+                .map_err(|e| DiskSenseError::from_io(e, &copy))?;
+            let original = hashing::hash_file(entry.path())
+                .map_err(|e| DiskSenseError::from_io(e, entry.path()))?;
+            let copied =
+                hashing::hash_file(&copy).map_err(|e| DiskSenseError::from_io(e, &copy))?;
+            if original != copied {
+                return Err(DiskSenseError::Failed(format!(
+                    "Copy of {} does not match the original",
+                    entry.path().display()
+                )));
+            }
+            files += 1;
+        } else {
+            // Links inside the tree would point somewhere else once moved
+            return Err(DiskSenseError::InvalidInput(format!(
+                "{} contains a link and cannot be archived",
+                source.display()
+            )));
+        }
+    }
+
+    Ok((files, bytes))
+}
+
+#[cfg(unix)]
+fn create_link(target: &Path, link: &Path) -> Result<(), DiskSenseError> {
+    std::os::unix::fs::symlink(target, link).map_err(|e| DiskSenseError::from_io(e, link))
+}
+
+#[cfg(windows)]
+fn create_link(target: &Path, link: &Path) -> Result<(), DiskSenseError> {
+    if !target.is_dir() {
+        // File symlinks need Developer Mode or admin rights
+        return std::os::windows::fs::symlink_file(target, link)
+            .map_err(|e| DiskSenseError::from_io(e, link));
+    }
+
+    // Junctions need no special rights, unlike directory symlinks
+    let status = std::process::Command::new("cmd")
+        .arg("/C")
+        .arg("mklink")
+        .arg("/J")
+        .arg(link)
+        .arg(target)
+        .status()
+        .map_err(|e| DiskSenseError::from_io(e, link))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "Failed to create a junction at {}",
+            link.display()
+        )))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn create_link(_target: &Path, _link: &Path) -> Result<(), DiskSenseError> {
+    Err(DiskSenseError::Unsupported(
+        "Links are not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let dir = std::env::temp_dir().join(format!(
+            "disksense-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn destination_inside_source_is_refused() {
+        let source = temp_dir("offload-source");
+        assert!(ensure_outside(&source, &source).is_err());
+        assert!(ensure_outside(&source, &source.join("archive")).is_err());
+        assert!(ensure_outside(&source, &source.join("a").join("b")).is_err());
+        assert!(ensure_outside(&source, &source.join("..").join("elsewhere")).is_ok());
+        std::fs::remove_dir_all(&source).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn destination_reached_through_a_link_is_refused() {
+        let source = temp_dir("offload-linked");
+        let outside = temp_dir("offload-outside");
+        let link = outside.join("into-source");
+        std::os::unix::fs::symlink(&source, &link).unwrap();
+
+        assert!(ensure_outside(&source, &link.join("archive")).is_err());
+        assert!(ensure_outside(&link, &source.join("archive")).is_err());
+        std::fs::remove_dir_all(&outside).unwrap();
+        std::fs::remove_dir_all(&source).unwrap();
+    }
+
+    #[test]
+    fn missing_folders_resolve_below_their_existing_parent() {
+        let base = temp_dir("offload-resolve");
+        let missing = base.join("not").join("yet");
+        assert_eq!(
+            resolve(&missing),
+            dunce::canonicalize(&base).unwrap().join("not").join("yet")
+        );
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn pending_link_is_a_hidden_sibling_name() {
+        assert_eq!(
+            link_name(std::ffi::OsStr::new("photos")),
+            std::ffi::OsString::from(".photos.disksense-link")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn pending_link_moves_into_place() {
+        let base = temp_dir("offload-link");
+        let target = base.join("copy");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("kept.txt"), b"kept").unwrap();
+        let pending = base.join(link_name(std::ffi::OsStr::new("original")));
+
+        create_link(&target, &pending).unwrap();
+        std::fs::rename(&pending, base.join("original")).unwrap();
+
+        assert_eq!(
+            std::fs::read(base.join("original").join("kept.txt")).unwrap(),
+            b"kept"
+        );
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,82 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+// Marks a path string that carries the exact OS encoding as hex: bytes on Unix,
+// UTF-16 code units on Windows
+const RAW_PREFIX: &str = "os-raw:";
+
+// Exact form of a path that doesn't survive conversion to a string (invalid
+// UTF-8 on Unix, unpaired surrogates on Windows). None for every other path.
+pub(crate) fn encode(path: &Path) -> Option<String> {
+    if path.to_str().is_some() {
+        return None;
+    }
+
+    let mut encoded = String::from(RAW_PREFIX);
+    for unit in os_units(path) {
+        encoded.push_str(&format!("{:0width$x}", unit, width = UNIT_WIDTH));
+    }
+    Some(encoded)
+}
+
+// Path from a command argument, which is either a plain path or a raw_path
+// from a scanned item. Anything that fails to decode is taken literally.
+pub(crate) fn decode(path: &str) -> PathBuf {
+    path.strip_prefix(RAW_PREFIX)
+        .and_then(decode_units)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(path))
+}
+
+#[cfg(unix)]
+const UNIT_WIDTH: usize = 2;
+#[cfg(windows)]
+const UNIT_WIDTH: usize = 4;
+
+#[cfg(unix)]
+fn os_units(path: &Path) -> Vec<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str()
+        .as_bytes()
+        .iter()
+        .map(|b| u64::from(*b))
+        .collect()
+}
+
+#[cfg(windows)]
+fn os_units(path: &Path) -> Vec<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str().encode_wide().map(u64::from).collect()
+}
+
+#[cfg(unix)]
+fn decode_units(hex: &str) -> Option<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    let bytes = parse_units(hex)?
+        .into_iter()
+        .map(u8::try_from)
+        .collect::<Result<Vec<u8>, _>>()
+        .ok()?;
+    Some(OsString::from_vec(bytes))
+}
+
+#[cfg(windows)]
+fn decode_units(hex: &str) -> Option<OsString> {
+    use std::os::windows::ffi::OsStringExt;
+    let wide = parse_units(hex)?
+        .into_iter()
+        .map(u16::try_from)
+        .collect::<Result<Vec<u16>, _>>()
+        .ok()?;
+    Some(OsString::from_wide(&wide))
+}
+
+fn parse_units(hex: &str) -> Option<Vec<u64>> {
+    if !hex.is_ascii() || hex.len() % UNIT_WIDTH != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(UNIT_WIDTH)
+        .map(|i| u64::from_str_radix(&hex[i..i + UNIT_WIDTH], 16).ok())
+        .collect()
+}
@@ -0,0 +1,195 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, home_dir, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageCacheInfo {
+    pub(crate) manager: String,
+    pub(crate) paths: Vec<String>,
+    pub(crate) size: u64,
+    clean_command: String,
+}
+
+struct PackageManager {
+    name: &'static str,
+    program: &'static str,
+    clean_args: &'static [&'static str],
+}
+
+// Official clean commands for each supported package manager
+const MANAGERS: [PackageManager; 7] = [
+    PackageManager {
+        name: "apt",
+        program: "apt-get",
+        clean_args: &["clean"],
+    },
+    PackageManager {
+        name: "dnf",
+        program: "dnf",
+        clean_args: &["clean", "all"],
+    },
+    PackageManager {
+        name: "pacman",
+        program: "pacman",
+        clean_args: &["-Sc", "--noconfirm"],
+    },
+    PackageManager {
+        name: "brew",
+        program: "brew",
+        clean_args: &["cleanup", "-s"],
+    },
+    PackageManager {
+        name: "npm",
+        program: "npm",
+        clean_args: &["cache", "clean", "--force"],
+    },
+    PackageManager {
+        name: "pip",
+        program: "pip",
+        clean_args: &["cache", "purge"],
+    },
+    PackageManager {
+        name: "cargo",
+        program: "cargo",
+        clean_args: &["cache", "--autoclean"],
+    },
+];
+
+#[command]
+pub async fn get_package_cache_sizes() -> Result<Vec<PackageCacheInfo>, DiskSenseError> {
+    let mut caches: Vec<PackageCacheInfo> = MANAGERS
+        .iter()
+        .filter_map(|manager| {
+            let paths: Vec<PathBuf> = cache_paths(manager.name)
+                .into_iter()
+                .filter(|path| path.exists())
+                .collect();
+
+            // Only report managers whose cache actually exists on this machine
+            if paths.is_empty() {
+                return None;
+            }
+
+            let size = paths.iter().map(|path| get_size(path).unwrap_or(0)).sum();
+
+            Some(PackageCacheInfo {
+                manager: manager.name.to_string(),
+                paths: paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                size,
+                clean_command: format!("{} {}", manager.program, manager.clean_args.join(" ")),
+            })
+        })
+        .collect();
+
+    caches.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    Ok(caches)
+}
+
+#[command]
+pub async fn clean_package_cache(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    manager: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    // Cleaning runs an external tool with side effects, so require explicit confirmation
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Cache clean must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    let before = cache_size(&manager);
+    let result = clean_cache(&manager);
+    audit::record(
+        &app,
+        "clean_package_cache",
+        action.as_deref(),
+        cache_paths(&manager)
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        before.saturating_sub(cache_size(&manager)),
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn cache_size(manager: &str) -> u64 {
+    cache_paths(manager)
+        .iter()
+        .map(|path| get_size(path).unwrap_or(0))
+        .sum()
+}
+
+pub(crate) fn clean_cache(manager: &str) -> Result<String, DiskSenseError> {
+    let manager = MANAGERS.iter().find(|m| m.name == manager).ok_or_else(|| {
+        DiskSenseError::InvalidInput(format!("Unsupported package manager: {}", manager))
+    })?;
+
+    let output = Command::new(manager.program)
+        .args(manager.clean_args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, manager.program))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "{} cache clean failed: {}",
+            manager.name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+// Standard cache locations for a package manager on the current platform
+fn cache_paths(manager: &str) -> Vec<PathBuf> {
+    let home = match home_dir() {
+        Some(home) => home,
+        None => return Vec::new(),
+    };
+
+    match manager {
+        "apt" => vec![PathBuf::from("/var/cache/apt/archives")],
+        "dnf" => vec![PathBuf::from("/var/cache/dnf")],
+        "pacman" => vec![PathBuf::from("/var/cache/pacman/pkg")],
+        "brew" => vec![
+            home.join("Library/Caches/Homebrew"),
+            home.join(".cache/Homebrew"),
+        ],
+        "npm" => {
+            if cfg!(target_os = "windows") {
+                vec![home.join(r"AppData\Local\npm-cache")]
+            } else {
+                vec![home.join(".npm/_cacache")]
+            }
+        }
+        "pip" => {
+            if cfg!(target_os = "windows") {
+                vec![home.join(r"AppData\Local\pip\Cache")]
+            } else if cfg!(target_os = "macos") {
+                vec![home.join("Library/Caches/pip")]
+            } else {
+                vec![home.join(".cache/pip")]
+            }
+        }
+        "cargo" => {
+            let cargo_home = std::env::var_os("CARGO_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".cargo"));
+            vec![cargo_home.join("registry"), cargo_home.join("git")]
+        }
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+// Minimum time between "scan-partial-sizes" events
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartialSize {
+    path: String,
+    // Bytes found so far; only grows until the scan result replaces it
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PartialSizes {
+    root: String,
+    sizes: Vec<PartialSize>,
+}
+
+// Running totals for each top-level folder of a scan, so the treemap can fill
+// in while deep folders are still being walked
+pub(crate) struct PartialSizeTracker {
+    root: PathBuf,
+    sizes: Mutex<HashMap<PathBuf, u64>>,
+    last_emit: Mutex<Instant>,
+}
+
+impl PartialSizeTracker {
+    pub(crate) fn new(root: &Path) -> Self {
+        PartialSizeTracker {
+            root: root.to_path_buf(),
+            sizes: Mutex::new(HashMap::new()),
+            last_emit: Mutex::new(Instant::now()),
+        }
+    }
+
+    // Count `bytes` found at or below `path` towards its top-level folder.
+    // Each byte must be added exactly once, at the directory that measured it.
+    pub(crate) fn add(&self, app: &AppHandle, path: &Path, bytes: u64) {
+        let top_level = match path
+            .strip_prefix(&self.root)
+            .ok()
+            .and_then(|relative| relative.components().next())
+        {
+            Some(first) => self.root.join(first),
+            // Files directly in the root are final as soon as they are listed
+            None => return,
+        };
+        if bytes == 0 {
+            return;
+        }
+
+        let snapshot = {
+            let mut sizes = self.sizes.lock().unwrap_or_else(|e| e.into_inner());
+            *sizes.entry(top_level).or_default() += bytes;
+
+            let mut last_emit = self.last_emit.lock().unwrap_or_else(|e| e.into_inner());
+            if last_emit.elapsed() < EMIT_INTERVAL {
+                return;
+            }
+            *last_emit = Instant::now();
+
+            sizes
+                .iter()
+                .map(|(path, size)| PartialSize {
+                    path: path.to_string_lossy().to_string(),
+                    size: *size,
+                })
+                .collect()
+        };
+
+        let _ = app.emit(
+            "scan-partial-sizes",
+            PartialSizes {
+                root: self.root.to_string_lossy().to_string(),
+                sizes: snapshot,
+            },
+        );
+    }
+}
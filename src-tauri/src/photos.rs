@@ -0,0 +1,248 @@
+use crate::DiskSenseError;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::command;
+use walkdir::WalkDir;
+
+const RAW_EXTENSIONS: [&str; 11] = [
+    "cr2", "cr3", "nef", "arw", "dng", "raf", "orf", "rw2", "pef", "srw", "raw",
+];
+const JPEG_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "heic"];
+
+// Shots taken closer together than this are treated as one burst
+const DEFAULT_BURST_GAP_SECONDS: f64 = 1.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PhotoPair {
+    raw: String,
+    jpeg: String,
+    raw_size: u64,
+    jpeg_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PhotoBurst {
+    // Every file of every shot in the burst, in capture order
+    files: Vec<String>,
+    shots: usize,
+    total_size: u64,
+    // Space freed by keeping only the largest shot
+    reclaimable: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PhotoReport {
+    pairs: Vec<PhotoPair>,
+    bursts: Vec<PhotoBurst>,
+    // Space freed by dropping the JPEG (or the RAW) of every pair
+    pair_reclaimable_keep_raw: u64,
+    pair_reclaimable_keep_jpeg: u64,
+    burst_reclaimable: u64,
+}
+
+// One exposure: a RAW, a JPEG, or both sharing a file stem
+struct Shot {
+    files: Vec<(PathBuf, u64)>,
+    taken: f64,
+}
+
+#[command]
+pub async fn analyze_photos(
+    root: String,
+    burst_gap_seconds: Option<f64>,
+) -> Result<PhotoReport, DiskSenseError> {
+    if !Path::new(&root).is_dir() {
+        return Err(DiskSenseError::NotFound { path: root });
+    }
+    let burst_gap = burst_gap_seconds.unwrap_or(DEFAULT_BURST_GAP_SECONDS);
+
+    // Group photos by folder and file stem, so IMG_0001.CR2 and IMG_0001.JPG form one shot
+    let mut stems: HashMap<(PathBuf, String), Vec<(PathBuf, u64)>> = HashMap::new();
+    for entry in WalkDir::new(&root)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+    {
+        let path = entry.path();
+        if photo_kind(path).is_none() {
+            continue;
+        }
+        let (parent, stem) = match (path.parent(), path.file_stem()) {
+            (Some(parent), Some(stem)) => {
+                (parent.to_path_buf(), stem.to_string_lossy().to_lowercase())
+            }
+            _ => continue,
+        };
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        stems
+            .entry((parent, stem))
+            .or_default()
+            .push((path.to_path_buf(), size));
+    }
+
+    let mut pairs = Vec::new();
+    for files in stems.values() {
+        let raw = files
+            .iter()
+            .find(|(path, _)| photo_kind(path) == Some(PhotoKind::Raw));
+        let jpeg = files
+            .iter()
+            .find(|(path, _)| photo_kind(path) == Some(PhotoKind::Jpeg));
+        if let (Some((raw, raw_size)), Some((jpeg, jpeg_size))) = (raw, jpeg) {
+            pairs.push(PhotoPair {
+                raw: raw.to_string_lossy().to_string(),
+                jpeg: jpeg.to_string_lossy().to_string(),
+                raw_size: *raw_size,
+                jpeg_size: *jpeg_size,
+            });
+        }
+    }
+    pairs.sort_by(|a, b| a.raw.cmp(&b.raw));
+
+    // Capture times come from EXIF, which means opening every file; do it in parallel
+    let mut by_folder: HashMap<PathBuf, Vec<Shot>> = HashMap::new();
+    let shots: Vec<(PathBuf, Shot)> = stems
+        .into_par_iter()
+        .map(|((parent, _), files)| {
+            let taken = files
+                .iter()
+                .find_map(|(path, _)| capture_time(path))
+                .unwrap_or(0.0);
+            (parent, Shot { files, taken })
+        })
+        .collect();
+    for (parent, shot) in shots {
+        by_folder.entry(parent).or_default().push(shot);
+    }
+
+    let mut bursts = Vec::new();
+    for mut shots in by_folder.into_values() {
+        shots.sort_by(|a, b| a.taken.total_cmp(&b.taken));
+
+        let mut current: Vec<Shot> = Vec::new();
+        for shot in shots {
+            let continues = current
+                .last()
+                .is_some_and(|last| last.taken > 0.0 && shot.taken - last.taken <= burst_gap);
+            if !continues {
+                bursts.extend(finish_burst(std::mem::take(&mut current)));
+            }
+            current.push(shot);
+        }
+        bursts.extend(finish_burst(current));
+    }
+    bursts.sort_by_key(|item| std::cmp::Reverse(item.reclaimable));
+
+    Ok(PhotoReport {
+        pair_reclaimable_keep_raw: pairs.iter().map(|pair| pair.jpeg_size).sum(),
+        pair_reclaimable_keep_jpeg: pairs.iter().map(|pair| pair.raw_size).sum(),
+        burst_reclaimable: bursts.iter().map(|burst| burst.reclaimable).sum(),
+        pairs,
+        bursts,
+    })
+}
+
+fn finish_burst(shots: Vec<Shot>) -> Option<PhotoBurst> {
+    if shots.len() < 2 {
+        return None;
+    }
+
+    let shot_sizes: Vec<u64> = shots
+        .iter()
+        .map(|shot| shot.files.iter().map(|(_, size)| size).sum())
+        .collect();
+    let total_size: u64 = shot_sizes.iter().sum();
+    let largest = shot_sizes.iter().copied().max().unwrap_or(0);
+
+    Some(PhotoBurst {
+        files: shots
+            .iter()
+            .flat_map(|shot| shot.files.iter())
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect(),
+        shots: shots.len(),
+        total_size,
+        reclaimable: total_size - largest,
+    })
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum PhotoKind {
+    Raw,
+    Jpeg,
+}
+
+fn photo_kind(path: &Path) -> Option<PhotoKind> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if RAW_EXTENSIONS.contains(&extension.as_str()) {
+        Some(PhotoKind::Raw)
+    } else if JPEG_EXTENSIONS.contains(&extension.as_str()) {
+        Some(PhotoKind::Jpeg)
+    } else {
+        None
+    }
+}
+
+// Capture time in seconds (EXIF DateTimeOriginal + sub-seconds), falling back to
+// the modification time for files without EXIF
+fn capture_time(path: &Path) -> Option<f64> {
+    exif_time(path).or_else(|| {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs_f64())
+    })
+}
+
+fn exif_time(path: &Path) -> Option<f64> {
+    use exif::{In, Tag, Value};
+
+    let file = File::open(path).ok()?;
+    let exif = exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()?;
+
+    let field = exif.get_field(Tag::DateTimeOriginal, In::PRIMARY)?;
+    let date = match &field.value {
+        Value::Ascii(values) => exif::DateTime::from_ascii(values.first()?).ok()?,
+        _ => return None,
+    };
+
+    let subsec = exif
+        .get_field(Tag::SubSecTimeOriginal, In::PRIMARY)
+        .and_then(|field| match &field.value {
+            Value::Ascii(values) => values
+                .first()
+                .map(|v| String::from_utf8_lossy(v).to_string()),
+            _ => None,
+        })
+        .and_then(|digits| format!("0.{}", digits.trim()).parse::<f64>().ok())
+        .unwrap_or(0.0);
+
+    // Only differences matter, so the camera's local time zone can be ignored
+    let days = days_from_civil(
+        i64::from(date.year),
+        i64::from(date.month),
+        i64::from(date.day),
+    );
+    let seconds = days * 86_400
+        + i64::from(date.hour) * 3600
+        + i64::from(date.minute) * 60
+        + i64::from(date.second);
+    Some(seconds as f64 + subsec)
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
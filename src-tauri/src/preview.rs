@@ -0,0 +1,85 @@
+use crate::{delta, estimate_dir_size, os_path, visibility, DiskItem, ScanOptions};
+use rayon::prelude::*;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Time the preview may spend listing before it estimates everything left
+const PREVIEW_BUDGET: Duration = Duration::from_millis(1500);
+// The preview never lists deeper than this
+const PREVIEW_DEPTH: usize = 2;
+
+// Rough tree for the first pass of a two-pass scan. Files are sized from their
+// metadata; folders at the preview depth, or reached after the time budget is
+// spent, are estimated from a sample of their entries and flagged `estimated`.
+pub(crate) fn instant_tree(path: &Path, max_depth: usize, options: &ScanOptions) -> DiskItem {
+    let deadline = Instant::now() + PREVIEW_BUDGET;
+    let mut root = list(path, max_depth.min(PREVIEW_DEPTH), options, deadline);
+    delta::assign_ids(&mut root);
+    root
+}
+
+fn list(dir_path: &Path, depth: usize, options: &ScanOptions, deadline: Instant) -> DiskItem {
+    let mut item = node(dir_path, 0, true);
+    let entries: Vec<_> = match std::fs::read_dir(dir_path) {
+        Ok(entries) => entries.filter_map(Result::ok).collect(),
+        Err(_) => return item,
+    };
+
+    let mut children: Vec<DiskItem> = entries
+        .par_iter()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if options.skip_hidden
+                && visibility::is_hidden(&entry.file_name().to_string_lossy(), &path)
+            {
+                return None;
+            }
+
+            let file_type = entry.file_type().ok()?;
+            if !file_type.is_dir() {
+                let size = entry.metadata().map_or(0, |m| m.len());
+                return Some(node(&path, size, false));
+            }
+
+            if depth > 0 && Instant::now() < deadline {
+                Some(list(&path, depth - 1, options, deadline))
+            } else {
+                let mut estimate = node(&path, estimate_dir_size(&path), true);
+                estimate.estimated = true;
+                Some(estimate)
+            }
+        })
+        .collect();
+
+    children.sort_by_key(|item| std::cmp::Reverse(item.size));
+    item.size = children.iter().map(|child| child.size).sum();
+    item.estimated = children.iter().any(|child| child.estimated);
+    item.children = Some(children);
+    item
+}
+
+fn node(path: &Path, size: u64, is_dir: bool) -> DiskItem {
+    DiskItem {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string()),
+        path: path.to_string_lossy().to_string(),
+        size,
+        is_dir,
+        children: is_dir.then(Vec::new),
+        game: None,
+        git_repo: false,
+        id: String::new(),
+        allocated_size: None,
+        sparse: false,
+        link_target: None,
+        hard_links: None,
+        in_archive: false,
+        raw_path: os_path::encode(path),
+        estimated: false,
+        hidden: false,
+        known_folder: None,
+        allocated_partial: false,
+    }
+}
@@ -0,0 +1,46 @@
+// Lower the CPU and I/O priority of the calling thread so that a background
+// scan yields to interactive work. Failures are ignored: the scan still runs,
+// just at normal priority.
+pub(crate) fn lower_current_thread_priority() {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use winapi::um::processthreadsapi::{GetCurrentThread, SetThreadPriority};
+        use winapi::um::winbase::THREAD_MODE_BACKGROUND_BEGIN;
+
+        // Background mode lowers both scheduling and I/O priority (very low I/O hint)
+        SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN as i32);
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe {
+        // ioprio_set(IOPRIO_WHO_PROCESS, 0 = calling thread, IOPRIO_CLASS_IDLE)
+        const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+        const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        );
+
+        // On Linux, setpriority with who = 0 only affects the calling thread
+        libc::setpriority(libc::PRIO_PROCESS, 0, 19);
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        extern "C" {
+            fn setiopolicy_np(
+                iotype: libc::c_int,
+                scope: libc::c_int,
+                policy: libc::c_int,
+            ) -> libc::c_int;
+        }
+        const IOPOL_TYPE_DISK: libc::c_int = 0;
+        const IOPOL_SCOPE_THREAD: libc::c_int = 1;
+        const IOPOL_THROTTLE: libc::c_int = 3;
+
+        setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_THREAD, IOPOL_THROTTLE);
+    }
+}
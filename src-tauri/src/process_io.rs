@@ -0,0 +1,103 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{command, State};
+
+// Time between samples of every process's I/O counters
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessDiskActivity {
+    pid: u32,
+    name: String,
+    // Totals since monitoring started
+    read_bytes: u64,
+    written_bytes: u64,
+    // Bytes per second over the latest sample
+    read_rate: u64,
+    write_rate: u64,
+}
+
+// Samples per-process disk I/O in the background once first asked for it
+#[derive(Default)]
+pub struct ProcessIoMonitor {
+    started: OnceLock<()>,
+    activity: Arc<Mutex<HashMap<u32, ProcessDiskActivity>>>,
+}
+
+impl ProcessIoMonitor {
+    fn start(&self) {
+        self.started.get_or_init(|| {
+            let activity = self.activity.clone();
+            std::thread::Builder::new()
+                .name("disksense-process-io".to_string())
+                .spawn(move || sample_loop(&activity))
+                .ok();
+        });
+    }
+}
+
+// Processes by current write rate, busiest first. The first call starts the
+// monitor, so rates appear after one sample interval.
+#[command]
+pub async fn get_process_disk_activity(
+    monitor: State<'_, ProcessIoMonitor>,
+) -> Result<Vec<ProcessDiskActivity>, DiskSenseError> {
+    monitor.start();
+
+    let mut activity: Vec<ProcessDiskActivity> = monitor
+        .activity
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .filter(|process| process.read_bytes > 0 || process.written_bytes > 0)
+        .cloned()
+        .collect();
+    activity.sort_by(|a, b| {
+        b.write_rate
+            .cmp(&a.write_rate)
+            .then(b.written_bytes.cmp(&a.written_bytes))
+    });
+
+    Ok(activity)
+}
+
+// sysinfo reads GetProcessIoCounters on Windows, /proc/<pid>/io on Linux and
+// proc_pid_rusage on macOS
+fn sample_loop(activity: &Mutex<HashMap<u32, ProcessDiskActivity>>) {
+    let refresh = ProcessRefreshKind::nothing().with_disk_usage();
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+    let mut last_sample = Instant::now();
+
+    loop {
+        std::thread::sleep(SAMPLE_INTERVAL);
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        let elapsed = last_sample.elapsed().as_secs_f64().max(0.001);
+        last_sample = Instant::now();
+
+        let mut activity = activity.lock().unwrap_or_else(|e| e.into_inner());
+        activity.retain(|pid, _| system.process(sysinfo::Pid::from_u32(*pid)).is_some());
+
+        for (pid, process) in system.processes() {
+            let usage = process.disk_usage();
+            let entry = activity
+                .entry(pid.as_u32())
+                .or_insert_with(|| ProcessDiskActivity {
+                    pid: pid.as_u32(),
+                    name: process.name().to_string_lossy().to_string(),
+                    read_bytes: 0,
+                    written_bytes: 0,
+                    read_rate: 0,
+                    write_rate: 0,
+                });
+            entry.read_bytes += usage.read_bytes;
+            entry.written_bytes += usage.written_bytes;
+            entry.read_rate = (usage.read_bytes as f64 / elapsed) as u64;
+            entry.write_rate = (usage.written_bytes as f64 / elapsed) as u64;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::sparse;
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::fs::Metadata;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ItemProperties {
+    path: String,
+    name: String,
+    size: u64,
+    allocated_size: Option<u64>,
+    is_dir: bool,
+    is_symlink: bool,
+    link_target: Option<String>,
+    hard_links: Option<u64>,
+    readonly: bool,
+    modified: Option<u64>,
+    created: Option<u64>,
+}
+
+#[command]
+pub async fn get_item_properties(path: String) -> Result<ItemProperties, DiskSenseError> {
+    let item = &crate::os_path::decode(&path);
+    let link_metadata =
+        std::fs::symlink_metadata(item).map_err(|e| DiskSenseError::from_io(e, &path))?;
+    let is_symlink = link_metadata.file_type().is_symlink();
+
+    // Describe what the link points to; a dangling link still describes itself
+    let metadata = if is_symlink {
+        std::fs::metadata(item).unwrap_or(link_metadata)
+    } else {
+        link_metadata
+    };
+
+    let seconds = |time: std::io::Result<std::time::SystemTime>| {
+        time.ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+    };
+
+    Ok(ItemProperties {
+        name: item
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone()),
+        size: metadata.len(),
+        allocated_size: if metadata.is_file() {
+            sparse::allocation(item, &metadata).0
+        } else {
+            None
+        },
+        is_dir: metadata.is_dir(),
+        is_symlink,
+        link_target: if is_symlink { link_target(item) } else { None },
+        hard_links: if metadata.is_file() {
+            hard_link_count(item, &metadata)
+        } else {
+            None
+        },
+        readonly: metadata.permissions().readonly(),
+        modified: seconds(metadata.modified()),
+        created: seconds(metadata.created()),
+        path,
+    })
+}
+
+// Fully resolved target of a symlink or junction
+pub(crate) fn link_target(path: &Path) -> Option<String> {
+    let target = dunce::canonicalize(path)
+        .or_else(|_| std::fs::read_link(path))
+        .ok()?;
+    Some(target.to_string_lossy().to_string())
+}
+
+// Number of directory entries sharing this file's data
+pub(crate) fn hard_link_count(path: &Path, metadata: &Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let _ = path;
+        Some(metadata.nlink())
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // The link count is not part of a directory listing; the file must be opened
+        use std::os::windows::io::AsRawHandle;
+        use winapi::um::fileapi::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION};
+
+        let _ = metadata;
+        let file = std::fs::File::open(path).ok()?;
+        let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+        let ok = unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) };
+        if ok == 0 {
+            None
+        } else {
+            Some(u64::from(info.nNumberOfLinks))
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{home_dir, DiskSenseError, ScanStore};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use sysinfo::Disks;
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir holding the user's own protected paths
+const PROTECTED_PATHS_FILE: &str = "protected_paths.json";
+
+// System locations whose entire contents must never be removed
+#[cfg(target_os = "windows")]
+const PROTECTED_TREES: [&str; 7] = [
+    r"c:\windows",
+    r"c:\program files",
+    r"c:\program files (x86)",
+    r"c:\system volume information",
+    r"c:\$recycle.bin",
+    r"c:\recovery",
+    r"c:\boot",
+];
+#[cfg(target_os = "macos")]
+const PROTECTED_TREES: [&str; 9] = [
+    "/System", "/Library", "/bin", "/sbin", "/usr", "/etc", "/dev", "/private", "/cores",
+];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PROTECTED_TREES: [&str; 11] = [
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib64", "/proc", "/sbin", "/sys", "/usr", "/run",
+];
+
+// Locations whose contents users manage, but which must not be removed themselves
+#[cfg(target_os = "windows")]
+const PROTECTED_ROOTS: [&str; 3] = [r"c:\users", r"c:\programdata", r"c:\users\public"];
+#[cfg(target_os = "macos")]
+const PROTECTED_ROOTS: [&str; 4] = ["/Applications", "/Users", "/Volumes", "/opt"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const PROTECTED_ROOTS: [&str; 6] = ["/home", "/root", "/var", "/opt", "/srv", "/mnt"];
+
+// User-extensible protected paths, persisted in the app data dir
+#[derive(Default)]
+pub struct ProtectedPaths {
+    user: Mutex<Vec<String>>,
+}
+
+impl ProtectedPaths {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let user = protected_paths_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        ProtectedPaths {
+            user: Mutex::new(user),
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = protected_paths_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let user = self.user.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = serde_json::to_string_pretty(&*user).map_err(|e| {
+            DiskSenseError::Failed(format!("Failed to save protected paths: {}", e))
+        })?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    // The user's own paths; the built-in ones are never exported
+    pub(crate) fn user_paths(&self) -> Vec<String> {
+        self.user.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub(crate) fn import(&self, app: &AppHandle, paths: Vec<String>) -> Result<(), DiskSenseError> {
+        *self.user.lock().unwrap_or_else(|e| e.into_inner()) = paths;
+        self.save(app)
+    }
+}
+
+fn protected_paths_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(PROTECTED_PATHS_FILE))
+}
+
+#[command]
+pub async fn get_protected_paths(
+    protected: State<'_, ProtectedPaths>,
+) -> Result<Vec<String>, DiskSenseError> {
+    let mut paths: Vec<String> = PROTECTED_TREES
+        .iter()
+        .chain(PROTECTED_ROOTS.iter())
+        .map(|path| path.to_string())
+        .collect();
+    paths.extend(
+        protected
+            .user
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned(),
+    );
+    Ok(paths)
+}
+
+#[command]
+pub async fn add_protected_path(
+    app: AppHandle,
+    protected: State<'_, ProtectedPaths>,
+    path: String,
+) -> Result<(), DiskSenseError> {
+    {
+        let mut user = protected.user.lock().unwrap_or_else(|e| e.into_inner());
+        if !user.contains(&path) {
+            user.push(path);
+        }
+    }
+    protected.save(&app)
+}
+
+#[command]
+pub async fn remove_protected_path(
+    app: AppHandle,
+    protected: State<'_, ProtectedPaths>,
+    path: String,
+) -> Result<(), DiskSenseError> {
+    protected
+        .user
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|p| p != &path);
+    protected.save(&app)
+}
+
+// Refuse to destroy a protected path, anything containing one, or the contents of a
+// protected tree, unless the caller explicitly overrides the guard
+pub(crate) fn ensure_deletable(
+    path: &Path,
+    protected: &ProtectedPaths,
+    store: &ScanStore,
+    allow_override: bool,
+) -> Result<(), DiskSenseError> {
+    if allow_override {
+        return Ok(());
+    }
+
+    // Resolve only the parent: a symlink is judged by where it lives, not where it points
+    let target = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => dunce::canonicalize(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()),
+    };
+    let refuse = || {
+        Err(DiskSenseError::Protected {
+            path: path.to_string_lossy().to_string(),
+        })
+    };
+
+    let user_paths = protected
+        .user
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+
+    // The target itself, or anything inside, is protected
+    let trees = PROTECTED_TREES
+        .iter()
+        .map(PathBuf::from)
+        .chain(user_paths.iter().map(PathBuf::from));
+    for tree in trees {
+        if contains(&tree, &target) || contains(&target, &tree) {
+            return refuse();
+        }
+    }
+
+    // Only the root itself (and its ancestors) are protected
+    let mut roots: Vec<PathBuf> = PROTECTED_ROOTS.iter().map(PathBuf::from).collect();
+    roots.extend(home_dir());
+    roots.extend(
+        Disks::new_with_refreshed_list()
+            .iter()
+            .map(|disk| disk.mount_point().to_path_buf()),
+    );
+    roots.extend(store.root_paths().into_iter().map(PathBuf::from));
+
+    if roots.iter().any(|root| contains(&target, root)) {
+        return refuse();
+    }
+
+    Ok(())
+}
+
+// Whether `outer` is `inner` or one of its ancestors; case-insensitive on Windows
+fn contains(outer: &Path, inner: &Path) -> bool {
+    if cfg!(target_os = "windows") {
+        let outer = PathBuf::from(outer.to_string_lossy().to_lowercase());
+        let inner = PathBuf::from(inner.to_string_lossy().to_lowercase());
+        inner.starts_with(outer)
+    } else {
+        inner.starts_with(outer)
+    }
+}
@@ -0,0 +1,109 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir holding the read-only setting
+const READ_ONLY_FILE: &str = "read_only.json";
+
+// Set to 1 to force read-only mode, e.g. on servers; it then can't be turned off
+const READ_ONLY_ENV: &str = "DISKSENSE_READ_ONLY";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReadOnlySettings {
+    enabled: bool,
+    // True when forced by the environment and not changeable from the app
+    locked: bool,
+}
+
+// Audit mode: scans and reports work, but every command that deletes, moves or
+// changes data is rejected with ReadOnly. Persisted in the app data dir.
+#[derive(Default)]
+pub struct ReadOnlyMode {
+    enabled: AtomicBool,
+    locked: bool,
+}
+
+impl ReadOnlyMode {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let locked = std::env::var(READ_ONLY_ENV).is_ok_and(|value| value == "1");
+        let saved = read_only_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str::<bool>(&contents).ok())
+            .unwrap_or(false);
+
+        ReadOnlyMode {
+            enabled: AtomicBool::new(locked || saved),
+            locked,
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = read_only_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let contents = serde_json::to_string(&self.enabled.load(Ordering::SeqCst))
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to save read-only mode: {}", e)))?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    // Called first by every destructive command
+    pub(crate) fn ensure_writable(&self) -> Result<(), DiskSenseError> {
+        if self.enabled.load(Ordering::SeqCst) {
+            Err(DiskSenseError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
+    // Apply an imported setting; a mode forced by the environment stays on
+    pub(crate) fn import(&self, app: &AppHandle, enabled: bool) -> Result<(), DiskSenseError> {
+        self.enabled.store(self.locked || enabled, Ordering::SeqCst);
+        self.save(app)
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    fn settings(&self) -> ReadOnlySettings {
+        ReadOnlySettings {
+            enabled: self.enabled.load(Ordering::SeqCst),
+            locked: self.locked,
+        }
+    }
+}
+
+fn read_only_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(READ_ONLY_FILE))
+}
+
+#[command]
+pub async fn get_read_only_mode(
+    mode: State<'_, ReadOnlyMode>,
+) -> Result<ReadOnlySettings, DiskSenseError> {
+    Ok(mode.settings())
+}
+
+#[command]
+pub async fn set_read_only_mode(
+    app: AppHandle,
+    mode: State<'_, ReadOnlyMode>,
+    enabled: bool,
+) -> Result<ReadOnlySettings, DiskSenseError> {
+    if mode.locked && !enabled {
+        return Err(DiskSenseError::ReadOnly);
+    }
+
+    mode.enabled.store(enabled, Ordering::SeqCst);
+    mode.save(&app)?;
+    Ok(mode.settings())
+}
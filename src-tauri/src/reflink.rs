@@ -0,0 +1,331 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, hashing, os_path, DiskSenseError};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ReflinkReport {
+    files_deduped: u64,
+    // Bytes no longer stored twice now that the copies share the kept file's data
+    bytes_shared: u64,
+    // Files left as they were; the rest are still deduplicated
+    failures: Vec<DiskSenseError>,
+}
+
+// Make the duplicates in `paths` share the data blocks of the first one. Unlike
+// hard links the files stay independent: each keeps its own name, permissions and
+// timestamps (on macOS not its owner, extended attributes or ACLs, see share_data),
+// and writing to one later never changes the others. Needs a file system with
+// shared extents: btrfs or XFS on Linux, APFS on macOS, ReFS on Windows.
+// Every copy is checked to be byte-identical to the first before it is touched.
+#[command]
+pub async fn dedupe_reflink(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    paths: Vec<String>,
+    action: Option<String>,
+) -> Result<ReflinkReport, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let (first, copies) = match paths.split_first() {
+        Some((first, copies)) if !copies.is_empty() => (first, copies),
+        _ => {
+            return Err(DiskSenseError::InvalidInput(
+                "Deduplicating needs a file to keep and at least one copy".to_string(),
+            ))
+        }
+    };
+
+    let source = os_path::decode(first);
+    let size = std::fs::metadata(&source)
+        .map_err(|e| DiskSenseError::from_io(e, &source))?
+        .len();
+    let hash = hashing::hash_file(&source).map_err(|e| DiskSenseError::from_io(e, &source))?;
+
+    let mut report = ReflinkReport::default();
+    for copy in copies {
+        let target = os_path::decode(copy);
+        match verify_copy(&source, &target, size, &hash)
+            .and_then(|_| share_data(&source, &target, size))
+        {
+            Ok(()) => {
+                report.files_deduped += 1;
+                report.bytes_shared += size;
+            }
+            Err(e) => report.failures.push(e),
+        }
+    }
+
+    audit::record(
+        &app,
+        "dedupe_reflink",
+        action.as_deref(),
+        paths.clone(),
+        report.bytes_shared,
+        report.failures.first(),
+    );
+    Ok(report)
+}
+
+fn verify_copy(source: &Path, target: &Path, size: u64, hash: &str) -> Result<(), DiskSenseError> {
+    let metadata =
+        std::fs::symlink_metadata(target).map_err(|e| DiskSenseError::from_io(e, target))?;
+    let same_file = dunce::canonicalize(source).ok() == dunce::canonicalize(target).ok();
+    let identical = metadata.is_file()
+        && !same_file
+        && metadata.len() == size
+        && hashing::hash_file(target).map_err(|e| DiskSenseError::from_io(e, target))? == hash;
+
+    if identical {
+        Ok(())
+    } else {
+        Err(DiskSenseError::InvalidInput(format!(
+            "{} is not a copy of {}",
+            target.display(),
+            source.display()
+        )))
+    }
+}
+
+// The kernel compares both ranges again and only shares them if they still match,
+// so a file changed since it was hashed is left alone
+#[cfg(target_os = "linux")]
+fn share_data(source: &Path, target: &Path, size: u64) -> Result<(), DiskSenseError> {
+    use std::fs::{File, OpenOptions};
+    use std::os::unix::io::AsRawFd;
+
+    // _IOWR(0x94, 54, struct file_dedupe_range)
+    const FIDEDUPERANGE: libc::c_ulong = 0xC018_9436;
+    // Larger requests are cut short by some file systems
+    const CHUNK: u64 = 16 * 1024 * 1024;
+    const FILE_DEDUPE_RANGE_DIFFERS: i32 = 1;
+
+    #[repr(C)]
+    struct DedupeRange {
+        src_offset: u64,
+        src_length: u64,
+        dest_count: u16,
+        reserved1: u16,
+        reserved2: u32,
+        // One destination
+        dest_fd: i64,
+        dest_offset: u64,
+        bytes_deduped: u64,
+        status: i32,
+        reserved: u32,
+    }
+
+    let src = File::open(source).map_err(|e| DiskSenseError::from_io(e, source))?;
+    let dest = OpenOptions::new()
+        .write(true)
+        .open(target)
+        .map_err(|e| DiskSenseError::from_io(e, target))?;
+
+    let mut offset = 0;
+    while offset < size {
+        let mut range = DedupeRange {
+            src_offset: offset,
+            src_length: (size - offset).min(CHUNK),
+            dest_count: 1,
+            reserved1: 0,
+            reserved2: 0,
+            dest_fd: i64::from(dest.as_raw_fd()),
+            dest_offset: offset,
+            bytes_deduped: 0,
+            status: 0,
+            reserved: 0,
+        };
+        if unsafe { libc::ioctl(src.as_raw_fd(), FIDEDUPERANGE as _, &mut range) } != 0 {
+            return Err(unsupported_or(std::io::Error::last_os_error(), target));
+        }
+        match range.status {
+            FILE_DEDUPE_RANGE_DIFFERS => {
+                return Err(DiskSenseError::InvalidInput(format!(
+                    "{} changed while it was being deduplicated",
+                    target.display()
+                )))
+            }
+            status if status < 0 => {
+                return Err(unsupported_or(
+                    std::io::Error::from_raw_os_error(-status),
+                    target,
+                ))
+            }
+            _ if range.bytes_deduped == 0 => {
+                return Err(DiskSenseError::Failed(format!(
+                    "No data of {} could be shared",
+                    target.display()
+                )))
+            }
+            _ => offset += range.bytes_deduped,
+        }
+    }
+
+    Ok(())
+}
+
+// APFS can only clone whole files, so the copy is replaced by a clone of the kept
+// file that is given the copy's permissions and timestamps. The copy's owner,
+// extended attributes and ACLs are not carried over, and other hard links to the
+// copy keep the old data.
+#[cfg(target_os = "macos")]
+fn share_data(source: &Path, target: &Path, size: u64) -> Result<(), DiskSenseError> {
+    use std::ffi::CString;
+    use std::fs::FileTimes;
+    use std::os::unix::ffi::OsStrExt;
+
+    let metadata = std::fs::metadata(target).map_err(|e| DiskSenseError::from_io(e, target))?;
+    let mut clone_name = target.file_name().unwrap_or_default().to_os_string();
+    clone_name.push(".disksense-clone");
+    let clone = target.with_file_name(clone_name);
+
+    let to_c = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| DiskSenseError::InvalidInput(format!("Invalid path: {}", path.display())))
+    };
+    let (src_c, clone_c) = (to_c(source)?, to_c(&clone)?);
+    if unsafe { libc::clonefile(src_c.as_ptr(), clone_c.as_ptr(), 0) } != 0 {
+        return Err(unsupported_or(std::io::Error::last_os_error(), target));
+    }
+
+    let finish = || -> std::io::Result<()> {
+        std::fs::set_permissions(&clone, metadata.permissions())?;
+        let times = FileTimes::new()
+            .set_accessed(metadata.accessed()?)
+            .set_modified(metadata.modified()?);
+        std::fs::File::options()
+            .write(true)
+            .open(&clone)?
+            .set_times(times)?;
+        Ok(())
+    };
+    finish().map_err(|e| {
+        let _ = std::fs::remove_file(&clone);
+        DiskSenseError::from_io(e, target)
+    })?;
+
+    // Nothing compares the data again here, so a copy written to since it was
+    // hashed must not be swapped for the clone
+    let current = std::fs::metadata(target).map_err(|e| DiskSenseError::from_io(e, target))?;
+    if current.len() != size || current.modified().ok() != metadata.modified().ok() {
+        let _ = std::fs::remove_file(&clone);
+        return Err(DiskSenseError::InvalidInput(format!(
+            "{} changed while it was being deduplicated",
+            target.display()
+        )));
+    }
+    std::fs::rename(&clone, target).map_err(|e| {
+        let _ = std::fs::remove_file(&clone);
+        DiskSenseError::from_io(e, target)
+    })
+}
+
+// ReFS copies the block references for cluster-aligned ranges; the copy is
+// already the same length, so its last range may run up to the cluster boundary
+#[cfg(target_os = "windows")]
+fn share_data(source: &Path, target: &Path, size: u64) -> Result<(), DiskSenseError> {
+    use std::fs::{File, OpenOptions};
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::AsRawHandle;
+    use winapi::um::fileapi::{GetDiskFreeSpaceW, GetVolumePathNameW};
+    use winapi::um::ioapiset::DeviceIoControl;
+    use winapi::um::winnt::HANDLE;
+
+    const FSCTL_DUPLICATE_EXTENTS_TO_FILE: u32 = 0x0009_8344;
+    // Well below the 4 GiB a single request may cover
+    const CHUNK: u64 = 1024 * 1024 * 1024;
+
+    #[repr(C)]
+    struct DuplicateExtentsData {
+        file_handle: HANDLE,
+        source_file_offset: i64,
+        target_file_offset: i64,
+        byte_count: i64,
+    }
+
+    let wide: Vec<u16> = target.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut volume = vec![0u16; 261];
+    let (mut sectors, mut bytes_per_sector, mut free, mut total) = (0, 0, 0, 0);
+    let cluster = unsafe {
+        if GetVolumePathNameW(wide.as_ptr(), volume.as_mut_ptr(), volume.len() as u32) == 0
+            || GetDiskFreeSpaceW(
+                volume.as_ptr(),
+                &mut sectors,
+                &mut bytes_per_sector,
+                &mut free,
+                &mut total,
+            ) == 0
+        {
+            return Err(DiskSenseError::from_io(
+                std::io::Error::last_os_error(),
+                target,
+            ));
+        }
+        u64::from(sectors) * u64::from(bytes_per_sector)
+    };
+
+    let src = File::open(source).map_err(|e| DiskSenseError::from_io(e, source))?;
+    let dest = OpenOptions::new()
+        .write(true)
+        .open(target)
+        .map_err(|e| DiskSenseError::from_io(e, target))?;
+
+    let mut offset = 0;
+    while offset < size {
+        let length = (size - offset).min(CHUNK).div_ceil(cluster) * cluster;
+        let data = DuplicateExtentsData {
+            file_handle: src.as_raw_handle() as HANDLE,
+            source_file_offset: offset as i64,
+            target_file_offset: offset as i64,
+            byte_count: length as i64,
+        };
+        let mut returned = 0;
+        let ok = unsafe {
+            DeviceIoControl(
+                dest.as_raw_handle() as HANDLE,
+                FSCTL_DUPLICATE_EXTENTS_TO_FILE,
+                &data as *const DuplicateExtentsData as *mut _,
+                std::mem::size_of::<DuplicateExtentsData>() as u32,
+                std::ptr::null_mut(),
+                0,
+                &mut returned,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok == 0 {
+            return Err(unsupported_or(std::io::Error::last_os_error(), target));
+        }
+        offset += length;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn share_data(_source: &Path, _target: &Path, _size: u64) -> Result<(), DiskSenseError> {
+    Err(DiskSenseError::Unsupported(
+        "Sharing file data is not available on this platform".to_string(),
+    ))
+}
+
+// File systems without shared extents refuse the request outright
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+fn unsupported_or(err: std::io::Error, path: &Path) -> DiskSenseError {
+    #[cfg(unix)]
+    let unsupported = [libc::EOPNOTSUPP, libc::ENOTSUP, libc::EXDEV];
+    // ERROR_INVALID_FUNCTION, ERROR_NOT_SUPPORTED, ERROR_NOT_SAME_DEVICE
+    #[cfg(windows)]
+    let unsupported = [1, 50, 17];
+
+    if err
+        .raw_os_error()
+        .is_some_and(|code| unsupported.contains(&code))
+    {
+        DiskSenseError::Unsupported(format!(
+            "The file system holding {} cannot share data between files",
+            path.display()
+        ))
+    } else {
+        DiskSenseError::from_io(err, path)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::delta::node_id;
+use crate::DiskItem;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Result of scanning a remote or device source
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemoteScan {
+    pub root: DiskItem,
+    pub file_count: u64,
+    // Directories that could not be listed, usually for lack of permission
+    pub unreadable: Vec<String>,
+}
+
+// A file reported by a remote source, with its path relative to the scanned root
+pub(crate) struct RemoteEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Default)]
+struct RemoteDir {
+    size: u64,
+    dirs: BTreeMap<String, RemoteDir>,
+    files: Vec<(String, u64)>,
+}
+
+// Build the same tree a local scan returns from a flat listing, so remote
+// sources (buckets, SFTP hosts) can be shown with the existing views.
+// `root` is the display path of the scanned location, e.g. "s3://bucket/prefix".
+pub(crate) fn build_tree(root: &str, entries: impl IntoIterator<Item = RemoteEntry>) -> DiskItem {
+    let mut tree = RemoteDir::default();
+    for entry in entries {
+        let parts: Vec<&str> = entry
+            .path
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .collect();
+        let (file_name, dirs) = match parts.split_last() {
+            Some(split) => split,
+            None => continue,
+        };
+
+        let mut dir = &mut tree;
+        dir.size += entry.size;
+        for part in dirs {
+            dir = dir.dirs.entry(part.to_string()).or_default();
+            dir.size += entry.size;
+        }
+        dir.files.push((file_name.to_string(), entry.size));
+    }
+
+    let root = root.trim_end_matches('/');
+    let name = root.rsplit('/').next().unwrap_or(root).to_string();
+    let size = tree.size;
+    remote_item(
+        name,
+        size,
+        true,
+        Some(remote_items(root, tree)),
+        root.to_string(),
+    )
+}
+
+fn remote_items(parent: &str, dir: RemoteDir) -> Vec<DiskItem> {
+    let mut items: Vec<DiskItem> = dir
+        .dirs
+        .into_iter()
+        .map(|(name, sub)| {
+            let path = format!("{}/{}", parent, name);
+            remote_item(name, sub.size, true, Some(remote_items(&path, sub)), path)
+        })
+        .chain(dir.files.into_iter().map(|(name, size)| {
+            let path = format!("{}/{}", parent, name);
+            remote_item(name, size, false, None, path)
+        }))
+        .collect();
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.size));
+    items
+}
+
+fn remote_item(
+    name: String,
+    size: u64,
+    is_dir: bool,
+    children: Option<Vec<DiskItem>>,
+    path: String,
+) -> DiskItem {
+    DiskItem {
+        id: node_id(&path),
+        name,
+        path,
+        size,
+        is_dir,
+        children,
+        game: None,
+        git_repo: false,
+        allocated_size: None,
+        sparse: false,
+        link_target: None,
+        hard_links: None,
+        in_archive: false,
+        raw_path: None,
+        estimated: false,
+        hidden: false,
+        known_folder: None,
+        allocated_partial: false,
+    }
+}
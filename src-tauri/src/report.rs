@@ -0,0 +1,243 @@
+use crate::{format_bytes, DiskItem, DiskSenseError, ScanStore};
+use std::collections::HashMap;
+use std::fmt::Write;
+use tauri::{command, State};
+
+// Number of rows shown in the "top" tables
+const TOP_ENTRIES: usize = 20;
+
+// Slice colors for the category pie chart, matching the treemap palette
+const PIE_COLORS: [&str; 8] = [
+    "#3b82f6", "#6366f1", "#a855f7", "#d946ef", "#ec4899", "#f43f5e", "#ef4444", "#93c5fd",
+];
+
+#[command]
+pub async fn export_report_html(
+    store: State<'_, ScanStore>,
+    scan_id: String,
+    path: String,
+) -> Result<(), DiskSenseError> {
+    let root = store.get(&scan_id)?;
+    let html = render_report(&root);
+
+    std::fs::write(&path, html).map_err(|e| DiskSenseError::from_io(e, &path))
+}
+
+// Group files into broad categories by extension
+pub(crate) fn file_category(name: &str) -> &'static str {
+    let extension = match name.rsplit_once('.') {
+        Some((_, ext)) => ext.to_lowercase(),
+        None => return "Other",
+    };
+
+    match extension.as_str() {
+        "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp" | "heic" | "tiff" | "svg" | "raw"
+        | "cr2" | "nef" | "arw" | "dng" => "Images",
+        "mp4" | "mkv" | "mov" | "avi" | "wmv" | "webm" | "m4v" | "flv" => "Video",
+        "mp3" | "flac" | "wav" | "aac" | "ogg" | "m4a" | "wma" | "opus" => "Audio",
+        "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "txt" | "md" | "odt" | "rtf"
+        | "csv" => "Documents",
+        "zip" | "rar" | "7z" | "tar" | "gz" | "bz2" | "xz" | "zst" | "iso" | "dmg" => "Archives",
+        "exe" | "dll" | "so" | "dylib" | "msi" | "app" | "bin" | "pkg" | "deb" | "rpm" => {
+            "Executables"
+        }
+        "rs" | "js" | "ts" | "tsx" | "py" | "c" | "cpp" | "h" | "java" | "go" | "cs" | "json"
+        | "html" | "css" => "Code",
+        _ => "Other",
+    }
+}
+
+fn render_report(root: &DiskItem) -> String {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    collect_items(root, &mut files, &mut dirs);
+
+    files.sort_by_key(|item| std::cmp::Reverse(item.size));
+    dirs.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    let mut categories: HashMap<&str, u64> = HashMap::new();
+    for file in &files {
+        *categories.entry(file_category(&file.name)).or_insert(0) += file.size;
+    }
+    let mut categories: Vec<(&str, u64)> = categories.into_iter().collect();
+    categories.sort_by_key(|item| std::cmp::Reverse(item.1));
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>DiskSense report - {title}</title>\n<style>{style}</style>\n</head>\n<body>\n\
+         <h1>DiskSense report</h1>\n<p class=\"path\">{title}</p>\n",
+        title = escape_html(&root.path),
+        style = REPORT_STYLE,
+    );
+
+    // Summary
+    let _ = write!(
+        html,
+        "<h2>Summary</h2>\n<table>\n<tr><th>Total size</th><td>{}</td></tr>\n\
+         <tr><th>Files</th><td>{}</td></tr>\n<tr><th>Folders</th><td>{}</td></tr>\n</table>\n",
+        format_bytes(root.size),
+        files.len(),
+        dirs.len(),
+    );
+
+    html.push_str("<h2>Top directories</h2>\n");
+    html.push_str(&render_table(&dirs, root.size));
+
+    html.push_str("<h2>Top files</h2>\n");
+    html.push_str(&render_table(&files, root.size));
+
+    html.push_str("<h2>Categories</h2>\n");
+    html.push_str(&render_pie(&categories));
+
+    html.push_str("<h2>Possible duplicates</h2>\n");
+    html.push_str(&render_duplicates(&files));
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn collect_items<'a>(
+    item: &'a DiskItem,
+    files: &mut Vec<&'a DiskItem>,
+    dirs: &mut Vec<&'a DiskItem>,
+) {
+    if let Some(children) = &item.children {
+        // The hidden entries' node isn't a file of its own
+        for child in children.iter().filter(|child| !child.hidden) {
+            if child.is_dir {
+                dirs.push(child);
+                collect_items(child, files, dirs);
+            } else {
+                files.push(child);
+            }
+        }
+    }
+}
+
+fn render_table(items: &[&DiskItem], total: u64) -> String {
+    let mut html = String::from("<table>\n<tr><th>Path</th><th>Size</th><th>Share</th></tr>\n");
+
+    for item in items.iter().take(TOP_ENTRIES) {
+        let share = if total > 0 {
+            item.size as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{:.1}%</td></tr>",
+            escape_html(&item.path),
+            format_bytes(item.size),
+            share,
+        );
+    }
+
+    html.push_str("</table>\n");
+    html
+}
+
+// Inline SVG pie chart so the report stays a single self-contained file
+fn render_pie(categories: &[(&str, u64)]) -> String {
+    let total: u64 = categories.iter().map(|(_, size)| size).sum();
+    if total == 0 {
+        return "<p>No files found.</p>\n".to_string();
+    }
+
+    let mut svg = String::from(
+        "<div class=\"pie\">\n<svg viewBox=\"-1 -1 2 2\" width=\"240\" height=\"240\">\n",
+    );
+    let mut legend = String::from("<ul>\n");
+    let mut angle: f64 = -std::f64::consts::FRAC_PI_2;
+
+    for (index, (name, size)) in categories.iter().enumerate() {
+        let color = PIE_COLORS[index % PIE_COLORS.len()];
+        let fraction = *size as f64 / total as f64;
+
+        if fraction >= 0.9999 {
+            let _ = writeln!(svg, "<circle r=\"1\" fill=\"{}\"/>", color);
+        } else if fraction > 0.0 {
+            let end = angle + fraction * std::f64::consts::TAU;
+            let large_arc = if fraction > 0.5 { 1 } else { 0 };
+            let _ = writeln!(
+                svg,
+                "<path d=\"M0,0 L{:.4},{:.4} A1,1 0 {},1 {:.4},{:.4} Z\" fill=\"{}\"/>",
+                angle.cos(),
+                angle.sin(),
+                large_arc,
+                end.cos(),
+                end.sin(),
+                color,
+            );
+            angle = end;
+        }
+
+        let _ = writeln!(
+            legend,
+            "<li><span style=\"background:{}\"></span>{} - {} ({:.1}%)</li>",
+            color,
+            name,
+            format_bytes(*size),
+            fraction * 100.0,
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    legend.push_str("</ul>\n");
+    format!("{}{}</div>\n", svg, legend)
+}
+
+// Files sharing both name and size are likely copies of each other
+fn render_duplicates(files: &[&DiskItem]) -> String {
+    let mut groups: HashMap<(&str, u64), Vec<&DiskItem>> = HashMap::new();
+    for file in files.iter().filter(|file| file.size > 0) {
+        groups
+            .entry((file.name.as_str(), file.size))
+            .or_default()
+            .push(file);
+    }
+
+    let mut groups: Vec<Vec<&DiskItem>> = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect();
+    if groups.is_empty() {
+        return "<p>No duplicates found.</p>\n".to_string();
+    }
+
+    // Order by the space the extra copies waste
+    groups.sort_by_key(|group| std::cmp::Reverse(group[0].size * (group.len() as u64 - 1)));
+
+    let mut html = String::from(
+        "<table>\n<tr><th>Name</th><th>Copies</th><th>Wasted</th><th>Paths</th></tr>\n",
+    );
+    for group in groups.iter().take(TOP_ENTRIES) {
+        let paths: Vec<String> = group.iter().map(|file| escape_html(&file.path)).collect();
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&group[0].name),
+            group.len(),
+            format_bytes(group[0].size * (group.len() as u64 - 1)),
+            paths.join("<br>"),
+        );
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const REPORT_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#111}\
+h1{margin-bottom:0}.path{color:#555;margin-top:.25rem}\
+table{border-collapse:collapse;margin-bottom:1.5rem}\
+th,td{text-align:left;padding:.25rem .75rem;border-bottom:1px solid #ddd;vertical-align:top}\
+.pie{display:flex;gap:2rem;align-items:center}.pie ul{list-style:none;padding:0}\
+.pie li span{display:inline-block;width:.8rem;height:.8rem;margin-right:.5rem}";
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+// Space a drive counts as used that no scan can find: blocks kept back for root
+// (ext4 reserves 5% by default), APFS local snapshots and Windows shadow copy
+// storage. Each part is None where the platform has no such thing or it can't
+// be read, for example vssadmin without elevation.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReservedSpace {
+    // Free blocks only root may use; counted as used since users can't write there
+    root_reserved: Option<u64>,
+    // Snapshot sizes aren't exposed on APFS; their space shows up as purgeable
+    local_snapshots: Option<u32>,
+    shadow_storage: Option<u64>,
+}
+
+pub(crate) fn reserved_space(mount_point: &Path) -> ReservedSpace {
+    ReservedSpace {
+        root_reserved: root_reserved(mount_point),
+        local_snapshots: local_snapshots(mount_point),
+        shadow_storage: shadow_storage(mount_point),
+    }
+}
+
+#[cfg(unix)]
+fn root_reserved(mount_point: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    let reserved = (stat.f_bfree as u64).saturating_sub(stat.f_bavail as u64);
+    Some(reserved * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn root_reserved(_mount_point: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn local_snapshots(mount_point: &Path) -> Option<u32> {
+    let output = Command::new("tmutil")
+        .arg("listlocalsnapshots")
+        .arg(mount_point)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| line.trim().starts_with("com.apple."))
+            .count() as u32,
+    )
+}
+
+#[cfg(not(target_os = "macos"))]
+fn local_snapshots(_mount_point: &Path) -> Option<u32> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn shadow_storage(mount_point: &Path) -> Option<u64> {
+    crate::vss::used_shadow_storage(&mount_point.to_string_lossy())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shadow_storage(_mount_point: &Path) -> Option<u64> {
+    None
+}
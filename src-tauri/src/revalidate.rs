@@ -0,0 +1,70 @@
+use crate::{os_path, scan_windows, DiskItem, DiskSenseError, ScanStore};
+use serde::Serialize;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Revalidation {
+    // Nodes that no longer exist and were taken out of the stored scans
+    missing: Vec<String>,
+    // Files whose size changed, patched in place
+    resized: Vec<String>,
+}
+
+// Check the nodes the front-end is showing against the disk, for when files
+// were deleted or changed by another program while DiskSense was open. Call it
+// when a window regains focus; only the given nodes and the direct children
+// the tree lists for them are stat'ed, so it stays cheap. Changes go out as
+// "scan-delta" events like any other patch. Entries created elsewhere only show
+// up after a rescan.
+#[command]
+pub async fn revalidate_visible(
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+    paths: Vec<String>,
+) -> Result<Revalidation, DiskSenseError> {
+    let mut revalidation = Revalidation::default();
+    for path in paths {
+        // Nodes dropped by an earlier path of this call are skipped
+        let node = match store.find_node(&path) {
+            Ok(node) if !node.in_archive => node,
+            _ => continue,
+        };
+        if !check_node(&app, &store, &node, &mut revalidation) {
+            continue;
+        }
+        for child in node.children.iter().flatten() {
+            if !child.hidden && !child.in_archive {
+                check_node(&app, &store, child, &mut revalidation);
+            }
+        }
+    }
+
+    Ok(revalidation)
+}
+
+// Patch the stored scans for one node, returning whether it still exists
+fn check_node(
+    app: &AppHandle,
+    store: &ScanStore,
+    node: &DiskItem,
+    revalidation: &mut Revalidation,
+) -> bool {
+    let target = os_path::decode(node.raw_path.as_deref().unwrap_or(&node.path));
+    let changes = match std::fs::symlink_metadata(&target) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            revalidation.missing.push(node.path.clone());
+            store.apply_deletion(&node.path, node.size, true)
+        }
+        // Folder sizes are only known from a scan
+        Ok(metadata) if metadata.is_file() && metadata.len() != node.size => {
+            revalidation.resized.push(node.path.clone());
+            store.apply_resize(&node.path, metadata.len())
+        }
+        _ => return true,
+    };
+
+    for change in changes {
+        scan_windows::emit_for(app, &node.path, "scan-delta", &change);
+    }
+    !revalidation.missing.contains(&node.path)
+}
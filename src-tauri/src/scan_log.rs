@@ -0,0 +1,171 @@
+use crate::DiskSenseError;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{Disks, System};
+use tauri::{command, AppHandle, Manager};
+
+// Number of per-scan log files kept before the oldest are removed
+const MAX_SCAN_LOGS: usize = 10;
+
+const LOG_DIR_NAME: &str = "logs";
+const LOG_PREFIX: &str = "scan-";
+
+// Log of a single scan, written to <app data>/logs/scan-<timestamp>.log.
+// Logging never fails a scan: if the file can't be created, events are dropped.
+pub(crate) struct ScanLog {
+    file: Option<Mutex<File>>,
+}
+
+impl ScanLog {
+    pub(crate) fn create(app: &AppHandle) -> Self {
+        let file = log_dir(app).and_then(|dir| {
+            std::fs::create_dir_all(&dir).ok()?;
+            rotate_logs(&dir);
+            let name = format!("{}{}.log", LOG_PREFIX, timestamp_millis());
+            File::create(dir.join(name)).ok()
+        });
+
+        ScanLog {
+            file: file.map(Mutex::new),
+        }
+    }
+
+    pub(crate) fn info(&self, message: impl Display) {
+        self.write("INFO", message);
+    }
+
+    pub(crate) fn skipped(&self, path: &Path, reason: &str) {
+        self.write("SKIP", format_args!("{} ({})", path.display(), reason));
+    }
+
+    pub(crate) fn error(&self, path: &Path, error: impl Display) {
+        self.write("ERROR", format_args!("{}: {}", path.display(), error));
+    }
+
+    fn write(&self, level: &str, message: impl Display) {
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            let millis = timestamp_millis();
+            let _ = writeln!(
+                file,
+                "[{}.{:03}] {:5} {}",
+                millis / 1000,
+                millis % 1000,
+                level,
+                message
+            );
+        }
+    }
+}
+
+// Write a plain-text bundle of environment info and recent scan logs for bug reports.
+// Returns the path of the written file.
+#[command]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<String, DiskSenseError> {
+    let output = match path {
+        Some(path) => PathBuf::from(path),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| {
+                DiskSenseError::Failed(format!("App data directory is unavailable: {}", e))
+            })?
+            .join(format!("diagnostics-{}.txt", timestamp_millis())),
+    };
+
+    let mut bundle = String::new();
+    bundle.push_str("== Environment ==\n");
+    bundle.push_str(&format!("App version: {}\n", app.package_info().version));
+    bundle.push_str(&format!(
+        "OS: {}\n",
+        System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string())
+    ));
+    bundle.push_str(&format!(
+        "Kernel: {}\n",
+        System::kernel_version().unwrap_or_default()
+    ));
+    bundle.push_str(&format!("Architecture: {}\n", std::env::consts::ARCH));
+    bundle.push_str(&format!(
+        "CPUs: {}\n",
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(0)
+    ));
+
+    bundle.push_str("\n== Drives ==\n");
+    for disk in Disks::new_with_refreshed_list().iter() {
+        bundle.push_str(&format!(
+            "{} on {} ({}): {} of {} bytes free\n",
+            disk.name().to_string_lossy(),
+            disk.mount_point().display(),
+            disk.file_system().to_string_lossy(),
+            disk.available_space(),
+            disk.total_space()
+        ));
+    }
+
+    if let Some(dir) = log_dir(&app) {
+        for log in scan_logs(&dir) {
+            bundle.push_str(&format!(
+                "\n== {} ==\n",
+                log.file_name().unwrap_or_default().to_string_lossy()
+            ));
+            bundle.push_str(&std::fs::read_to_string(&log).unwrap_or_default());
+        }
+    }
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+    }
+    std::fs::write(&output, bundle).map_err(|e| DiskSenseError::from_io(e, &output))?;
+
+    Ok(output.to_string_lossy().to_string())
+}
+
+fn log_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(LOG_DIR_NAME))
+}
+
+// Scan logs in the log dir, oldest first (names embed a sortable timestamp)
+fn scan_logs(dir: &Path) -> Vec<PathBuf> {
+    let mut logs: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().starts_with(LOG_PREFIX))
+                    .unwrap_or(false)
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    logs.sort();
+    logs
+}
+
+// Make room for a new log so at most MAX_SCAN_LOGS remain afterwards
+fn rotate_logs(dir: &Path) {
+    let logs = scan_logs(dir);
+    let excess = (logs.len() + 1).saturating_sub(MAX_SCAN_LOGS);
+    for log in logs.iter().take(excess) {
+        let _ = std::fs::remove_file(log);
+    }
+}
+
+fn timestamp_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
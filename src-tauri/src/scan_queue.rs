@@ -0,0 +1,441 @@
+use crate::visibility::{self, HiddenTally};
+use crate::{
+    estimate_dir_size, is_large_directory, os_path, properties, scan_link_count, scan_windows,
+    sparse, DiskItem, ScanContext, ScanProgress,
+};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+#[cfg(target_os = "windows")]
+use crate::SKIP_DIRS;
+
+// A directory waiting to be listed
+struct Job {
+    id: usize,
+    path: PathBuf,
+    max_depth: usize,
+    // Below the depth limit: only the total size is kept
+    collapsed: bool,
+    // Share of the whole scan this directory and everything below it stands for
+    weight: f64,
+}
+
+// A listed directory waiting for its subdirectories to finish
+struct Node {
+    item: Option<DiskItem>,
+    parent: Option<usize>,
+    waiting: usize,
+    collapsed: bool,
+}
+
+struct Queue {
+    pending: VecDeque<Job>,
+    // Workers currently listing a directory; they may still queue more
+    active: usize,
+    nodes: Vec<Node>,
+    root: Option<DiskItem>,
+    done_weight: f64,
+}
+
+// Read by progress events without taking the queue lock
+struct Progress {
+    pending_dirs: AtomicUsize,
+    percent: AtomicU32,
+}
+
+enum Listing {
+    // Resumed or skipped; goes into the tree as it is
+    Done(DiskItem),
+    // Files are in `item`; `subdirs` still have to be scanned
+    Listed {
+        item: DiskItem,
+        subdirs: Vec<PathBuf>,
+    },
+}
+
+// Fast scan: workers take directories off a shared queue instead of recursing,
+// so the number of directories still to list is always known. Each directory
+// passes its share of the progress bar on to its subdirectories, which keeps
+// the percentage from ever going backwards.
+pub(crate) fn fast_scan(dir_path: &Path, max_depth: usize, ctx: &ScanContext) -> DiskItem {
+    let state = Mutex::new(Queue {
+        pending: VecDeque::from([Job {
+            id: 0,
+            path: dir_path.to_path_buf(),
+            max_depth,
+            collapsed: false,
+            weight: 1.0,
+        }]),
+        active: 0,
+        nodes: vec![Node {
+            item: None,
+            parent: None,
+            waiting: 0,
+            collapsed: false,
+        }],
+        root: None,
+        done_weight: 0.0,
+    });
+    let wake = Condvar::new();
+    let progress = Progress {
+        pending_dirs: AtomicUsize::new(1),
+        percent: AtomicU32::new(0f32.to_bits()),
+    };
+
+    rayon::scope(|scope| {
+        for _ in 0..rayon::current_num_threads().max(1) {
+            scope.spawn(|_| worker(&state, &wake, &progress, ctx));
+        }
+    });
+
+    let root = state.into_inner().unwrap_or_else(|e| e.into_inner()).root;
+    root.unwrap_or_else(|| ctx.skip(dir_path, "unresponsive"))
+}
+
+fn worker(state: &Mutex<Queue>, wake: &Condvar, progress: &Progress, ctx: &ScanContext) {
+    loop {
+        let job = {
+            let mut queue = state.lock().unwrap_or_else(|e| e.into_inner());
+            loop {
+                // Newest first, so subtrees finish (and are checkpointed) early
+                // and the queue stays small
+                if let Some(job) = queue.pending.pop_back() {
+                    queue.active += 1;
+                    break job;
+                }
+                if queue.active == 0 {
+                    return;
+                }
+                queue = wake.wait(queue).unwrap_or_else(|e| e.into_inner());
+            }
+        };
+
+        let listing = list_dir(&job.path, job.max_depth, job.collapsed, ctx, progress);
+
+        let mut ready = match listing {
+            Listing::Done(item) => {
+                add_done(state, progress, job.weight);
+                attach(state, job.id, item)
+            }
+            Listing::Listed { mut item, subdirs } if subdirs.is_empty() => {
+                add_done(state, progress, job.weight);
+                finish(&mut item, job.collapsed, ctx);
+                attach(state, job.id, item)
+            }
+            Listing::Listed { item, subdirs } => {
+                let share = job.weight / (subdirs.len() + 1) as f64;
+                // Folders past the depth limit are still walked to the bottom,
+                // but only their size goes into the tree
+                let collapsed = job.collapsed || job.max_depth == 0;
+                let mut queue = state.lock().unwrap_or_else(|e| e.into_inner());
+                queue.nodes[job.id].item = Some(item);
+                queue.nodes[job.id].waiting = subdirs.len();
+                for path in subdirs {
+                    let id = queue.nodes.len();
+                    queue.nodes.push(Node {
+                        item: None,
+                        parent: Some(job.id),
+                        waiting: 0,
+                        collapsed,
+                    });
+                    queue.pending.push_back(Job {
+                        id,
+                        path,
+                        max_depth: job.max_depth.saturating_sub(1),
+                        collapsed,
+                        weight: share,
+                    });
+                }
+                queue.done_weight += share;
+                update_progress(&queue, progress);
+                wake.notify_all();
+                None
+            }
+        };
+
+        // Finishing the last subdirectory may finish its parents in turn
+        while let Some((id, mut item, collapsed)) = ready {
+            finish(&mut item, collapsed, ctx);
+            ready = attach(state, id, item);
+        }
+
+        let mut queue = state.lock().unwrap_or_else(|e| e.into_inner());
+        queue.active -= 1;
+        update_progress(&queue, progress);
+        if queue.active == 0 && queue.pending.is_empty() {
+            wake.notify_all();
+        }
+    }
+}
+
+fn add_done(state: &Mutex<Queue>, progress: &Progress, weight: f64) {
+    let mut queue = state.lock().unwrap_or_else(|e| e.into_inner());
+    queue.done_weight += weight;
+    update_progress(&queue, progress);
+}
+
+fn update_progress(queue: &Queue, progress: &Progress) {
+    progress
+        .pending_dirs
+        .store(queue.pending.len() + queue.active, Ordering::Relaxed);
+    let percent = (queue.done_weight * 100.0).min(100.0) as f32;
+    progress.percent.store(percent.to_bits(), Ordering::Relaxed);
+}
+
+fn report(ctx: &ScanContext, progress: &Progress, path: &Path, processed: usize) {
+    let current_path = path.to_string_lossy().to_string();
+    scan_windows::emit_for(
+        ctx.app,
+        &current_path,
+        "scan-progress",
+        &ScanProgress {
+            current_path: current_path.clone(),
+            processed_items: processed,
+            total_items: processed + progress.pending_dirs.load(Ordering::Relaxed),
+            percent: f32::from_bits(progress.percent.load(Ordering::Relaxed)),
+        },
+    );
+}
+
+// Hand a finished directory to its parent. Returns the parent once its last
+// subdirectory is in.
+fn attach(state: &Mutex<Queue>, id: usize, item: DiskItem) -> Option<(usize, DiskItem, bool)> {
+    let mut queue = state.lock().unwrap_or_else(|e| e.into_inner());
+    let parent = match queue.nodes[id].parent {
+        Some(parent) => parent,
+        None => {
+            queue.root = Some(item);
+            return None;
+        }
+    };
+
+    let node = &mut queue.nodes[parent];
+    if let Some(children) = node.item.as_mut().and_then(|p| p.children.as_mut()) {
+        children.push(item);
+    }
+    node.waiting -= 1;
+    if node.waiting == 0 {
+        let collapsed = node.collapsed;
+        node.item.take().map(|item| (parent, item, collapsed))
+    } else {
+        None
+    }
+}
+
+// Sort children by size (largest first), total them and checkpoint the directory.
+// A collapsed directory adds its subfolders to the bytes of its own files and
+// drops them; its parent's checkpoint covers it.
+fn finish(item: &mut DiskItem, collapsed: bool, ctx: &ScanContext) {
+    if collapsed {
+        let subfolders: u64 = item.children.iter().flatten().map(|c| c.size).sum();
+        item.size += subfolders;
+        item.children = Some(Vec::new());
+        return;
+    }
+    if let Some(children) = item.children.as_mut() {
+        children.sort_by_key(|item| std::cmp::Reverse(item.size));
+        item.size = children.iter().map(|child| child.size).sum();
+    }
+    ctx.checkpoint.record(item);
+}
+
+// List one directory: files become children straight away, subdirectories are
+// returned to be queued. Only an instant preview estimates folders in place.
+fn list_dir(
+    dir_path: &Path,
+    max_depth: usize,
+    collapsed: bool,
+    ctx: &ScanContext,
+    progress: &Progress,
+) -> Listing {
+    // Reuse the subtree if an interrupted scan already finished this directory
+    if let Some(item) = ctx.checkpoint.resumed(dir_path) {
+        ctx.partial_sizes.add(ctx.app, dir_path, item.size);
+        return Listing::Done(item);
+    }
+
+    if ctx.timed_out() {
+        return Listing::Done(ctx.skip(dir_path, "timed out"));
+    }
+
+    let mut item = DiskItem {
+        name: dir_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| dir_path.to_string_lossy().to_string()),
+        path: dir_path.to_string_lossy().to_string(),
+        size: 0,
+        is_dir: true,
+        children: Some(Vec::new()),
+        game: None,
+        git_repo: false,
+        id: String::new(),
+        allocated_size: None,
+        sparse: false,
+        link_target: None,
+        hard_links: None,
+        in_archive: false,
+        raw_path: os_path::encode(dir_path),
+        estimated: false,
+        hidden: false,
+        known_folder: None,
+        allocated_partial: false,
+    };
+
+    let entries = {
+        let _permit = ctx.dir_reads.acquire();
+        let dir = dir_path.to_path_buf();
+        ctx.read_with_stall_guard(move || {
+            std::fs::read_dir(dir).map(|entries| entries.filter_map(Result::ok).collect::<Vec<_>>())
+        })
+    };
+    let entries = match entries {
+        Some(Ok(entries)) => entries,
+        Some(Err(e)) => {
+            ctx.log.error(dir_path, &e);
+            return Listing::Listed {
+                item,
+                subdirs: Vec::new(),
+            };
+        }
+        None => return Listing::Done(ctx.skip(dir_path, "unresponsive")),
+    };
+
+    let mut children = Vec::new();
+    let mut subdirs = Vec::new();
+    let mut file_bytes = 0;
+    let mut hidden = HiddenTally::default();
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+
+        // Hidden files and directories are left out of the listing if
+        // configured, but still counted
+        if ctx.options.skip_hidden && visibility::is_hidden(&name, &path) {
+            let _permit = ctx.dir_reads.acquire();
+            file_bytes += hidden.add(&path);
+            continue;
+        }
+
+        // Links are listed as leaves and never followed, like comprehensive_scan
+        // does: a link to a folder would count its target twice, or walk
+        // anywhere on the disk once past the depth limit. The entry's own type
+        // needs no stat on most platforms.
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(_) => continue,
+        };
+        let is_dir = file_type.is_dir();
+        let is_symlink = file_type.is_symlink();
+        if ctx.is_ignored(&path, is_dir) {
+            continue;
+        }
+
+        if file_type.is_file() || is_symlink {
+            let current = ctx.processed_items.fetch_add(1, Ordering::SeqCst) + 1;
+            if current % 100 == 0 || current < 100 {
+                report(ctx, progress, &path, current);
+            }
+
+            // Get file size, and the space it really takes if it is sparse
+            let metadata = entry.metadata().ok();
+            let size = metadata.as_ref().map_or(0, |m| m.len());
+            file_bytes += size;
+            if collapsed {
+                continue;
+            }
+            let (allocated_size, sparse) = match &metadata {
+                Some(metadata) => sparse::allocation(&path, metadata),
+                None => (None, false),
+            };
+
+            // Show where links point and which files share their data
+            let link_target = is_symlink.then(|| properties::link_target(&path)).flatten();
+            let hard_links = metadata.as_ref().and_then(scan_link_count);
+
+            children.push(DiskItem {
+                name,
+                path: path.to_string_lossy().to_string(),
+                size,
+                is_dir: false,
+                children: None,
+                game: None,
+                git_repo: false,
+                id: String::new(),
+                allocated_size,
+                sparse,
+                link_target,
+                hard_links,
+                in_archive: false,
+                raw_path: os_path::encode(&path),
+                estimated: false,
+                hidden: false,
+                known_folder: None,
+                allocated_partial: false,
+            });
+            continue;
+        }
+        if !is_dir {
+            continue;
+        }
+
+        // Skip system directories that cause permission issues
+        #[cfg(target_os = "windows")]
+        {
+            let lower = path.to_string_lossy().to_lowercase();
+            if SKIP_DIRS.iter().any(|skip| lower.starts_with(skip)) {
+                ctx.log.skipped(&path, "system directory");
+                continue;
+            }
+        }
+
+        let current = ctx.processed_items.fetch_add(1, Ordering::SeqCst) + 1;
+        if current % 20 == 0 || current < 100 {
+            report(ctx, progress, &path, current);
+        }
+
+        // An instant preview only estimates folders past the depth limit and
+        // large folders; otherwise every folder is counted exactly
+        let estimate = ctx.options.instant_preview
+            && !collapsed
+            && (max_depth == 0 || (max_depth > 1 && is_large_directory(&path)));
+        if !estimate {
+            subdirs.push(path);
+            continue;
+        }
+
+        let size = estimate_dir_size(&path);
+        ctx.partial_sizes.add(ctx.app, &path, size);
+        children.push(DiskItem {
+            name,
+            path: path.to_string_lossy().to_string(),
+            size,
+            is_dir: true,
+            children: Some(Vec::new()),
+            game: None,
+            git_repo: false,
+            id: String::new(),
+            allocated_size: None,
+            sparse: false,
+            link_target: None,
+            hard_links: None,
+            in_archive: false,
+            raw_path: os_path::encode(&path),
+            estimated: true,
+            hidden: false,
+            known_folder: None,
+            allocated_partial: false,
+        });
+    }
+
+    ctx.partial_sizes.add(ctx.app, dir_path, file_bytes);
+    if collapsed {
+        item.size = file_bytes;
+    } else {
+        children.extend(hidden.into_node(dir_path));
+    }
+    item.children = Some(children);
+    Listing::Listed { item, subdirs }
+}
@@ -0,0 +1,207 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Component, Path, Prefix};
+use std::time::{Duration, Instant};
+use sysinfo::Disks;
+use tauri::command;
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScanTargetValidation {
+    path: String,
+    exists: bool,
+    is_dir: bool,
+    readable: bool,
+    file_system: Option<String>,
+    is_network: bool,
+    is_removable: bool,
+    estimated_entries: u64,
+    estimate_is_exact: bool,
+    estimated_seconds: u64,
+    denied_paths: Vec<String>,
+    is_elevated: bool,
+    elevation_would_help: bool,
+}
+
+// How long the sampling walk may take before the entry count is extrapolated
+const SAMPLE_BUDGET: Duration = Duration::from_millis(1500);
+
+// Denied folders reported back; enough to show the user what they are missing
+const MAX_DENIED_PATHS: usize = 20;
+
+// Rough entries-per-second rates used for the duration estimate
+const LOCAL_RATE: u64 = 20_000;
+const REMOVABLE_RATE: u64 = 5_000;
+const NETWORK_RATE: u64 = 1_000;
+
+// File systems served over the network
+const NETWORK_FILE_SYSTEMS: [&str; 9] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smbfs",
+    "smb2",
+    "afpfs",
+    "fuse.sshfs",
+    "9p",
+    "davfs",
+];
+
+#[command]
+pub async fn validate_scan_target(path: String) -> Result<ScanTargetValidation, DiskSenseError> {
+    let target = Path::new(&path);
+    let metadata = std::fs::metadata(target);
+
+    let mut validation = ScanTargetValidation {
+        path: path.clone(),
+        exists: metadata.is_ok(),
+        is_dir: metadata.as_ref().is_ok_and(|m| m.is_dir()),
+        readable: false,
+        file_system: None,
+        is_network: false,
+        is_removable: false,
+        estimated_entries: 0,
+        estimate_is_exact: false,
+        estimated_seconds: 0,
+        denied_paths: Vec::new(),
+        is_elevated: is_elevated(),
+        elevation_would_help: false,
+    };
+
+    if let Err(e) = &metadata {
+        // A target we may not even stat still exists, and elevation may reach it
+        if e.kind() == ErrorKind::PermissionDenied {
+            validation.exists = true;
+            validation.denied_paths.push(path);
+            validation.elevation_would_help = !validation.is_elevated;
+        }
+        return Ok(validation);
+    }
+
+    validation.readable = if validation.is_dir {
+        std::fs::read_dir(target).is_ok()
+    } else {
+        std::fs::File::open(target).is_ok()
+    };
+
+    let canonical = dunce::canonicalize(target).unwrap_or_else(|_| target.to_path_buf());
+    let disks = Disks::new_with_refreshed_list();
+    if let Some(disk) = disks
+        .iter()
+        .filter(|disk| canonical.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    {
+        let file_system = disk.file_system().to_string_lossy().to_string();
+        validation.is_network = NETWORK_FILE_SYSTEMS.contains(&file_system.to_lowercase().as_str());
+        validation.is_removable = disk.is_removable();
+        validation.file_system = Some(file_system);
+    }
+
+    // UNC paths (\\server\share) are always network locations
+    if let Some(Component::Prefix(prefix)) = canonical.components().next() {
+        if matches!(prefix.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..)) {
+            validation.is_network = true;
+        }
+    }
+
+    if validation.is_dir && validation.readable {
+        sample_entries(&canonical, &mut validation);
+    } else {
+        validation.estimated_entries = 1;
+        validation.estimate_is_exact = true;
+    }
+
+    let rate = if validation.is_network {
+        NETWORK_RATE
+    } else if validation.is_removable {
+        REMOVABLE_RATE
+    } else {
+        LOCAL_RATE
+    };
+    validation.estimated_seconds = validation.estimated_entries / rate;
+    validation.elevation_would_help =
+        !validation.is_elevated && !validation.denied_paths.is_empty();
+
+    Ok(validation)
+}
+
+// Walk the target for a short while, extrapolating the entry count from how many
+// top-level folders were finished when the time ran out
+fn sample_entries(root: &Path, validation: &mut ScanTargetValidation) {
+    let top_level = std::fs::read_dir(root)
+        .map(|e| e.count())
+        .unwrap_or(0)
+        .max(1) as u64;
+    let started = Instant::now();
+    let mut seen: u64 = 0;
+    let mut top_level_done: u64 = 0;
+
+    for entry in WalkDir::new(root).min_depth(1).follow_links(false) {
+        match entry {
+            Ok(entry) => {
+                seen += 1;
+                if entry.depth() == 1 {
+                    top_level_done += 1;
+                }
+            }
+            Err(e) => {
+                let denied = e
+                    .io_error()
+                    .is_some_and(|io| io.kind() == ErrorKind::PermissionDenied);
+                if denied && validation.denied_paths.len() < MAX_DENIED_PATHS {
+                    if let Some(path) = e.path() {
+                        validation
+                            .denied_paths
+                            .push(path.to_string_lossy().to_string());
+                    }
+                }
+            }
+        }
+
+        if started.elapsed() > SAMPLE_BUDGET {
+            // The folder being walked is only partly counted
+            let finished = top_level_done.saturating_sub(1).max(1);
+            validation.estimated_entries = seen * top_level / finished;
+            return;
+        }
+    }
+
+    validation.estimated_entries = seen;
+    validation.estimate_is_exact = true;
+}
+
+pub(crate) fn is_elevated() -> bool {
+    #[cfg(unix)]
+    {
+        unsafe { libc::geteuid() == 0 }
+    }
+
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use std::mem::size_of;
+        use winapi::shared::minwindef::DWORD;
+        use winapi::um::handleapi::CloseHandle;
+        use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+        use winapi::um::securitybaseapi::GetTokenInformation;
+        use winapi::um::winnt::{TokenElevation, HANDLE, TOKEN_ELEVATION, TOKEN_QUERY};
+
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut returned: DWORD = 0;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut _ as *mut _,
+            size_of::<TOKEN_ELEVATION>() as DWORD,
+            &mut returned,
+        );
+        CloseHandle(token);
+
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{DiskSenseError, ScanStore};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+// Labels of extra scan windows; the capability file grants them the main window's permissions
+const WINDOW_LABEL_PREFIX: &str = "scan-";
+
+// Which windows each scan belongs to, so every window runs its own scan session
+// and only hears about the trees it shows. Keyed by window label.
+#[derive(Default)]
+pub struct ScanWindows {
+    roots: Mutex<HashMap<String, HashSet<String>>>,
+    next_id: AtomicU64,
+}
+
+impl ScanWindows {
+    pub(crate) fn claim(&self, label: &str, root: &str) {
+        self.roots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(label.to_string())
+            .or_default()
+            .insert(root.to_string());
+    }
+
+    // Forget a closed window, returning the roots no other window still shows
+    pub(crate) fn release(&self, label: &str) -> Vec<String> {
+        let mut roots = self.roots.lock().unwrap_or_else(|e| e.into_inner());
+        let released = roots.remove(label).unwrap_or_default();
+        released
+            .into_iter()
+            .filter(|root| !roots.values().any(|shown| shown.contains(root)))
+            .collect()
+    }
+
+    // Windows showing a scan that contains `path`
+    fn owners(&self, path: &str) -> Vec<String> {
+        self.roots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, roots)| roots.iter().any(|root| Path::new(path).starts_with(root)))
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+}
+
+// Send a scan event to the windows showing the scan `path` belongs to. Scans no
+// window has claimed, like restored sessions and scheduled rescans, go to all.
+pub(crate) fn emit_for<S: Serialize + Clone>(app: &AppHandle, path: &str, event: &str, payload: S) {
+    let owners = match app.try_state::<ScanWindows>() {
+        Some(windows) => windows.owners(path),
+        None => Vec::new(),
+    };
+    if owners.is_empty() {
+        let _ = app.emit(event, payload);
+        return;
+    }
+    for label in owners {
+        let _ = app.emit_to(label.as_str(), event, payload.clone());
+    }
+}
+
+// Drop the trees of a closed window that no other window shows
+pub(crate) fn window_closed(app: &AppHandle, label: &str) {
+    let (windows, store) = match (app.try_state::<ScanWindows>(), app.try_state::<ScanStore>()) {
+        (Some(windows), Some(store)) => (windows, store),
+        _ => return,
+    };
+    for root in windows.release(label) {
+        store.remove(&root);
+    }
+}
+
+// Open another window that scans `path` on load, so two drives or folders can
+// be compared side by side. Returns the new window's label.
+#[command]
+pub async fn open_scan_window(app: AppHandle, path: String) -> Result<String, DiskSenseError> {
+    if !Path::new(&path).exists() {
+        return Err(DiskSenseError::NotFound { path });
+    }
+
+    let windows = app.state::<ScanWindows>();
+    let label = format!(
+        "{}{}",
+        WINDOW_LABEL_PREFIX,
+        windows.next_id.fetch_add(1, Ordering::SeqCst) + 1
+    );
+    // The front-end reads the path to scan before it renders
+    let initial_scan = serde_json::to_string(&path)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to open window: {}", e)))?;
+
+    WebviewWindowBuilder::new(&app, &label, WebviewUrl::default())
+        .title(format!("DiskSense - {}", path))
+        .inner_size(1200.0, 800.0)
+        .min_inner_size(800.0, 600.0)
+        .initialization_script(&format!(
+            "window.__DISKSENSE_INITIAL_SCAN__ = {};",
+            initial_scan
+        ))
+        .build()
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to open window: {}", e)))?;
+
+    Ok(label)
+}
@@ -0,0 +1,305 @@
+use crate::extension_colors::file_extension;
+use crate::unicode_names::nfc;
+use crate::{DiskItem, DiskSenseError, ScanStore};
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir holding saved searches
+const SEARCHES_FILE: &str = "saved_searches.json";
+
+// Results returned when a query doesn't set a limit
+const DEFAULT_RESULT_LIMIT: usize = 1000;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Filters over a stored scan. Every field that is set must match.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ScanQuery {
+    // Glob on the item name, matched case-insensitively ("*.mkv", "IMG_*")
+    #[serde(default)]
+    name: Option<String>,
+    // Extensions without the dot, e.g. ["mp4", "mkv"]
+    #[serde(default)]
+    extensions: Vec<String>,
+    #[serde(default)]
+    min_size: Option<u64>,
+    #[serde(default)]
+    max_size: Option<u64>,
+    #[serde(default)]
+    is_dir: Option<bool>,
+    #[serde(default)]
+    modified_before_days: Option<u64>,
+    // Uses access times, which some filesystems don't keep up to date
+    #[serde(default)]
+    accessed_before_days: Option<u64>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResult {
+    path: String,
+    name: String,
+    size: u64,
+    is_dir: bool,
+    raw_path: Option<String>,
+}
+
+// A named query ("smart view") tied to the scan root it runs against
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedSearch {
+    name: String,
+    scan_id: String,
+    query: ScanQuery,
+}
+
+// Saved searches, persisted in the app data dir
+#[derive(Default)]
+pub struct SavedSearches {
+    searches: Mutex<Vec<SavedSearch>>,
+}
+
+impl SavedSearches {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let searches = searches_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        SavedSearches {
+            searches: Mutex::new(searches),
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = searches_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let searches = self.searches.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = serde_json::to_string_pretty(&*searches)
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to save searches: {}", e)))?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    pub(crate) fn searches(&self) -> Vec<SavedSearch> {
+        self.searches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub(crate) fn import(
+        &self,
+        app: &AppHandle,
+        searches: Vec<SavedSearch>,
+    ) -> Result<(), DiskSenseError> {
+        for search in &searches {
+            compile_query(&search.query)?;
+        }
+        *self.searches.lock().unwrap_or_else(|e| e.into_inner()) = searches;
+        self.save(app)
+    }
+}
+
+fn searches_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(SEARCHES_FILE))
+}
+
+// Items of a stored scan matching `query`, largest first
+#[command]
+pub async fn query_scan(
+    store: State<'_, ScanStore>,
+    scan_id: String,
+    query: ScanQuery,
+) -> Result<Vec<SearchResult>, DiskSenseError> {
+    run_query(&store.get(&scan_id)?, &query)
+}
+
+// Save a query under `name`, replacing any search of the same name
+#[command]
+pub async fn save_search(
+    app: AppHandle,
+    searches: State<'_, SavedSearches>,
+    name: String,
+    scan_id: String,
+    query: ScanQuery,
+) -> Result<(), DiskSenseError> {
+    // Fail now rather than every time the search runs
+    compile_query(&query)?;
+
+    {
+        let mut list = searches.searches.lock().unwrap_or_else(|e| e.into_inner());
+        list.retain(|search| search.name != name);
+        list.push(SavedSearch {
+            name,
+            scan_id,
+            query,
+        });
+    }
+    searches.save(&app)
+}
+
+#[command]
+pub async fn list_searches(
+    searches: State<'_, SavedSearches>,
+) -> Result<Vec<SavedSearch>, DiskSenseError> {
+    Ok(searches
+        .searches
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone())
+}
+
+#[command]
+pub async fn remove_search(
+    app: AppHandle,
+    searches: State<'_, SavedSearches>,
+    name: String,
+) -> Result<(), DiskSenseError> {
+    searches
+        .searches
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|search| search.name != name);
+    searches.save(&app)
+}
+
+// Run a saved search against the most recent scan of its root
+#[command]
+pub async fn run_saved_search(
+    store: State<'_, ScanStore>,
+    searches: State<'_, SavedSearches>,
+    name: String,
+) -> Result<Vec<SearchResult>, DiskSenseError> {
+    let search = searches
+        .searches
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|search| search.name == name)
+        .cloned()
+        .ok_or(DiskSenseError::NotFound { path: name })?;
+
+    run_query(&store.get(&search.scan_id)?, &search.query)
+}
+
+struct CompiledQuery<'a> {
+    query: &'a ScanQuery,
+    name: Option<GlobMatcher>,
+    extensions: Vec<String>,
+}
+
+fn compile_query(query: &ScanQuery) -> Result<CompiledQuery<'_>, DiskSenseError> {
+    let name = query
+        .name
+        .as_deref()
+        .map(|pattern| {
+            GlobBuilder::new(&nfc(pattern))
+                .case_insensitive(true)
+                .build()
+                .map(|glob| glob.compile_matcher())
+                .map_err(|e| {
+                    DiskSenseError::InvalidInput(format!("Invalid pattern {}: {}", pattern, e))
+                })
+        })
+        .transpose()?;
+
+    Ok(CompiledQuery {
+        query,
+        name,
+        extensions: query
+            .extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.').to_lowercase())
+            .collect(),
+    })
+}
+
+fn run_query(root: &DiskItem, query: &ScanQuery) -> Result<Vec<SearchResult>, DiskSenseError> {
+    let compiled = compile_query(query)?;
+    let mut results = Vec::new();
+    collect_matches(root, &compiled, &mut results);
+
+    results.sort_by_key(|item| std::cmp::Reverse(item.size));
+    results.truncate(query.limit.unwrap_or(DEFAULT_RESULT_LIMIT));
+    Ok(results)
+}
+
+fn collect_matches(item: &DiskItem, query: &CompiledQuery, results: &mut Vec<SearchResult>) {
+    // The hidden entries' node is a summary and archive contents only exist inside
+    // the archive; neither is a file that can be opened or deleted
+    for child in item
+        .children
+        .iter()
+        .flatten()
+        .filter(|child| !child.hidden && !child.in_archive)
+    {
+        if matches(child, query) {
+            results.push(SearchResult {
+                path: child.path.clone(),
+                name: child.name.clone(),
+                size: child.size,
+                is_dir: child.is_dir,
+                raw_path: child.raw_path.clone(),
+            });
+        }
+        collect_matches(child, query, results);
+    }
+}
+
+// Cheap checks on the tree first; timestamps need a stat, so they come last
+fn matches(item: &DiskItem, compiled: &CompiledQuery) -> bool {
+    let query = compiled.query;
+    if query.is_dir.is_some_and(|is_dir| is_dir != item.is_dir)
+        || query.min_size.is_some_and(|min| item.size < min)
+        || query.max_size.is_some_and(|max| item.size > max)
+    {
+        return false;
+    }
+
+    if !compiled.extensions.is_empty()
+        && (item.is_dir || !compiled.extensions.contains(&file_extension(&item.name)))
+    {
+        return false;
+    }
+
+    // Names are matched in NFC form, like duplicate grouping
+    if let Some(matcher) = &compiled.name {
+        if !matcher.is_match(&*nfc(&item.name)) {
+            return false;
+        }
+    }
+
+    if query.modified_before_days.is_none() && query.accessed_before_days.is_none() {
+        return true;
+    }
+
+    let metadata = match std::fs::symlink_metadata(crate::os_path::decode(
+        item.raw_path.as_deref().unwrap_or(&item.path),
+    )) {
+        Ok(metadata) => metadata,
+        Err(_) => return false,
+    };
+    let older_than = |time: std::io::Result<SystemTime>, days: u64| {
+        let cutoff = SystemTime::now() - Duration::from_secs(days * SECS_PER_DAY);
+        time.is_ok_and(|time| time < cutoff && time > UNIX_EPOCH)
+    };
+
+    query
+        .modified_before_days
+        .map_or(true, |days| older_than(metadata.modified(), days))
+        && query
+            .accessed_before_days
+            .map_or(true, |days| older_than(metadata.accessed(), days))
+}
@@ -0,0 +1,280 @@
+use crate::delta::node_id;
+use crate::{
+    change_recorder, fs_events, incremental, perform_scan, DiskItem, DiskSenseError, ScanOptions,
+    ScanStore,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir describing the last session
+const SESSION_FILE: &str = "session.json";
+
+// Folder next to it holding one scan tree per open root
+const SNAPSHOT_DIR: &str = "session-snapshots";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionScan {
+    root: String,
+    depth: usize,
+    options: ScanOptions,
+    // Paths of the folders expanded in the tree view
+    #[serde(default)]
+    expanded: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+struct SessionState {
+    scans: Vec<SessionScan>,
+    active: Option<String>,
+    saved_at: u64,
+    // FSEvents event id when the snapshots were written (macOS)
+    #[serde(default)]
+    event_id: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoredScan {
+    #[serde(flatten)]
+    scan: SessionScan,
+    tree: DiskItem,
+    // True when the tree was loaded from the saved snapshot rather than rescanned
+    from_snapshot: bool,
+    snapshot_age_seconds: Option<u64>,
+    // Folders relisted because they changed since the snapshot; None unless the
+    // snapshot was brought up to date from the file system's change history
+    refreshed_dirs: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestoredSession {
+    scans: Vec<RestoredScan>,
+    active: Option<String>,
+    // Roots that no longer exist or failed to rescan
+    missing: Vec<String>,
+}
+
+// The scan roots open in this session. Scans register themselves; the front-end
+// reports which root is active and which folders are expanded.
+#[derive(Default)]
+pub struct Session {
+    state: Mutex<SessionState>,
+}
+
+// Note a finished scan as open in the session, keeping any expansion state it had
+pub(crate) fn record_scan(app: &AppHandle, root: &str, depth: usize, options: &ScanOptions) {
+    let session = match app.try_state::<Session>() {
+        Some(session) => session,
+        None => return,
+    };
+    let mut state = session.state.lock().unwrap_or_else(|e| e.into_inner());
+    match state.scans.iter_mut().find(|scan| scan.root == root) {
+        Some(scan) => {
+            scan.depth = depth;
+            scan.options = options.clone();
+        }
+        None => state.scans.push(SessionScan {
+            root: root.to_string(),
+            depth,
+            options: options.clone(),
+            expanded: Vec::new(),
+        }),
+    }
+}
+
+// Write the session and a snapshot of every open scan, called when the app exits
+pub(crate) fn save_on_exit(app: &AppHandle) {
+    let (session, store) = match (app.try_state::<Session>(), app.try_state::<ScanStore>()) {
+        (Some(session), Some(store)) => (session, store),
+        _ => return,
+    };
+    let dir = match app.path().app_data_dir() {
+        Ok(dir) => dir,
+        Err(_) => return,
+    };
+
+    let mut state = session
+        .state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    state.saved_at = now();
+    state.event_id = fs_events::current_event_id();
+
+    // Start from an empty snapshot folder so closed roots don't linger
+    let snapshots = dir.join(SNAPSHOT_DIR);
+    let _ = std::fs::remove_dir_all(&snapshots);
+    let _ = std::fs::create_dir_all(&snapshots);
+    for scan in &state.scans {
+        if let Ok(tree) = store.get(&scan.root) {
+            if let Ok(contents) = serde_json::to_vec(&tree) {
+                let _ = std::fs::write(snapshot_file(&snapshots, &scan.root), contents);
+            }
+        }
+    }
+
+    if let Ok(contents) = serde_json::to_string_pretty(&state) {
+        let _ = std::fs::write(dir.join(SESSION_FILE), contents);
+    }
+}
+
+#[command]
+pub async fn set_session_view(
+    session: State<'_, Session>,
+    root: String,
+    expanded: Vec<String>,
+    active: bool,
+) -> Result<(), DiskSenseError> {
+    let mut state = session.state.lock().unwrap_or_else(|e| e.into_inner());
+    let scan = state
+        .scans
+        .iter_mut()
+        .find(|scan| scan.root == root)
+        .ok_or_else(|| DiskSenseError::NotFound { path: root.clone() })?;
+    scan.expanded = expanded;
+    if active {
+        state.active = Some(root);
+    }
+    Ok(())
+}
+
+// Drop a root from the session so it isn't restored next time
+#[command]
+pub async fn close_session_scan(
+    session: State<'_, Session>,
+    root: String,
+) -> Result<(), DiskSenseError> {
+    let mut state = session.state.lock().unwrap_or_else(|e| e.into_inner());
+    state.scans.retain(|scan| scan.root != root);
+    if state.active.as_deref() == Some(root.as_str()) {
+        state.active = None;
+    }
+    Ok(())
+}
+
+// Bring back the scans open when the app last exited. Snapshots are used as they
+// are unless they are older than `max_age_seconds`, in which case the root is
+// rescanned with its saved options; without a snapshot it is always rescanned.
+// Where the changes since the snapshot are known (FSEvents on macOS, or the
+// change recorder on Linux while the app kept running), a snapshot of any age is
+// brought up to date by relisting only the folders that changed.
+#[command]
+pub async fn restore_session(
+    app: AppHandle,
+    store: State<'_, ScanStore>,
+    session: State<'_, Session>,
+    max_age_seconds: Option<u64>,
+) -> Result<RestoredSession, DiskSenseError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| DiskSenseError::Failed("App data directory is unavailable".to_string()))?;
+    let saved: SessionState = match std::fs::read_to_string(dir.join(SESSION_FILE)) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| DiskSenseError::Failed(format!("Saved session is unreadable: {}", e)))?,
+        Err(_) => SessionState::default(),
+    };
+
+    let age = now().saturating_sub(saved.saved_at);
+    let fresh_enough = max_age_seconds.map_or(true, |max| age <= max);
+    let snapshots = dir.join(SNAPSHOT_DIR);
+
+    let mut restored = RestoredSession {
+        scans: Vec::new(),
+        active: saved.active.clone(),
+        missing: Vec::new(),
+    };
+    let mut trees: HashMap<String, DiskItem> = HashMap::new();
+
+    for scan in saved.scans.iter().cloned() {
+        if !Path::new(&scan.root).exists() {
+            restored.missing.push(scan.root);
+            continue;
+        }
+
+        let snapshot = std::fs::read(snapshot_file(&snapshots, &scan.root))
+            .ok()
+            .and_then(|contents| serde_json::from_slice::<DiskItem>(&contents).ok());
+        let changed = snapshot.as_ref().and_then(|_| {
+            let root = Path::new(&scan.root);
+            saved
+                .event_id
+                .and_then(|id| fs_events::changed_since(root, id))
+                .or_else(|| change_recorder::changed_since(&app, root, saved.saved_at))
+        });
+
+        let (tree, from_snapshot, refreshed_dirs) = match (snapshot, changed) {
+            (Some(tree), Some(dirty)) if dirty.is_empty() => {
+                trees.insert(scan.root.clone(), tree.clone());
+                (tree, true, Some(0))
+            }
+            (Some(tree), Some(dirty)) => match incremental::refresh(
+                &app,
+                &store,
+                &tree,
+                scan.depth,
+                scan.options.clone(),
+                &dirty,
+            ) {
+                Ok(tree) => (tree, false, Some(dirty.len())),
+                Err(_) => {
+                    restored.missing.push(scan.root);
+                    continue;
+                }
+            },
+            (Some(tree), None) if fresh_enough => {
+                trees.insert(scan.root.clone(), tree.clone());
+                (tree, true, None)
+            }
+            _ => match perform_scan(
+                &app,
+                &store,
+                Path::new(&scan.root),
+                scan.depth,
+                scan.options.clone(),
+                HashMap::new(),
+            ) {
+                Ok(tree) => (tree, false, None),
+                Err(_) => {
+                    restored.missing.push(scan.root);
+                    continue;
+                }
+            },
+        };
+
+        restored.scans.push(RestoredScan {
+            scan,
+            tree,
+            from_snapshot,
+            snapshot_age_seconds: from_snapshot.then_some(age),
+            refreshed_dirs,
+        });
+    }
+
+    // Snapshots go into the store so reports and queries work on them too
+    if let Ok(mut scans) = store.scans.lock() {
+        scans.extend(trees);
+    }
+    *session.state.lock().unwrap_or_else(|e| e.into_inner()) = SessionState {
+        scans: restored.scans.iter().map(|r| r.scan.clone()).collect(),
+        active: saved.active,
+        saved_at: saved.saved_at,
+        event_id: saved.event_id,
+    };
+
+    Ok(restored)
+}
+
+fn snapshot_file(dir: &Path, root: &str) -> PathBuf {
+    dir.join(format!("{}.json", node_id(root)))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,157 @@
+use crate::remote::{build_tree, RemoteEntry, RemoteScan};
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use ssh2::{Session, Sftp};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+// Sessions opened to list directories in parallel; most servers cap
+// concurrent sessions per user at around ten
+const SFTP_CONNECTIONS: usize = 4;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SftpCredentials {
+    username: String,
+    password: Option<String>,
+    // Private key file; the SSH agent is tried when neither this nor a password is set
+    private_key: Option<String>,
+    passphrase: Option<String>,
+}
+
+// Walk `path` on an SFTP server and build the usual tree. `host` may carry a
+// port ("example.com:2222"). Directories are listed level by level, split
+// across a fixed number of sessions.
+#[command]
+pub async fn scan_remote_sftp(
+    host: String,
+    path: String,
+    credentials: SftpCredentials,
+) -> Result<RemoteScan, DiskSenseError> {
+    let root = if path.is_empty() {
+        "/".to_string()
+    } else {
+        path
+    };
+
+    let mut sessions = Vec::with_capacity(SFTP_CONNECTIONS);
+    sessions.push(connect(&host, &credentials)?);
+    // Extra sessions are best effort; the scan still works over one
+    while sessions.len() < SFTP_CONNECTIONS {
+        match connect(&host, &credentials) {
+            Ok(session) => sessions.push(session),
+            Err(_) => break,
+        }
+    }
+
+    let root_path = PathBuf::from(&root);
+    let mut level = vec![root_path.clone()];
+    let mut entries = Vec::new();
+    let mut unreadable = Vec::new();
+
+    while !level.is_empty() {
+        let chunk_size = level.len().div_ceil(sessions.len());
+        let results: Vec<Listing> = std::thread::scope(|scope| {
+            let workers: Vec<_> = sessions
+                .iter_mut()
+                .zip(level.chunks(chunk_size))
+                .map(|((_, sftp), dirs)| scope.spawn(move || list_dirs(&*sftp, dirs)))
+                .collect();
+            workers
+                .into_iter()
+                .filter_map(|worker| worker.join().ok())
+                .collect()
+        });
+
+        level = Vec::new();
+        for listing in results {
+            for (file, size) in listing.files {
+                let relative = file.strip_prefix(&root_path).unwrap_or(&file);
+                entries.push(RemoteEntry {
+                    path: relative.to_string_lossy().replace('\\', "/"),
+                    size,
+                });
+            }
+            level.extend(listing.dirs);
+            unreadable.extend(listing.unreadable);
+        }
+    }
+
+    let display = format!("sftp://{}/{}", host, root.trim_start_matches('/'));
+    Ok(RemoteScan {
+        file_count: entries.len() as u64,
+        root: build_tree(&display, entries),
+        unreadable,
+    })
+}
+
+fn connect(host: &str, credentials: &SftpCredentials) -> Result<(Session, Sftp), DiskSenseError> {
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:22", host)
+    };
+    let tcp = TcpStream::connect(&address)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to connect to {}: {}", host, e)))?;
+
+    let ssh_error =
+        |e: ssh2::Error| DiskSenseError::Failed(format!("SSH error with {}: {}", host, e));
+    let mut session = Session::new().map_err(ssh_error)?;
+    session.set_tcp_stream(tcp);
+    session.handshake().map_err(ssh_error)?;
+
+    let user = &credentials.username;
+    let auth = if let Some(key) = &credentials.private_key {
+        session.userauth_pubkey_file(
+            user,
+            None,
+            Path::new(key),
+            credentials.passphrase.as_deref(),
+        )
+    } else if let Some(password) = &credentials.password {
+        session.userauth_password(user, password)
+    } else {
+        session.userauth_agent(user)
+    };
+    if auth.is_err() || !session.authenticated() {
+        return Err(DiskSenseError::PermissionDenied {
+            path: format!("{}@{}", user, host),
+        });
+    }
+
+    let sftp = session.sftp().map_err(ssh_error)?;
+    Ok((session, sftp))
+}
+
+#[derive(Default)]
+struct Listing {
+    files: Vec<(PathBuf, u64)>,
+    dirs: Vec<PathBuf>,
+    unreadable: Vec<String>,
+}
+
+fn list_dirs(sftp: &Sftp, dirs: &[PathBuf]) -> Listing {
+    let mut listing = Listing::default();
+    for dir in dirs {
+        let children = match sftp.readdir(dir) {
+            Ok(children) => children,
+            Err(_) => {
+                listing.unreadable.push(dir.to_string_lossy().to_string());
+                continue;
+            }
+        };
+
+        for (path, stat) in children {
+            // Symlinks are not followed, like the local scanner
+            if stat.file_type().is_symlink() {
+                continue;
+            }
+            if stat.is_dir() {
+                listing.dirs.push(path);
+            } else {
+                listing.files.push((path, stat.size.unwrap_or(0)));
+            }
+        }
+    }
+    listing
+}
@@ -0,0 +1,76 @@
+use crate::DiskItem;
+use std::fs::Metadata;
+use std::path::Path;
+
+// Bytes actually allocated on disk for a file, and whether it is sparse.
+// Sparse files (VM disks, databases) can be far larger than the space they use.
+pub(crate) fn allocation(path: &Path, metadata: &Metadata) -> (Option<u64>, bool) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let _ = path;
+        // st_blocks is always in 512-byte units; one block of slack covers tail packing
+        let allocated = metadata.blocks() * 512;
+        let sparse = allocated + metadata.blksize() < metadata.len();
+        (Some(allocated), sparse)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::ffi::OsStrExt;
+        use std::os::windows::fs::MetadataExt;
+        use winapi::um::fileapi::{GetCompressedFileSizeW, INVALID_FILE_SIZE};
+        use winapi::um::winnt::FILE_ATTRIBUTE_SPARSE_FILE;
+
+        let sparse = metadata.file_attributes() & FILE_ATTRIBUTE_SPARSE_FILE != 0;
+
+        // For sparse and compressed files this is the allocated size, otherwise the length
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut high = 0;
+        let low = unsafe { GetCompressedFileSizeW(wide.as_ptr(), &mut high) };
+        let allocated = if low == INVALID_FILE_SIZE
+            && std::io::Error::last_os_error().raw_os_error() != Some(0)
+        {
+            None
+        } else {
+            Some((u64::from(high) << 32) | u64::from(low))
+        };
+
+        (allocated, sparse)
+    }
+}
+
+// Fill in directory allocation totals from what is known below them. Folders
+// past the depth limit are listed without contents, so their allocation isn't
+// known; a total missing some is kept and marked partial rather than dropped.
+pub fn sum_allocated(item: &mut DiskItem) -> Option<u64> {
+    if !item.is_dir {
+        return item.allocated_size;
+    }
+
+    let children = match item.children.as_mut() {
+        Some(children) if !children.is_empty() => children,
+        // A folder that really is empty takes no space for contents
+        Some(_) if item.size == 0 => {
+            item.allocated_size = Some(0);
+            return item.allocated_size;
+        }
+        _ => return None,
+    };
+
+    let mut total = 0u64;
+    let mut partial = false;
+    for child in children.iter_mut() {
+        match sum_allocated(child) {
+            Some(bytes) => total += bytes,
+            None => partial = true,
+        }
+        partial |= child.allocated_partial;
+    }
+
+    // Nothing known at all is no total
+    item.allocated_size = (total > 0 || !partial).then_some(total);
+    item.allocated_partial = partial;
+    item.allocated_size
+}
@@ -0,0 +1,324 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, deletion, home_dir, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::Disks;
+use tauri::{command, AppHandle, State};
+
+// Folder created at the root of each drive to hold staged items
+const STAGING_DIR_NAME: &str = ".disksense-staging";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StagedItem {
+    id: String,
+    original_path: String,
+    staged_path: String,
+    pub(crate) size: u64,
+    staged_at: u64,
+}
+
+// Move a path into its drive's staging folder instead of deleting it.
+// Staying on the same drive keeps this a cheap rename.
+pub(crate) fn stage_path(path: &Path) -> Result<StagedItem, DiskSenseError> {
+    let metadata = std::fs::symlink_metadata(path).map_err(|e| DiskSenseError::from_io(e, path))?;
+    let size = if metadata.is_dir() {
+        get_size(path).unwrap_or(0)
+    } else {
+        metadata.len()
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let id = now.as_nanos().to_string();
+
+    let staging_dir = staging_dir_for(path)?;
+    let item_dir = staging_dir.join(&id);
+    std::fs::create_dir_all(&item_dir).map_err(|e| DiskSenseError::from_io(e, &item_dir))?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| DiskSenseError::InvalidInput(format!("Cannot stage {}", path.display())))?;
+    let staged_path = item_dir.join(name);
+
+    if let Err(e) = std::fs::rename(path, &staged_path) {
+        let _ = std::fs::remove_dir(&item_dir);
+        return Err(DiskSenseError::from_io(e, path));
+    }
+
+    let item = StagedItem {
+        id: id.clone(),
+        original_path: path.to_string_lossy().to_string(),
+        staged_path: staged_path.to_string_lossy().to_string(),
+        size,
+        staged_at: now.as_secs(),
+    };
+
+    // The manifest sits next to the item folder so restore knows where it came from
+    let manifest = staging_dir.join(format!("{}.json", id));
+    let contents = serde_json::to_string_pretty(&item)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to record staged item: {}", e)))?;
+    std::fs::write(&manifest, contents).map_err(|e| DiskSenseError::from_io(e, &manifest))?;
+
+    Ok(item)
+}
+
+#[command]
+pub async fn list_staged() -> Result<Vec<StagedItem>, DiskSenseError> {
+    let mut items: Vec<StagedItem> = staging_dirs()
+        .iter()
+        .flat_map(|dir| read_manifests(dir))
+        .map(|(_, item)| item)
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.staged_at));
+    Ok(items)
+}
+
+// Permanently delete everything that has been staged, returning the bytes freed
+#[command]
+pub async fn purge_staged(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    action: Option<String>,
+) -> Result<u64, DiskSenseError> {
+    read_only.ensure_writable()?;
+    let result = purge();
+    audit::record(
+        &app,
+        "purge_staged",
+        action.as_deref(),
+        Vec::new(),
+        *result.as_ref().unwrap_or(&0),
+        result.as_ref().err(),
+    );
+    result
+}
+
+pub(crate) fn purge() -> Result<u64, DiskSenseError> {
+    let mut freed = 0;
+
+    for dir in staging_dirs() {
+        for (manifest, item) in read_manifests(&dir) {
+            let item_dir = dir.join(&item.id);
+            if item_dir.exists() {
+                deletion::remove_path(&item_dir)?;
+            }
+            std::fs::remove_file(&manifest).map_err(|e| DiskSenseError::from_io(e, &manifest))?;
+            freed += item.size;
+        }
+
+        // Drop the staging folder itself once it is empty
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    Ok(freed)
+}
+
+// Move a staged item back to where it was deleted from
+#[command]
+pub async fn restore_staged(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    path: String,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    let result = restore(&path);
+    audit::record(
+        &app,
+        "restore_staged",
+        action.as_deref(),
+        vec![path],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn restore(path: &str) -> Result<(), DiskSenseError> {
+    let (staging_dir, manifest, item) = staging_dirs()
+        .into_iter()
+        .flat_map(|dir| {
+            read_manifests(&dir)
+                .into_iter()
+                .map(move |(manifest, item)| (dir.clone(), manifest, item))
+        })
+        .find(|(_, _, item)| item.original_path == path || item.id == path)
+        .ok_or_else(|| DiskSenseError::NotFound {
+            path: path.to_string(),
+        })?;
+
+    let original = Path::new(&item.original_path);
+    if original.exists() {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Cannot restore, something already exists at {}",
+            item.original_path
+        )));
+    }
+
+    if let Some(parent) = original.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+    }
+    std::fs::rename(&item.staged_path, original)
+        .map_err(|e| DiskSenseError::from_io(e, &item.staged_path))?;
+
+    let _ = std::fs::remove_dir(staging_dir.join(&item.id));
+    let _ = std::fs::remove_file(&manifest);
+    let _ = std::fs::remove_dir(&staging_dir);
+
+    Ok(())
+}
+
+fn staging_dir_for(path: &Path) -> Result<PathBuf, DiskSenseError> {
+    let disks = Disks::new_with_refreshed_list();
+    let mounts: Vec<&Path> = disks.iter().map(|disk| disk.mount_point()).collect();
+    choose_staging_dir(path, &mounts, home_dir().as_deref()).ok_or_else(|| {
+        DiskSenseError::Unsupported(format!(
+            "{} is not on a known drive, so it can't be staged",
+            path.display()
+        ))
+    })
+}
+
+// Staging folder on the same drive as `path`. Paths in the user's home use a folder
+// there, since the drive root is often not writable. Only these folders are
+// searched by staging_dirs, so a path on no known drive gets None rather than a
+// folder that could never be listed, restored or purged.
+fn choose_staging_dir(path: &Path, mounts: &[&Path], home: Option<&Path>) -> Option<PathBuf> {
+    let mount = mounts
+        .iter()
+        .filter(|mount| path.starts_with(mount))
+        .max_by_key(|mount| mount.as_os_str().len());
+
+    if let Some(home) = home {
+        let home_on_same_drive = mount.map_or(true, |mount| home.starts_with(mount));
+        if path.starts_with(home) && home_on_same_drive {
+            return Some(home.join(STAGING_DIR_NAME));
+        }
+    }
+
+    mount.map(|mount| mount.join(STAGING_DIR_NAME))
+}
+
+// Every staging folder that currently exists
+fn staging_dirs() -> Vec<PathBuf> {
+    let disks = Disks::new_with_refreshed_list();
+    let mut dirs: Vec<PathBuf> = disks
+        .iter()
+        .map(|disk| disk.mount_point().join(STAGING_DIR_NAME))
+        .chain(home_dir().map(|home| home.join(STAGING_DIR_NAME)))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    // The home folder can be a mount point of its own
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn read_manifests(dir: &Path) -> Vec<(PathBuf, StagedItem)> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let contents = std::fs::read_to_string(&path).ok()?;
+            let item = serde_json::from_str(&contents).ok()?;
+            Some((path, item))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_on_the_deepest_mount_holding_the_path() {
+        let mounts = [Path::new("/"), Path::new("/mnt/data")];
+        assert_eq!(
+            choose_staging_dir(Path::new("/mnt/data/old/file.iso"), &mounts, None),
+            Some(Path::new("/mnt/data").join(STAGING_DIR_NAME))
+        );
+        assert_eq!(
+            choose_staging_dir(Path::new("/var/tmp/file"), &mounts, None),
+            Some(Path::new("/").join(STAGING_DIR_NAME))
+        );
+    }
+
+    #[test]
+    fn stages_in_home_when_home_is_on_the_same_drive() {
+        let mounts = [Path::new("/"), Path::new("/mnt/data")];
+        let home = Path::new("/home/user");
+        assert_eq!(
+            choose_staging_dir(Path::new("/home/user/videos/a.mkv"), &mounts, Some(home)),
+            Some(home.join(STAGING_DIR_NAME))
+        );
+        assert_eq!(
+            choose_staging_dir(Path::new("/mnt/data/x"), &mounts, Some(home)),
+            Some(Path::new("/mnt/data").join(STAGING_DIR_NAME))
+        );
+        // A drive mounted inside home keeps its own folder, so the move stays a rename
+        let mounts = [Path::new("/"), Path::new("/home/user/external")];
+        assert_eq!(
+            choose_staging_dir(
+                Path::new("/home/user/external/backup.tar"),
+                &mounts,
+                Some(home)
+            ),
+            Some(Path::new("/home/user/external").join(STAGING_DIR_NAME))
+        );
+    }
+
+    #[test]
+    fn refuses_paths_on_no_known_drive() {
+        let mounts = [Path::new("/mnt/data")];
+        assert_eq!(
+            choose_staging_dir(Path::new("/srv/file"), &mounts, None),
+            None
+        );
+        assert_eq!(
+            choose_staging_dir(
+                Path::new("/srv/file"),
+                &mounts,
+                Some(Path::new("/home/user"))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn manifests_are_read_and_other_files_skipped() {
+        let dir = std::env::temp_dir().join(format!(
+            "disksense-staging-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos())
+        ));
+        std::fs::create_dir_all(dir.join("1")).unwrap();
+        let item = StagedItem {
+            id: "1".to_string(),
+            original_path: "/data/old.iso".to_string(),
+            staged_path: dir.join("1").join("old.iso").to_string_lossy().to_string(),
+            size: 42,
+            staged_at: 7,
+        };
+        std::fs::write(dir.join("1.json"), serde_json::to_string(&item).unwrap()).unwrap();
+        std::fs::write(dir.join("2.json"), "not json").unwrap();
+        std::fs::write(dir.join("notes.txt"), "{}").unwrap();
+
+        let manifests = read_manifests(&dir);
+        assert_eq!(manifests.len(), 1);
+        assert_eq!(manifests[0].0, dir.join("1.json"));
+        assert_eq!(manifests[0].1.original_path, "/data/old.iso");
+        assert_eq!(manifests[0].1.size, 42);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, DiskSenseError};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SystemFileKind {
+    Hibernation,
+    Pagefile,
+    Swapfile,
+}
+
+// A file the OS keeps for memory management. Scans often can't open these, so
+// they are stat'ed directly.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SystemFile {
+    kind: SystemFileKind,
+    path: String,
+    size: u64,
+}
+
+#[command]
+pub async fn get_system_file_sizes() -> Result<Vec<SystemFile>, DiskSenseError> {
+    let files = candidates()
+        .into_iter()
+        .filter_map(|(kind, path)| {
+            // std falls back to directory listing data for files locked by the OS
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some(SystemFile {
+                kind,
+                path: path.to_string_lossy().to_string(),
+                size: metadata.len(),
+            })
+        })
+        .collect();
+
+    Ok(files)
+}
+
+fn candidates() -> Vec<(SystemFileKind, PathBuf)> {
+    let mut files = Vec::new();
+
+    if cfg!(target_os = "windows") {
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        let root = PathBuf::from(format!("{}\\", drive));
+        files.push((SystemFileKind::Hibernation, root.join("hiberfil.sys")));
+        files.push((SystemFileKind::Pagefile, root.join("pagefile.sys")));
+        files.push((SystemFileKind::Swapfile, root.join("swapfile.sys")));
+    } else if cfg!(target_os = "macos") {
+        let vm = PathBuf::from("/private/var/vm");
+        files.push((SystemFileKind::Hibernation, vm.join("sleepimage")));
+        if let Ok(entries) = std::fs::read_dir(&vm) {
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                if entry.file_name().to_string_lossy().starts_with("swapfile") {
+                    files.push((SystemFileKind::Swapfile, entry.path()));
+                }
+            }
+        }
+    } else {
+        // Swap files in use are listed with type "file"; partitions don't take space in a scan
+        if let Ok(swaps) = std::fs::read_to_string("/proc/swaps") {
+            for line in swaps.lines().skip(1) {
+                let mut fields = line.split_whitespace();
+                if let (Some(path), Some("file")) = (fields.next(), fields.next()) {
+                    files.push((SystemFileKind::Swapfile, PathBuf::from(path)));
+                }
+            }
+        }
+    }
+
+    files
+}
+
+// Turn hibernation off, which deletes hiberfil.sys (usually 40% of RAM).
+// Fast Startup depends on it and stops working too. Needs administrator rights.
+#[command]
+pub async fn disable_hibernation(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Disabling hibernation must be confirmed before it is run".to_string(),
+        ));
+    }
+    ensure_windows()?;
+
+    let hiberfil = candidates()
+        .into_iter()
+        .find(|(kind, _)| *kind == SystemFileKind::Hibernation)
+        .map(|(_, path)| path)
+        .unwrap_or_default();
+    let size = std::fs::metadata(&hiberfil).map(|m| m.len()).unwrap_or(0);
+    let result = hibernate_off();
+    audit::record(
+        &app,
+        "disable_hibernation",
+        action.as_deref(),
+        vec![hiberfil.to_string_lossy().to_string()],
+        if result.is_ok() { size } else { 0 },
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn hibernate_off() -> Result<(), DiskSenseError> {
+    let output = Command::new("powercfg")
+        .args(["/hibernate", "off"])
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "powercfg"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "Failed to disable hibernation: {}",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )))
+    }
+}
+
+// Give the pagefile a fixed size in MB, or hand it back to Windows with None.
+// Too small a pagefile can crash programs when memory runs out and prevents
+// crash dumps. Takes effect after a restart; needs administrator rights.
+#[command]
+pub async fn set_pagefile_size(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    initial_mb: Option<u32>,
+    maximum_mb: Option<u32>,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Pagefile changes must be confirmed before they are made".to_string(),
+        ));
+    }
+    ensure_windows()?;
+
+    let script = match (initial_mb, maximum_mb) {
+        (None, None) => "Get-CimInstance Win32_ComputerSystem | \
+             Set-CimInstance -Property @{AutomaticManagedPagefile=$true}"
+            .to_string(),
+        (Some(initial), Some(maximum)) if initial <= maximum && maximum > 0 => format!(
+            "Get-CimInstance Win32_ComputerSystem | \
+             Set-CimInstance -Property @{{AutomaticManagedPagefile=$false}}; \
+             Get-CimInstance Win32_PageFileSetting | \
+             Set-CimInstance -Property @{{InitialSize={}; MaximumSize={}}}",
+            initial, maximum
+        ),
+        _ => {
+            return Err(DiskSenseError::InvalidInput(
+                "Give both sizes with the initial size no larger than the maximum".to_string(),
+            ))
+        }
+    };
+
+    let result = run_powershell(&script);
+    let pagefile = candidates()
+        .into_iter()
+        .filter(|(kind, _)| *kind == SystemFileKind::Pagefile)
+        .map(|(_, path)| path.to_string_lossy().to_string())
+        .collect();
+    audit::record(
+        &app,
+        "set_pagefile_size",
+        action.as_deref(),
+        pagefile,
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn run_powershell(script: &str) -> Result<(), DiskSenseError> {
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "PowerShell"))?;
+    if output.status.success() && output.stderr.is_empty() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "Failed to change the pagefile: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+fn ensure_windows() -> Result<(), DiskSenseError> {
+    if cfg!(target_os = "windows") {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Unsupported(
+            "Hibernation and pagefile settings are only managed on Windows".to_string(),
+        ))
+    }
+}
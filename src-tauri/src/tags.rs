@@ -0,0 +1,175 @@
+use crate::DiskSenseError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{command, AppHandle, Manager, State};
+
+// File in the app data dir holding tags and notes
+const TAGS_FILE: &str = "item_tags.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ItemAnnotation {
+    tags: Vec<String>,
+    note: Option<String>,
+    updated_at: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TaggedItem {
+    path: String,
+    exists: bool,
+    #[serde(flatten)]
+    annotation: ItemAnnotation,
+}
+
+// Tags ("keep", "review later", "delete next cleanup"...) and notes on paths,
+// persisted in the app data dir so they survive across sessions
+#[derive(Default)]
+pub struct ItemTags {
+    items: Mutex<BTreeMap<String, ItemAnnotation>>,
+}
+
+impl ItemTags {
+    pub(crate) fn load(app: &AppHandle) -> Self {
+        let items = tags_file(app)
+            .and_then(|file| std::fs::read_to_string(file).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        ItemTags {
+            items: Mutex::new(items),
+        }
+    }
+
+    fn save(&self, app: &AppHandle) -> Result<(), DiskSenseError> {
+        let file = tags_file(app).ok_or_else(|| {
+            DiskSenseError::Failed("App data directory is unavailable".to_string())
+        })?;
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DiskSenseError::from_io(e, parent))?;
+        }
+
+        let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let contents = serde_json::to_string_pretty(&*items)
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to save tags: {}", e)))?;
+        std::fs::write(&file, contents).map_err(|e| DiskSenseError::from_io(e, &file))
+    }
+
+    // Change one path's annotation, dropping it once it has no tags or note left
+    fn update(
+        &self,
+        app: &AppHandle,
+        path: String,
+        change: impl FnOnce(&mut ItemAnnotation),
+    ) -> Result<(), DiskSenseError> {
+        {
+            let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+            let annotation = items.entry(path.clone()).or_default();
+            change(annotation);
+            annotation.updated_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            if annotation.tags.is_empty() && annotation.note.is_none() {
+                items.remove(&path);
+            }
+        }
+        self.save(app)
+    }
+}
+
+fn tags_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(TAGS_FILE))
+}
+
+#[command]
+pub async fn tag_item(
+    app: AppHandle,
+    tags: State<'_, ItemTags>,
+    path: String,
+    tag: String,
+) -> Result<(), DiskSenseError> {
+    let tag = tag.trim().to_string();
+    if tag.is_empty() {
+        return Err(DiskSenseError::InvalidInput(
+            "Tag cannot be empty".to_string(),
+        ));
+    }
+
+    tags.update(&app, path, |annotation| {
+        if !annotation.tags.contains(&tag) {
+            annotation.tags.push(tag);
+        }
+    })
+}
+
+#[command]
+pub async fn untag_item(
+    app: AppHandle,
+    tags: State<'_, ItemTags>,
+    path: String,
+    tag: String,
+) -> Result<(), DiskSenseError> {
+    tags.update(&app, path, |annotation| {
+        annotation.tags.retain(|existing| *existing != tag.trim());
+    })
+}
+
+// Set or, with None or an empty note, clear the note on a path
+#[command]
+pub async fn set_item_note(
+    app: AppHandle,
+    tags: State<'_, ItemTags>,
+    path: String,
+    note: Option<String>,
+) -> Result<(), DiskSenseError> {
+    tags.update(&app, path, |annotation| {
+        annotation.note = note.filter(|note| !note.trim().is_empty());
+    })
+}
+
+#[command]
+pub async fn get_item_annotation(
+    tags: State<'_, ItemTags>,
+    path: String,
+) -> Result<Option<ItemAnnotation>, DiskSenseError> {
+    let items = tags.items.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(items.get(&path).cloned())
+}
+
+// Every path carrying `tag`, most recently changed first. Paths that no longer
+// exist are still listed, flagged, so their tags can be cleaned up.
+#[command]
+pub async fn get_items_by_tag(
+    tags: State<'_, ItemTags>,
+    tag: String,
+) -> Result<Vec<TaggedItem>, DiskSenseError> {
+    let items = tags.items.lock().unwrap_or_else(|e| e.into_inner());
+    let mut tagged: Vec<TaggedItem> = items
+        .iter()
+        .filter(|(_, annotation)| annotation.tags.contains(&tag))
+        .map(|(path, annotation)| TaggedItem {
+            exists: std::path::Path::new(path).exists(),
+            path: path.clone(),
+            annotation: annotation.clone(),
+        })
+        .collect();
+    tagged.sort_by_key(|item| std::cmp::Reverse(item.annotation.updated_at));
+    Ok(tagged)
+}
+
+// Every tag in use, with how many paths carry it
+#[command]
+pub async fn list_tags(tags: State<'_, ItemTags>) -> Result<Vec<(String, usize)>, DiskSenseError> {
+    let items = tags.items.lock().unwrap_or_else(|e| e.into_inner());
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for tag in items.values().flat_map(|annotation| &annotation.tags) {
+        *counts.entry(tag.clone()).or_default() += 1;
+    }
+    Ok(counts.into_iter().collect())
+}
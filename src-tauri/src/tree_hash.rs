@@ -0,0 +1,168 @@
+use crate::hashing::{hash_file_with, to_hex};
+use crate::{os_path, DiskSenseError};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{command, AppHandle, Emitter};
+use walkdir::WalkDir;
+
+// Files hashed between "hash-tree-progress" events
+const PROGRESS_EVERY: u64 = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HashTreeProgress {
+    path: String,
+    files_done: u64,
+    files_total: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TreeHash {
+    algo: String,
+    hash: String,
+    files: u64,
+    dirs: u64,
+    bytes: u64,
+}
+
+enum Entry {
+    Dir,
+    File(u64),
+    Link(String),
+}
+
+// One hash for a whole folder: two copies with the same relative paths, file
+// contents and links hash the same, whatever the platform or timestamps.
+// `algo` is "sha256" (default) or "sha512".
+#[command]
+pub async fn hash_tree(
+    app: AppHandle,
+    path: String,
+    algo: Option<String>,
+) -> Result<TreeHash, DiskSenseError> {
+    let algo = algo.unwrap_or_else(|| "sha256".to_string()).to_lowercase();
+    let root = os_path::decode(&path);
+    if !root.is_dir() {
+        return Err(DiskSenseError::NotFound { path });
+    }
+
+    let (hash, files, dirs, bytes) = match algo.as_str() {
+        "sha256" => combine::<Sha256>(&app, &path, &root)?,
+        "sha512" => combine::<Sha512>(&app, &path, &root)?,
+        _ => {
+            return Err(DiskSenseError::InvalidInput(format!(
+                "Unknown hash algorithm: {}",
+                algo
+            )))
+        }
+    };
+
+    Ok(TreeHash {
+        algo,
+        hash,
+        files,
+        dirs,
+        bytes,
+    })
+}
+
+// Feed "<relative path>\0<kind>:<value>\n" for every entry, sorted by path,
+// into one digest; file contents go in as their own hash
+fn combine<D: Digest>(
+    app: &AppHandle,
+    path: &str,
+    root: &Path,
+) -> Result<(String, u64, u64, u64), DiskSenseError> {
+    let mut entries = list_entries(root)?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let files_total = entries
+        .iter()
+        .filter(|(_, entry)| matches!(entry, Entry::File(_)))
+        .count() as u64;
+    let files_done = AtomicU64::new(0);
+
+    let lines: Vec<String> = entries
+        .par_iter()
+        .map(|(relative, entry)| {
+            let value = match entry {
+                Entry::Dir => "dir:".to_string(),
+                Entry::Link(target) => format!("link:{}", target),
+                Entry::File(_) => {
+                    let hash = hash_file_with::<D>(&root.join(relative))
+                        .map_err(|e| DiskSenseError::from_io(e, root.join(relative)))?;
+                    let done = files_done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if done % PROGRESS_EVERY == 0 || done == files_total {
+                        let _ = app.emit(
+                            "hash-tree-progress",
+                            HashTreeProgress {
+                                path: path.to_string(),
+                                files_done: done,
+                                files_total,
+                            },
+                        );
+                    }
+                    format!("file:{}", hash)
+                }
+            };
+            Ok(format!("{}\0{}\n", relative, value))
+        })
+        .collect::<Result<_, DiskSenseError>>()?;
+
+    let mut hasher = D::new();
+    for line in &lines {
+        hasher.update(line.as_bytes());
+    }
+
+    let dirs = entries
+        .iter()
+        .filter(|(_, entry)| matches!(entry, Entry::Dir))
+        .count() as u64;
+    let bytes = entries
+        .iter()
+        .map(|(_, entry)| match entry {
+            Entry::File(size) => *size,
+            _ => 0,
+        })
+        .sum();
+
+    Ok((to_hex(&hasher.finalize()), files_total, dirs, bytes))
+}
+
+// Every entry below `root` with its path relative to it, using '/' separators.
+// Any unreadable entry fails the hash, since it would no longer prove anything.
+fn list_entries(root: &Path) -> Result<Vec<(String, Entry)>, DiskSenseError> {
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(root).min_depth(1) {
+        let entry = entry.map_err(|e| {
+            let path = e.path().unwrap_or(root).to_path_buf();
+            DiskSenseError::from_io(io::Error::from(e), path)
+        })?;
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let kind = if entry.file_type().is_dir() {
+            Entry::Dir
+        } else if entry.file_type().is_symlink() {
+            let target = std::fs::read_link(entry.path())
+                .map_err(|e| DiskSenseError::from_io(e, entry.path()))?;
+            Entry::Link(target.to_string_lossy().replace('\\', "/"))
+        } else {
+            let size = entry
+                .metadata()
+                .map(|m| m.len())
+                .map_err(|e| DiskSenseError::from_io(io::Error::from(e), entry.path()))?;
+            Entry::File(size)
+        };
+        entries.push((relative, kind));
+    }
+
+    Ok(entries)
+}
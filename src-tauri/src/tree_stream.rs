@@ -0,0 +1,134 @@
+use crate::{DiskItem, DiskSenseError, ScanStore};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::{command, State};
+
+// Nodes per binary chunk; keeps each message well below the IPC's comfortable size
+const NODES_PER_CHUNK: usize = 16_384;
+
+// Parent index of the root node
+const NO_PARENT: u32 = u32::MAX;
+
+// Leads every chunk; bumped whenever the node layout changes, so a reader
+// built for another layout can refuse the stream instead of misreading it
+const FORMAT_VERSION: u8 = 2;
+
+const FLAG_DIR: u8 = 1;
+const FLAG_GIT_REPO: u8 = 2;
+const FLAG_HAS_CHILDREN: u8 = 4;
+const FLAG_ESTIMATED: u8 = 8;
+const FLAG_HIDDEN: u8 = 16;
+const FLAG_IN_ARCHIVE: u8 = 32;
+
+// Send a stored tree over a channel as compact binary chunks instead of one JSON value.
+//
+// Each chunk is a u8 format version and a little-endian u32 node count, followed
+// by that many nodes in pre-order (parents before children):
+//   u32 parent index, u64 size, u64 id, u8 flags,
+//   u16 name length + name, u32 path length + path, u16 game length + game,
+//   u32 raw_path length + raw_path (empty when the path is valid Unicode)
+// Strings are UTF-8. A chunk with no nodes marks the end of the stream.
+#[command]
+pub async fn stream_scan_tree(
+    store: State<'_, ScanStore>,
+    scan_id: String,
+    on_chunk: Channel<InvokeResponseBody>,
+) -> Result<usize, DiskSenseError> {
+    let root = store.get(&scan_id)?;
+
+    let mut writer = ChunkWriter {
+        channel: &on_chunk,
+        buffer: Vec::new(),
+        in_chunk: 0,
+        written: 0,
+    };
+    writer.write_node(&root, NO_PARENT)?;
+    if writer.in_chunk > 0 {
+        writer.flush()?;
+    }
+
+    // End-of-stream marker
+    writer.flush()?;
+
+    Ok(writer.written)
+}
+
+struct ChunkWriter<'a> {
+    channel: &'a Channel<InvokeResponseBody>,
+    buffer: Vec<u8>,
+    in_chunk: u32,
+    written: usize,
+}
+
+impl ChunkWriter<'_> {
+    fn write_node(&mut self, item: &DiskItem, parent: u32) -> Result<(), DiskSenseError> {
+        let index = self.written as u32;
+
+        let mut flags = 0;
+        if item.is_dir {
+            flags |= FLAG_DIR;
+        }
+        if item.git_repo {
+            flags |= FLAG_GIT_REPO;
+        }
+        if item.children.is_some() {
+            flags |= FLAG_HAS_CHILDREN;
+        }
+        if item.estimated {
+            flags |= FLAG_ESTIMATED;
+        }
+        if item.hidden {
+            flags |= FLAG_HIDDEN;
+        }
+        if item.in_archive {
+            flags |= FLAG_IN_ARCHIVE;
+        }
+
+        let buffer = &mut self.buffer;
+        buffer.extend_from_slice(&parent.to_le_bytes());
+        buffer.extend_from_slice(&item.size.to_le_bytes());
+        buffer.extend_from_slice(&u64::from_str_radix(&item.id, 16).unwrap_or(0).to_le_bytes());
+        buffer.push(flags);
+        write_short_str(buffer, &item.name);
+        write_long_str(buffer, &item.path);
+        write_short_str(buffer, item.game.as_deref().unwrap_or(""));
+        write_long_str(buffer, item.raw_path.as_deref().unwrap_or(""));
+
+        self.in_chunk += 1;
+        self.written += 1;
+        if self.in_chunk as usize >= NODES_PER_CHUNK {
+            self.flush()?;
+        }
+
+        if let Some(children) = item.children.as_ref() {
+            for child in children {
+                self.write_node(child, index)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), DiskSenseError> {
+        let mut chunk = Vec::with_capacity(self.buffer.len() + 5);
+        chunk.push(FORMAT_VERSION);
+        chunk.extend_from_slice(&self.in_chunk.to_le_bytes());
+        chunk.append(&mut self.buffer);
+        self.in_chunk = 0;
+
+        self.channel
+            .send(InvokeResponseBody::Raw(chunk))
+            .map_err(|e| DiskSenseError::Failed(format!("Failed to send tree chunk: {}", e)))
+    }
+}
+
+// Names longer than u16::MAX bytes do not exist on any supported file system
+fn write_short_str(buffer: &mut Vec<u8>, value: &str) {
+    let bytes = &value.as_bytes()[..value.len().min(u16::MAX as usize)];
+    buffer.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+fn write_long_str(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value.as_bytes());
+}
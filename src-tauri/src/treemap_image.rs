@@ -0,0 +1,297 @@
+use crate::{format_bytes, DiskItem, DiskSenseError, ScanStore};
+use std::fmt::Write;
+use tauri::{command, State};
+
+// Default output size in pixels when none is given
+const DEFAULT_SIZE: (u32, u32) = (1200, 800);
+
+// How many directory levels are nested inside the exported image
+const MAX_RENDER_DEPTH: usize = 3;
+
+// Height of the label strip drawn above nested directories
+const HEADER_HEIGHT: f64 = 16.0;
+
+// Same blue-to-red scale as getColorForSize in the front-end
+const SIZE_COLORS: [&str; 9] = [
+    "#3b82f6", "#60a5fa", "#93c5fd", "#6366f1", "#a855f7", "#d946ef", "#ec4899", "#f43f5e",
+    "#ef4444",
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+// Render the stored node at `node_path` to an SVG or PNG file at `path`
+#[command]
+pub async fn export_treemap_image(
+    store: State<'_, ScanStore>,
+    node_path: String,
+    format: String,
+    size: Option<(u32, u32)>,
+    path: String,
+) -> Result<(), DiskSenseError> {
+    let node = store.find_node(&node_path)?;
+    let (width, height) = size.unwrap_or(DEFAULT_SIZE);
+
+    if width == 0 || height == 0 {
+        return Err(DiskSenseError::InvalidInput(
+            "Image size must be greater than zero".to_string(),
+        ));
+    }
+
+    let svg = render_svg(&node, width, height);
+
+    match format.to_lowercase().as_str() {
+        "svg" => std::fs::write(&path, svg).map_err(|e| DiskSenseError::from_io(e, &path)),
+        "png" => render_png(&svg, width, height, &path),
+        _ => Err(DiskSenseError::InvalidInput(format!(
+            "Unsupported image format: {}",
+            format
+        ))),
+    }
+}
+
+fn render_png(svg: &str, width: u32, height: u32, path: &str) -> Result<(), DiskSenseError> {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+
+    let tree = resvg::usvg::Tree::from_str(svg, &options)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to parse treemap SVG: {}", e)))?;
+
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| DiskSenseError::Failed("Failed to allocate image".to_string()))?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap
+        .save_png(path)
+        .map_err(|e| DiskSenseError::Failed(format!("Failed to write PNG: {}", e)))
+}
+
+fn render_svg(node: &DiskItem, width: u32, height: u32) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"11\">",
+        w = width,
+        h = height,
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"#111827\"/>",
+        width, height
+    );
+
+    let bounds = Rect {
+        x: 0.0,
+        y: 0.0,
+        w: width as f64,
+        h: height as f64,
+    };
+    let max_size = node
+        .children
+        .as_ref()
+        .and_then(|children| children.iter().map(|child| child.size).max())
+        .unwrap_or(node.size);
+
+    render_children(node, bounds, 0, max_size, &mut svg);
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn render_children(item: &DiskItem, bounds: Rect, depth: usize, max_size: u64, svg: &mut String) {
+    let children: Vec<&DiskItem> = match &item.children {
+        Some(children) => children.iter().filter(|child| child.size > 0).collect(),
+        None => return,
+    };
+    if children.is_empty() {
+        return;
+    }
+
+    let sizes: Vec<f64> = children.iter().map(|child| child.size as f64).collect();
+    for (child, rect) in children.iter().zip(squarify(&sizes, bounds)) {
+        render_item(child, rect, depth, max_size, svg);
+    }
+}
+
+fn render_item(item: &DiskItem, rect: Rect, depth: usize, max_size: u64, svg: &mut String) {
+    // Leave a one pixel gap between neighbours so tiles stay distinguishable
+    let inner = Rect {
+        x: rect.x + 0.5,
+        y: rect.y + 0.5,
+        w: (rect.w - 1.0).max(0.0),
+        h: (rect.h - 1.0).max(0.0),
+    };
+    if inner.w < 1.0 || inner.h < 1.0 {
+        return;
+    }
+
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\" stroke=\"#111827\"/>",
+        inner.x,
+        inner.y,
+        inner.w,
+        inner.h,
+        color_for_size(item.size, max_size),
+    );
+
+    let has_children = item.children.as_ref().is_some_and(|c| !c.is_empty());
+    let nest = item.is_dir
+        && has_children
+        && depth + 1 < MAX_RENDER_DEPTH
+        && inner.w > 40.0
+        && inner.h > HEADER_HEIGHT * 3.0;
+
+    if nest {
+        write_label(svg, item, inner.x + 3.0, inner.y + 12.0, inner.w - 6.0);
+        let body = Rect {
+            x: inner.x + 2.0,
+            y: inner.y + HEADER_HEIGHT,
+            w: inner.w - 4.0,
+            h: inner.h - HEADER_HEIGHT - 2.0,
+        };
+        render_children(item, body, depth + 1, max_size, svg);
+    } else if inner.w > 40.0 && inner.h > 28.0 {
+        write_label(svg, item, inner.x + 3.0, inner.y + 12.0, inner.w - 6.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.2}\" y=\"{:.2}\" fill=\"#ffffff\" fill-opacity=\"0.8\">{}</text>",
+            inner.x + 3.0,
+            inner.y + 25.0,
+            format_bytes(item.size),
+        );
+    }
+}
+
+fn write_label(svg: &mut String, item: &DiskItem, x: f64, y: f64, max_width: f64) {
+    // Roughly 6.5px per character at 11px; truncate rather than overflow the tile
+    let max_chars = (max_width / 6.5).floor() as usize;
+    if max_chars < 2 {
+        return;
+    }
+
+    let mut label: String = item.name.chars().take(max_chars).collect();
+    if item.name.chars().count() > max_chars {
+        label.pop();
+        label.push('\u{2026}');
+    }
+
+    let _ = writeln!(
+        svg,
+        "<text x=\"{:.2}\" y=\"{:.2}\" fill=\"#ffffff\">{}</text>",
+        x,
+        y,
+        escape_xml(&label),
+    );
+}
+
+fn color_for_size(size: u64, max_size: u64) -> &'static str {
+    let ratio = if max_size > 0 {
+        size as f64 / max_size as f64
+    } else {
+        0.0
+    };
+    let index = ((ratio * SIZE_COLORS.len() as f64).floor() as usize).min(SIZE_COLORS.len() - 1);
+    SIZE_COLORS[index]
+}
+
+// Squarified treemap layout (Bruls, Huizing, van Wijk); sizes must be sorted descending
+fn squarify(sizes: &[f64], bounds: Rect) -> Vec<Rect> {
+    let total: f64 = sizes.iter().sum();
+    if total <= 0.0 || bounds.w <= 0.0 || bounds.h <= 0.0 {
+        return Vec::new();
+    }
+
+    // Scale sizes to areas so they exactly fill the bounds
+    let scale = bounds.w * bounds.h / total;
+    let areas: Vec<f64> = sizes.iter().map(|size| size * scale).collect();
+
+    let mut rects = Vec::with_capacity(areas.len());
+    let mut free = bounds;
+    let mut start = 0;
+
+    while start < areas.len() {
+        let side = free.w.min(free.h);
+        let mut end = start + 1;
+        let mut best = worst_ratio(&areas[start..end], side);
+
+        // Grow the row while doing so improves the worst aspect ratio
+        while end < areas.len() {
+            let candidate = worst_ratio(&areas[start..end + 1], side);
+            if candidate > best {
+                break;
+            }
+            best = candidate;
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let row_area: f64 = row.iter().sum();
+
+        if free.w >= free.h {
+            // Lay the row out as a column along the left edge
+            let column_width = row_area / free.h;
+            let mut y = free.y;
+            for area in row {
+                let h = area / column_width;
+                rects.push(Rect {
+                    x: free.x,
+                    y,
+                    w: column_width,
+                    h,
+                });
+                y += h;
+            }
+            free.x += column_width;
+            free.w -= column_width;
+        } else {
+            // Lay the row out along the top edge
+            let row_height = row_area / free.w;
+            let mut x = free.x;
+            for area in row {
+                let w = area / row_height;
+                rects.push(Rect {
+                    x,
+                    y: free.y,
+                    w,
+                    h: row_height,
+                });
+                x += w;
+            }
+            free.y += row_height;
+            free.h -= row_height;
+        }
+
+        start = end;
+    }
+
+    rects
+}
+
+fn worst_ratio(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let max = row.iter().cloned().fold(f64::MIN, f64::max);
+    let min = row.iter().cloned().fold(f64::MAX, f64::min);
+    let side_sq = side * side;
+    let sum_sq = sum * sum;
+
+    (side_sq * max / sum_sq).max(sum_sq / (side_sq * min))
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,12 @@
+use std::borrow::Cow;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+// Names in NFC form. macOS filesystems hand out decomposed (NFD) names while
+// Windows and Linux usually keep them composed, so the same "café.jpg" can
+// differ byte for byte between two copies.
+pub(crate) fn nfc(name: &str) -> Cow<'_, str> {
+    match is_nfc_quick(name.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(name),
+        _ => Cow::Owned(name.nfc().collect()),
+    }
+}
@@ -0,0 +1,96 @@
+use crate::scan_validation::is_elevated;
+use crate::DiskSenseError;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::command;
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileUsage {
+    // Account name, taken from the folder name
+    user: String,
+    path: String,
+    size: u64,
+    file_count: u64,
+    // Folders that couldn't be read, so `size` is a lower bound when non-zero
+    unreadable_dirs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfilesReport {
+    profiles: Vec<ProfileUsage>,
+    // Without elevation other users' profiles are mostly unreadable
+    is_elevated: bool,
+}
+
+// Disk usage of every user profile on the machine, largest first
+#[command]
+pub async fn get_user_profiles_usage() -> Result<ProfilesReport, DiskSenseError> {
+    let mut profiles: Vec<ProfileUsage> = profile_dirs()
+        .into_par_iter()
+        .map(|(user, path)| measure(user, path))
+        .collect();
+    profiles.sort_by_key(|item| std::cmp::Reverse(item.size));
+
+    Ok(ProfilesReport {
+        profiles,
+        is_elevated: is_elevated(),
+    })
+}
+
+fn profile_dirs() -> Vec<(String, PathBuf)> {
+    let (parents, extra): (Vec<PathBuf>, Vec<PathBuf>) = if cfg!(target_os = "windows") {
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        (vec![PathBuf::from(format!("{}\\Users", drive))], Vec::new())
+    } else if cfg!(target_os = "macos") {
+        (vec![PathBuf::from("/Users")], Vec::new())
+    } else {
+        (vec![PathBuf::from("/home")], vec![PathBuf::from("/root")])
+    };
+
+    let mut dirs: Vec<(String, PathBuf)> = parents
+        .iter()
+        .filter_map(|parent| std::fs::read_dir(parent).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        // "All Users" and "Default User" are junctions to other folders
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            )
+        })
+        .collect();
+    dirs.extend(
+        extra
+            .into_iter()
+            .filter(|path| path.is_dir())
+            .map(|path| ("root".to_string(), path)),
+    );
+    dirs
+}
+
+// Walk the profile without following links, counting what can't be read
+fn measure(user: String, path: PathBuf) -> ProfileUsage {
+    let (mut size, mut file_count, mut unreadable_dirs) = (0, 0, 0);
+    for entry in WalkDir::new(&path) {
+        match entry {
+            Ok(entry) if entry.file_type().is_file() => {
+                size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                file_count += 1;
+            }
+            Ok(_) => {}
+            Err(_) => unreadable_dirs += 1,
+        }
+    }
+
+    ProfileUsage {
+        user,
+        path: path.to_string_lossy().to_string(),
+        size,
+        file_count,
+        unreadable_dirs,
+    }
+}
@@ -0,0 +1,97 @@
+use crate::delta::node_id;
+use crate::DiskItem;
+use fs_extra::dir::get_size;
+use std::path::Path;
+
+// Name of the synthetic node that stands in for a folder's hidden entries
+const HIDDEN_NAME: &str = "(hidden)";
+
+// Whether the platform's file manager hides an entry by default. Dotfiles are
+// hidden everywhere; macOS adds the hidden flag and Windows the hidden attribute.
+// Only the entry's own name and flags count, so everything inside a hidden
+// folder goes with it but a hidden scan root is still listed.
+pub(crate) fn is_hidden(name: &str, path: &Path) -> bool {
+    if name.starts_with('.') {
+        return true;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::os::macos::fs::MetadataExt;
+
+        std::fs::symlink_metadata(path).is_ok_and(|m| m.st_flags() & libc::UF_HIDDEN != 0)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::MetadataExt;
+        use winapi::um::winnt::FILE_ATTRIBUTE_HIDDEN;
+
+        std::fs::symlink_metadata(path)
+            .is_ok_and(|m| m.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+// Hidden entries left out of one folder's listing. They still count towards the
+// folder's size, so skipping them never makes a total come out short.
+#[derive(Default)]
+pub(crate) struct HiddenTally {
+    count: usize,
+    bytes: u64,
+}
+
+impl HiddenTally {
+    // Count a hidden file or folder, measuring a folder all the way down.
+    // Returns its size.
+    pub(crate) fn add(&mut self, path: &Path) -> u64 {
+        let size = get_size(path).unwrap_or(0);
+        self.count += 1;
+        self.bytes += size;
+        size
+    }
+
+    // Node collecting the tallied entries, none when nothing was hidden
+    pub(crate) fn into_node(self, dir_path: &Path) -> Option<DiskItem> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let path = hidden_path(dir_path);
+        Some(DiskItem {
+            name: format!("{} ({} items)", HIDDEN_NAME, self.count),
+            id: node_id(&path),
+            path,
+            size: self.bytes,
+            is_dir: false,
+            children: None,
+            game: None,
+            git_repo: false,
+            allocated_size: None,
+            sparse: false,
+            link_target: None,
+            hard_links: None,
+            in_archive: false,
+            raw_path: None,
+            estimated: false,
+            hidden: true,
+            known_folder: None,
+            allocated_partial: false,
+        })
+    }
+}
+
+// Path of a folder's hidden node. No file system allows a NUL in a name, so it
+// can never resolve to a real file and any command handed it fails instead of
+// acting on the folder.
+fn hidden_path(dir_path: &Path) -> String {
+    dir_path
+        .join(format!("\0{}", HIDDEN_NAME))
+        .to_string_lossy()
+        .to_string()
+}
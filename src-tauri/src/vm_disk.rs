@@ -0,0 +1,228 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, sparse, DiskSenseError};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VmDiskInfo {
+    path: String,
+    format: String,
+    // Bytes the image file takes on the host
+    file_size: u64,
+    allocated_size: Option<u64>,
+    // Capacity of the virtual disk as seen by the guest
+    virtual_size: Option<u64>,
+    // Growable images can give back space freed inside the guest by compacting
+    dynamic: bool,
+    compactable: bool,
+}
+
+#[command]
+pub async fn analyze_vm_disk(path: String) -> Result<VmDiskInfo, DiskSenseError> {
+    let disk = Path::new(&path);
+    let metadata = std::fs::metadata(disk).map_err(|e| DiskSenseError::from_io(e, &path))?;
+    let extension = disk
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let (format, header) = match extension.as_str() {
+        "vhd" => ("vhd", read_vhd(disk)),
+        "vhdx" => ("vhdx", None),
+        "vmdk" => ("vmdk", read_vmdk(disk)),
+        "qcow2" => ("qcow2", read_qcow2(disk)),
+        _ => {
+            return Err(DiskSenseError::Unsupported(format!(
+                "Not a supported disk image: {}",
+                path
+            )))
+        }
+    };
+
+    // qemu-img understands every format, including VHDX whose metadata we don't parse
+    let (virtual_size, dynamic) = match header.or_else(|| qemu_img_info(disk)) {
+        Some(header) => (Some(header.virtual_size), header.dynamic),
+        // VHDX images are dynamic unless created as fixed; assume the common case
+        None => (None, format == "vhdx"),
+    };
+
+    Ok(VmDiskInfo {
+        format: format.to_string(),
+        file_size: metadata.len(),
+        allocated_size: sparse::allocation(disk, &metadata).0,
+        virtual_size,
+        dynamic,
+        // Only VHD/VHDX have a built-in compaction path (compact_vhd)
+        compactable: dynamic && cfg!(target_os = "windows") && format.starts_with("vhd"),
+        path,
+    })
+}
+
+// Shrink a dynamic VHD/VHDX with diskpart. The image must not be in use by a VM,
+// and the command needs administrator rights.
+#[command]
+pub async fn compact_vhd(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    path: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<String, DiskSenseError> {
+    read_only.ensure_writable()?;
+    // Compacting rewrites the image in place, so the front-end must explicitly confirm it
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Compaction must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    if !cfg!(target_os = "windows") {
+        return Err(DiskSenseError::Unsupported(
+            "Compacting VHD files is only available on Windows".to_string(),
+        ));
+    }
+
+    let lower = path.to_lowercase();
+    if !lower.ends_with(".vhd") && !lower.ends_with(".vhdx") {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Only VHD and VHDX images can be compacted: {}",
+            path
+        )));
+    }
+    if path.contains('"') {
+        return Err(DiskSenseError::InvalidInput(format!(
+            "Invalid path: {}",
+            path
+        )));
+    }
+
+    // An image attached to a running VM is held open exclusively
+    File::options()
+        .read(true)
+        .write(true)
+        .open(&path)
+        .map_err(|e| DiskSenseError::from_io(e, &path))?;
+
+    let file_size = || std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let before = file_size();
+    let result = compact(&path);
+    audit::record(
+        &app,
+        "compact_vhd",
+        action.as_deref(),
+        vec![path.clone()],
+        before.saturating_sub(file_size()),
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn compact(path: &str) -> Result<String, DiskSenseError> {
+    let script = std::env::temp_dir().join("disksense-compact-vhd.txt");
+    let contents = format!(
+        "select vdisk file=\"{}\"\nattach vdisk readonly\ncompact vdisk\ndetach vdisk\n",
+        path
+    );
+    std::fs::write(&script, contents).map_err(|e| DiskSenseError::from_io(e, &script))?;
+
+    let output = Command::new("diskpart")
+        .arg("/s")
+        .arg(&script)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "diskpart"));
+    let _ = std::fs::remove_file(&script);
+    let output = output?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "diskpart failed: {}",
+            stdout
+        )))
+    }
+}
+
+struct ImageHeader {
+    virtual_size: u64,
+    dynamic: bool,
+}
+
+fn read_at(path: &Path, offset: SeekFrom, len: usize) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    file.seek(offset).ok()?;
+    let mut buffer = vec![0u8; len];
+    file.read_exact(&mut buffer).ok()?;
+    Some(buffer)
+}
+
+fn be_u64(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes[..8].try_into().unwrap_or_default())
+}
+
+// VHD keeps a 512-byte "conectix" footer at the end of the file
+fn read_vhd(path: &Path) -> Option<ImageHeader> {
+    let footer = read_at(path, SeekFrom::End(-512), 512)?;
+    if &footer[..8] != b"conectix" {
+        return None;
+    }
+
+    let disk_type = u32::from_be_bytes(footer[60..64].try_into().ok()?);
+    Some(ImageHeader {
+        virtual_size: be_u64(&footer[48..56]),
+        // 2 = fixed, 3 = dynamic, 4 = differencing
+        dynamic: disk_type != 2,
+    })
+}
+
+// Sparse (hosted) VMDK header: "KDMV" magic, capacity in 512-byte sectors.
+// Descriptor-only VMDKs (split/flat extents) fall through to qemu-img.
+fn read_vmdk(path: &Path) -> Option<ImageHeader> {
+    let header = read_at(path, SeekFrom::Start(0), 20)?;
+    if &header[..4] != b"KDMV" {
+        return None;
+    }
+
+    let sectors = u64::from_le_bytes(header[12..20].try_into().ok()?);
+    Some(ImageHeader {
+        virtual_size: sectors * 512,
+        dynamic: true,
+    })
+}
+
+fn read_qcow2(path: &Path) -> Option<ImageHeader> {
+    let header = read_at(path, SeekFrom::Start(0), 32)?;
+    if &header[..4] != b"QFI\xfb" {
+        return None;
+    }
+
+    Some(ImageHeader {
+        virtual_size: be_u64(&header[24..32]),
+        dynamic: true,
+    })
+}
+
+fn qemu_img_info(path: &Path) -> Option<ImageHeader> {
+    let output = Command::new("qemu-img")
+        .args(["info", "--output=json"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let virtual_size = info.get("virtual-size")?.as_u64()?;
+    let actual_size = info.get("actual-size").and_then(|s| s.as_u64());
+
+    Some(ImageHeader {
+        virtual_size,
+        dynamic: actual_size.map_or(true, |actual| actual < virtual_size),
+    })
+}
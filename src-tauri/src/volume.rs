@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+
+// Label, serial/UUID and encryption state of a volume, where the platform exposes them
+#[derive(Default)]
+pub(crate) struct VolumeDetails {
+    pub(crate) label: Option<String>,
+    pub(crate) serial: Option<String>,
+    pub(crate) encryption_status: EncryptionStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EncryptionStatus {
+    Locked,
+    Unlocked,
+    Unencrypted,
+    // Querying needs elevation, or the platform tool is missing
+    #[default]
+    Unknown,
+}
+
+pub(crate) fn volume_details(device: &str, mount_point: &Path) -> VolumeDetails {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = device;
+        windows_details(mount_point)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let _ = mount_point;
+        linux_details(device)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = device;
+        macos_details(mount_point)
+    }
+
+    // Mobile platforms expose no label, serial or encryption state
+    #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+    {
+        let _ = (device, mount_point);
+        VolumeDetails::default()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn windows_details(mount_point: &Path) -> VolumeDetails {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetVolumeInformationW;
+
+    let root: Vec<u16> = mount_point
+        .as_os_str()
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+    let mut label = [0u16; 261];
+    let mut serial = 0u32;
+
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            label.as_mut_ptr(),
+            label.len() as u32,
+            &mut serial,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ok == 0 {
+        return VolumeDetails {
+            encryption_status: bitlocker_status(mount_point),
+            ..VolumeDetails::default()
+        };
+    }
+
+    let len = label.iter().position(|c| *c == 0).unwrap_or(label.len());
+    let label = String::from_utf16_lossy(&label[..len]);
+
+    VolumeDetails {
+        label: (!label.is_empty()).then_some(label),
+        // Formatted the way `vol` prints it, e.g. 1A2B-3C4D
+        serial: Some(format!("{:04X}-{:04X}", serial >> 16, serial & 0xFFFF)),
+        encryption_status: bitlocker_status(mount_point),
+    }
+}
+
+// manage-bde only answers for administrators; anyone else gets Unknown
+#[cfg(target_os = "windows")]
+fn bitlocker_status(mount_point: &Path) -> EncryptionStatus {
+    let volume = mount_point.to_string_lossy();
+    let output = match Command::new("manage-bde")
+        .args(["-status", volume.trim_end_matches('\\')])
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return EncryptionStatus::Unknown,
+    };
+
+    let mut status = EncryptionStatus::Unknown;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        match (key, value) {
+            ("Lock Status", "Locked") => return EncryptionStatus::Locked,
+            ("Lock Status", "Unlocked") => status = EncryptionStatus::Unlocked,
+            ("Conversion Status", "Fully Decrypted") => status = EncryptionStatus::Unencrypted,
+            _ => {}
+        }
+    }
+    status
+}
+
+#[cfg(target_os = "linux")]
+fn linux_details(device: &str) -> VolumeDetails {
+    // udev keeps by-label/by-uuid symlinks to every block device
+    let device_path = dunce::canonicalize(device).unwrap_or_else(|_| PathBuf::from(device));
+    let find_link = |dir: &str| -> Option<String> {
+        std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(Result::ok)
+            .find(|entry| dunce::canonicalize(entry.path()).is_ok_and(|p| p == device_path))
+            .map(|entry| unescape_udev(&entry.file_name().to_string_lossy()))
+    };
+
+    let label = find_link("/dev/disk/by-label").or_else(|| blkid(device, "LABEL"));
+    let serial = find_link("/dev/disk/by-uuid").or_else(|| blkid(device, "UUID"));
+
+    VolumeDetails {
+        label,
+        serial,
+        encryption_status: luks_status(&device_path),
+    }
+}
+
+// A mounted LUKS volume is always unlocked; locked ones have no mount point to list
+#[cfg(target_os = "linux")]
+fn luks_status(device: &Path) -> EncryptionStatus {
+    let name = match device.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return EncryptionStatus::Unknown,
+    };
+
+    if is_dm_crypt(&name, 0) {
+        EncryptionStatus::Unlocked
+    } else {
+        EncryptionStatus::Unencrypted
+    }
+}
+
+// Follow device-mapper layers (e.g. LVM on LUKS) down to a dm-crypt target
+#[cfg(target_os = "linux")]
+fn is_dm_crypt(name: &str, depth: usize) -> bool {
+    let block = Path::new("/sys/class/block").join(name);
+    let is_crypt =
+        std::fs::read_to_string(block.join("dm/uuid")).is_ok_and(|uuid| uuid.starts_with("CRYPT-"));
+    if is_crypt || depth > 8 {
+        return is_crypt;
+    }
+
+    std::fs::read_dir(block.join("slaves"))
+        .map(|slaves| {
+            slaves
+                .filter_map(Result::ok)
+                .any(|slave| is_dm_crypt(&slave.file_name().to_string_lossy(), depth + 1))
+        })
+        .unwrap_or(false)
+}
+
+// udev escapes spaces and slashes in link names as \x20 and \x2f
+#[cfg(target_os = "linux")]
+fn unescape_udev(name: &str) -> String {
+    let mut result = String::new();
+    let mut rest = name;
+    while let Some(index) = rest.find("\\x") {
+        result.push_str(&rest[..index]);
+        let code = rest
+            .get(index + 2..index + 4)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match code {
+            Some(code) => {
+                result.push(code as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                result.push_str("\\x");
+                rest = &rest[index + 2..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(target_os = "linux")]
+fn blkid(device: &str, tag: &str) -> Option<String> {
+    let output = Command::new("blkid")
+        .args(["-o", "value", "-s", tag, device])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+#[cfg(target_os = "macos")]
+fn macos_details(mount_point: &Path) -> VolumeDetails {
+    let output = match Command::new("diskutil")
+        .arg("info")
+        .arg(mount_point)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return VolumeDetails::default(),
+    };
+
+    let mut identity = VolumeDetails::default();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim().to_string()),
+            None => continue,
+        };
+        match key {
+            "Volume Name" if !value.is_empty() => identity.label = Some(value),
+            "Volume UUID" => identity.serial = Some(value),
+            // e.g. "FileVault: Yes (Unlocked)" or "FileVault: No"
+            "FileVault" => {
+                identity.encryption_status = if value.starts_with("No") {
+                    EncryptionStatus::Unencrypted
+                } else if value.contains("Locked") {
+                    EncryptionStatus::Locked
+                } else {
+                    EncryptionStatus::Unlocked
+                }
+            }
+            _ => {}
+        }
+    }
+    identity
+}
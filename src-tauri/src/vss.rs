@@ -0,0 +1,221 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, parse_human_size, DiskSenseError};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+// Shadow copy storage of one volume. Restore points and "Previous Versions" live
+// here and never show up in a scan.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShadowStorage {
+    // Drive letter such as "C:"
+    volume: String,
+    // Volume holding the shadow copies, usually the same one
+    storage_volume: String,
+    used: u64,
+    allocated: u64,
+    // None when the limit is UNBOUNDED
+    maximum: Option<u64>,
+    shadow_copies: usize,
+}
+
+// Shadow storage of every volume that has it set up. Needs administrator rights.
+#[command]
+pub async fn get_shadow_storage() -> Result<Vec<ShadowStorage>, DiskSenseError> {
+    ensure_windows()?;
+
+    let mut storage = parse_shadow_storage(&run_vssadmin(&["list", "shadowstorage"])?);
+    // "list shadows" fails when there are none, which just means zero copies
+    let shadows = run_vssadmin(&["list", "shadows"]).unwrap_or_default();
+    for entry in &mut storage {
+        let original = format!("Original Volume: ({})", entry.volume);
+        entry.shadow_copies = shadows
+            .lines()
+            .filter(|line| line.trim().starts_with(&original))
+            .count();
+    }
+
+    Ok(storage)
+}
+
+// Used shadow storage of a single volume, None if unavailable
+#[cfg(target_os = "windows")]
+pub(crate) fn used_shadow_storage(volume: &str) -> Option<u64> {
+    let volume = volume.trim_end_matches('\\');
+    let output = run_vssadmin(&["list", "shadowstorage", &format!("/for={}", volume)]).ok()?;
+    parse_shadow_storage(&output)
+        .into_iter()
+        .next()
+        .map(|storage| storage.used)
+}
+
+// Cap the shadow storage of `volume` at `max_size` bytes. Windows deletes the
+// oldest restore points straight away if they no longer fit.
+#[command]
+pub async fn resize_shadow_storage(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    volume: String,
+    max_size: u64,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    // Shrinking can discard restore points, so the front-end must explicitly confirm it
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Shadow storage resize must be confirmed before it is run".to_string(),
+        ));
+    }
+    ensure_windows()?;
+    let volume = drive_letter(&volume)?;
+
+    // vssadmin rejects limits under 320 MB with its own message
+    let result = run_vssadmin(&[
+        "resize",
+        "shadowstorage",
+        &format!("/For={}", volume),
+        &format!("/On={}", volume),
+        &format!("/MaxSize={}B", max_size),
+    ])
+    .map(|_| ());
+    audit::record(
+        &app,
+        "resize_shadow_storage",
+        action.as_deref(),
+        vec![volume],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+// Delete the oldest shadow copy (restore point) of `volume`
+#[command]
+pub async fn delete_oldest_shadow_copy(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    volume: String,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Restore point deletion must be confirmed before it is run".to_string(),
+        ));
+    }
+    ensure_windows()?;
+    let volume = drive_letter(&volume)?;
+
+    let result = run_vssadmin(&[
+        "delete",
+        "shadows",
+        &format!("/For={}", volume),
+        "/Oldest",
+        "/Quiet",
+    ])
+    .map(|_| ());
+    audit::record(
+        &app,
+        "delete_oldest_shadow_copy",
+        action.as_deref(),
+        vec![volume],
+        0,
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn ensure_windows() -> Result<(), DiskSenseError> {
+    if cfg!(target_os = "windows") {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Unsupported(
+            "Shadow copies are only available on Windows".to_string(),
+        ))
+    }
+}
+
+// Accepts "C", "C:" or "C:\"
+fn drive_letter(volume: &str) -> Result<String, DiskSenseError> {
+    let letter = volume.trim_end_matches(['\\', ':']);
+    if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_alphabetic()) {
+        Ok(format!("{}:", letter.to_uppercase()))
+    } else {
+        Err(DiskSenseError::InvalidInput(format!(
+            "Not a drive letter: {}",
+            volume
+        )))
+    }
+}
+
+fn run_vssadmin(args: &[&str]) -> Result<String, DiskSenseError> {
+    let output = Command::new("vssadmin")
+        .args(args)
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "vssadmin"))?;
+
+    // vssadmin writes its errors to stdout
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(DiskSenseError::Failed(format!(
+            "vssadmin {} failed: {}",
+            args.join(" "),
+            stdout.lines().last().unwrap_or("").trim()
+        )))
+    }
+}
+
+// Blocks look like:
+//    For volume: (C:)\\?\Volume{...}\
+//    Shadow Copy Storage volume: (C:)\\?\Volume{...}\
+//    Used Shadow Copy Storage space: 1.25 GB (1%)
+//    Allocated Shadow Copy Storage space: 1.6 GB (1%)
+//    Maximum Shadow Copy Storage space: UNBOUNDED (100%)
+fn parse_shadow_storage(output: &str) -> Vec<ShadowStorage> {
+    let mut storage: Vec<ShadowStorage> = Vec::new();
+    for line in output.lines().map(str::trim) {
+        if let Some(value) = line.strip_prefix("For volume:") {
+            storage.push(ShadowStorage {
+                volume: volume_name(value),
+                storage_volume: String::new(),
+                used: 0,
+                allocated: 0,
+                maximum: None,
+                shadow_copies: 0,
+            });
+            continue;
+        }
+
+        let entry = match storage.last_mut() {
+            Some(entry) => entry,
+            None => continue,
+        };
+        if let Some(value) = line.strip_prefix("Shadow Copy Storage volume:") {
+            entry.storage_volume = volume_name(value);
+        } else if let Some(value) = line.strip_prefix("Used Shadow Copy Storage space:") {
+            entry.used = parse_human_size(value, 1024.0);
+        } else if let Some(value) = line.strip_prefix("Allocated Shadow Copy Storage space:") {
+            entry.allocated = parse_human_size(value, 1024.0);
+        } else if let Some(value) = line.strip_prefix("Maximum Shadow Copy Storage space:") {
+            if !value.trim().starts_with("UNBOUNDED") {
+                entry.maximum = Some(parse_human_size(value, 1024.0));
+            }
+        }
+    }
+
+    storage
+}
+
+// "(C:)\\?\Volume{...}\" -> "C:"
+fn volume_name(value: &str) -> String {
+    let value = value.trim();
+    value
+        .strip_prefix('(')
+        .and_then(|rest| rest.split(')').next())
+        .unwrap_or(value)
+        .to_string()
+}
@@ -0,0 +1,242 @@
+use crate::deletion::{DeleteCancel, DeletionReport};
+use crate::read_only::ReadOnlyMode;
+use crate::unicode_names::nfc;
+use crate::{audit, delete_and_report, os_path, DiskSenseError, ProtectedPaths, ScanStore};
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{command, AppHandle, Emitter, State};
+use walkdir::WalkDir;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchedFile {
+    path: String,
+    raw_path: Option<String>,
+    size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchProgress {
+    done: usize,
+    total: usize,
+    current_path: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MatchCleanup {
+    files: Vec<MatchedFile>,
+    total_size: u64,
+    // None for a dry run
+    deleted: Option<DeletionReport>,
+}
+
+// Find every file under `root` matching `glob` ("*.tmp", "Thumbs.db", "**/cache/*.bin").
+// A pattern without a slash is matched on the file name, otherwise on the path
+// relative to `root`. With `dry_run` the matches are only listed; otherwise
+// each one is deleted, reporting "delete-matching-progress" per file.
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn delete_matching(
+    app: AppHandle,
+    cancel: State<'_, DeleteCancel>,
+    protected: State<'_, ProtectedPaths>,
+    store: State<'_, ScanStore>,
+    read_only: State<'_, ReadOnlyMode>,
+    root: String,
+    glob: String,
+    dry_run: bool,
+    action: Option<String>,
+) -> Result<MatchCleanup, DiskSenseError> {
+    let root_path = os_path::decode(&root);
+    if !root_path.is_dir() {
+        return Err(DiskSenseError::NotFound { path: root });
+    }
+    let matcher = compile(&glob)?;
+    let on_name = !glob.contains('/');
+
+    let files = find_matches(&root_path, &matcher, on_name);
+    let total_size = files.iter().map(|file| file.size).sum();
+    if dry_run {
+        return Ok(MatchCleanup {
+            files,
+            total_size,
+            deleted: None,
+        });
+    }
+    read_only.ensure_writable()?;
+
+    let cancelled = cancel.reset();
+    let mut report = DeletionReport::default();
+    for (done, file) in files.iter().enumerate() {
+        let _ = app.emit(
+            "delete-matching-progress",
+            MatchProgress {
+                done,
+                total: files.len(),
+                current_path: file.path.clone(),
+            },
+        );
+        let target = file.raw_path.as_deref().unwrap_or(&file.path);
+        match delete_and_report(&app, cancelled, &protected, &store, target, false, false) {
+            Ok(deleted) => report.merge(deleted),
+            Err(e) => report.fail(e),
+        }
+        if report.is_cancelled() {
+            break;
+        }
+    }
+
+    audit::record(
+        &app,
+        "delete_matching",
+        action.as_deref(),
+        files.iter().map(|file| file.path.clone()).collect(),
+        report.bytes_reclaimed(),
+        report.cancel_error(),
+    );
+    Ok(MatchCleanup {
+        files,
+        total_size,
+        deleted: Some(report),
+    })
+}
+
+fn compile(glob: &str) -> Result<GlobMatcher, DiskSenseError> {
+    GlobBuilder::new(&nfc(glob))
+        .case_insensitive(true)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| DiskSenseError::InvalidInput(format!("Invalid pattern {}: {}", glob, e)))
+}
+
+// Only files are matched; links are listed but never followed
+fn find_matches(root: &Path, matcher: &GlobMatcher, on_name: bool) -> Vec<MatchedFile> {
+    WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_type().is_dir())
+        .filter(|entry| {
+            let candidate = if on_name {
+                entry.file_name().to_string_lossy().into_owned()
+            } else {
+                entry
+                    .path()
+                    .strip_prefix(root)
+                    .unwrap_or(entry.path())
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            };
+            matcher.is_match(&*nfc(&candidate))
+        })
+        .map(|entry| MatchedFile {
+            path: entry.path().to_string_lossy().to_string(),
+            raw_path: os_path::encode(entry.path()),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // A fresh folder under the system temp dir, removed by the test itself
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        let dir = std::env::temp_dir().join(format!(
+            "disksense-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn matched(root: &Path, glob: &str) -> Vec<String> {
+        let matcher = compile(glob).unwrap();
+        let mut paths: Vec<String> = find_matches(root, &matcher, !glob.contains('/'))
+            .into_iter()
+            .map(|file| {
+                Path::new(&file.path)
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn name_patterns_match_at_any_depth_ignoring_case() {
+        let root = temp_dir("wildcard-names");
+        std::fs::create_dir_all(root.join("a").join("b")).unwrap();
+        std::fs::write(root.join("x.tmp"), b"1").unwrap();
+        std::fs::write(root.join("a").join("b").join("Y.TMP"), b"22").unwrap();
+        std::fs::write(root.join("a").join("keep.txt"), b"3").unwrap();
+
+        assert_eq!(matched(&root, "*.tmp"), vec!["a/b/Y.TMP", "x.tmp"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn path_patterns_are_relative_to_the_root() {
+        let root = temp_dir("wildcard-paths");
+        std::fs::create_dir_all(root.join("cache")).unwrap();
+        std::fs::create_dir_all(root.join("app").join("cache")).unwrap();
+        std::fs::write(root.join("cache").join("top.bin"), b"1").unwrap();
+        std::fs::write(root.join("app").join("cache").join("deep.bin"), b"1").unwrap();
+        std::fs::write(root.join("app").join("cache").join("deep.txt"), b"1").unwrap();
+
+        // A single * never crosses a separator
+        assert_eq!(matched(&root, "cache/*.bin"), vec!["cache/top.bin"]);
+        assert_eq!(
+            matched(&root, "**/cache/*.bin"),
+            vec!["app/cache/deep.bin", "cache/top.bin"]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn folders_are_never_matched_and_sizes_are_reported() {
+        let root = temp_dir("wildcard-dirs");
+        std::fs::create_dir_all(root.join("logs.old")).unwrap();
+        std::fs::write(root.join("logs.old").join("a.old"), [0u8; 7]).unwrap();
+
+        let matcher = compile("*.old").unwrap();
+        let files = find_matches(&root, &matcher, true);
+
+        assert_eq!(files.len(), 1);
+        assert!(files[0].path.ends_with("a.old"));
+        assert_eq!(files[0].size, 7);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_are_listed_but_not_followed() {
+        let outside = temp_dir("wildcard-outside");
+        std::fs::write(outside.join("inside.tmp"), b"1").unwrap();
+        let root = temp_dir("wildcard-links");
+        std::os::unix::fs::symlink(&outside, root.join("linked.tmp")).unwrap();
+
+        assert_eq!(matched(&root, "*.tmp"), vec!["linked.tmp"]);
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(matches!(
+            compile("[unclosed"),
+            Err(DiskSenseError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,212 @@
+use crate::read_only::ReadOnlyMode;
+use crate::{audit, DiskSenseError};
+use fs_extra::dir::get_size;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use tauri::{command, AppHandle, State};
+
+// Disk Cleanup handlers are registered as subkeys of this key
+const VOLUME_CACHES_KEY: &str =
+    r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Explorer\VolumeCaches";
+
+// StateFlags slot used for our selection; cleanmgr /sagerun:<slot> runs it
+const SAGE_SLOT: u32 = 4207;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleanupCategory {
+    // Registry key name, passed back to run_windows_cleanup
+    id: String,
+    // Size of the files the category removes, where they live in known folders.
+    // Other handlers (e.g. Windows Update Cleanup) only size themselves inside cleanmgr.
+    size: Option<u64>,
+}
+
+// The categories Disk Cleanup offers on this machine, largest known sizes first
+#[command]
+pub async fn get_windows_cleanup_categories() -> Result<Vec<CleanupCategory>, DiskSenseError> {
+    if !cfg!(target_os = "windows") {
+        return Err(DiskSenseError::Unsupported(
+            "Disk Cleanup categories are only available on Windows".to_string(),
+        ));
+    }
+
+    let output = Command::new("reg")
+        .args(["query", VOLUME_CACHES_KEY])
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "reg"))?;
+    if !output.status.success() {
+        return Err(DiskSenseError::Failed(format!(
+            "Failed to read Disk Cleanup categories: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let prefix = format!("{}\\", VOLUME_CACHES_KEY);
+    let mut categories: Vec<CleanupCategory> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(prefix.as_str()))
+        .map(|id| CleanupCategory {
+            id: id.to_string(),
+            size: category_size(id),
+        })
+        .collect();
+
+    categories.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.id.cmp(&b.id)));
+    Ok(categories)
+}
+
+// Run Disk Cleanup unattended on the chosen categories. Needs administrator
+// rights for the system categories, and can take a long time for
+// Windows Update Cleanup.
+#[command]
+pub async fn run_windows_cleanup(
+    app: AppHandle,
+    read_only: State<'_, ReadOnlyMode>,
+    categories: Vec<String>,
+    confirm: bool,
+    action: Option<String>,
+) -> Result<(), DiskSenseError> {
+    read_only.ensure_writable()?;
+    // cleanmgr deletes without a recycle bin, so require explicit confirmation
+    if !confirm {
+        return Err(DiskSenseError::InvalidInput(
+            "Disk Cleanup must be confirmed before it is run".to_string(),
+        ));
+    }
+
+    if !cfg!(target_os = "windows") {
+        return Err(DiskSenseError::Unsupported(
+            "Disk Cleanup is only available on Windows".to_string(),
+        ));
+    }
+
+    if categories.is_empty() {
+        return Err(DiskSenseError::InvalidInput(
+            "No cleanup categories selected".to_string(),
+        ));
+    }
+
+    let known = get_windows_cleanup_categories().await?;
+    if let Some(unknown) = categories
+        .iter()
+        .find(|id| !known.iter().any(|category| &category.id == *id))
+    {
+        return Err(DiskSenseError::NotFound {
+            path: unknown.clone(),
+        });
+    }
+
+    let flag = format!("StateFlags{:04}", SAGE_SLOT);
+    for category in &known {
+        // 2 selects the category for this slot, 0 leaves it out
+        let selected = if categories.contains(&category.id) {
+            "2"
+        } else {
+            "0"
+        };
+        set_state_flag(&category.id, &flag, selected)?;
+    }
+
+    // Freed space is what the selected categories' known folders lost
+    let selected_size = || -> u64 { categories.iter().filter_map(|id| category_size(id)).sum() };
+    let before = selected_size();
+    let result = sage_run();
+    audit::record(
+        &app,
+        "run_windows_cleanup",
+        action.as_deref(),
+        categories.clone(),
+        before.saturating_sub(selected_size()),
+        result.as_ref().err(),
+    );
+    result
+}
+
+fn sage_run() -> Result<(), DiskSenseError> {
+    let status = Command::new("cleanmgr")
+        .arg(format!("/sagerun:{}", SAGE_SLOT))
+        .status()
+        .map_err(|e| DiskSenseError::from_spawn(e, "cleanmgr"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::Failed(
+            "Disk Cleanup did not finish".to_string(),
+        ))
+    }
+}
+
+fn set_state_flag(id: &str, flag: &str, value: &str) -> Result<(), DiskSenseError> {
+    let output = Command::new("reg")
+        .args([
+            "add",
+            &format!("{}\\{}", VOLUME_CACHES_KEY, id),
+            "/v",
+            flag,
+            "/t",
+            "REG_DWORD",
+            "/d",
+            value,
+            "/f",
+        ])
+        .output()
+        .map_err(|e| DiskSenseError::from_spawn(e, "reg"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DiskSenseError::PermissionDenied {
+            path: id.to_string(),
+        })
+    }
+}
+
+// Sum the folders a category is known to clear
+fn category_size(id: &str) -> Option<u64> {
+    let windows = std::env::var_os("SystemRoot").map(PathBuf::from)?;
+    let local = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+
+    let folders: Vec<PathBuf> = match id {
+        "Delivery Optimization Files" => vec![windows.join(
+            r"ServiceProfiles\NetworkService\AppData\Local\Microsoft\Windows\DeliveryOptimization\Cache",
+        )],
+        "Temporary Files" => vec![std::env::temp_dir(), windows.join("Temp")],
+        "Windows Error Reporting Files" => {
+            let data = std::env::var_os("ProgramData").map(PathBuf::from)?;
+            vec![data.join(r"Microsoft\Windows\WER")]
+        }
+        "Update Cleanup" => vec![windows.join(r"SoftwareDistribution\Download")],
+        "Upgrade Discarded Files" => vec![PathBuf::from(r"C:\$Windows.~BT")],
+        "Previous Installations" => vec![PathBuf::from(r"C:\Windows.old")],
+        "Thumbnail Cache" => return thumbnail_cache_size(local?),
+        _ => return None,
+    };
+
+    Some(
+        folders
+            .iter()
+            .filter(|folder| folder.exists())
+            .map(|folder| get_size(folder).unwrap_or(0))
+            .sum(),
+    )
+}
+
+// Thumbnails live in thumbcache_*.db files next to other Explorer data
+fn thumbnail_cache_size(local: PathBuf) -> Option<u64> {
+    let entries = std::fs::read_dir(local.join(r"Microsoft\Windows\Explorer")).ok()?;
+    Some(
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .starts_with("thumbcache_")
+            })
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum(),
+    )
+}
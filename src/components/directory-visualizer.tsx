@@ -7,6 +7,7 @@ import {
   scanDirectory,
   formatBytes,
   ScanOptions,
+  errorMessage,
 } from "@/lib/disk-utils";
 import ScanProgress from "./scan-progress";
 
@@ -64,8 +65,7 @@ export default function DirectoryVisualizer({ path }: { path: string }) {
         setScanEndTime(Date.now());
       } catch (err) {
         setError(
-          "Failed to scan directory: " +
-            (err instanceof Error ? err.message : String(err))
+          "Failed to scan directory: " + errorMessage(err)
         );
         console.error(err);
       } finally {
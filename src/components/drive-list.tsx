@@ -1,6 +1,7 @@
 "use client";
 
 import { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { getDriveInfo, formatBytes, DriveInfo } from "@/lib/disk-utils";
 
 export default function DriveList({
@@ -28,6 +29,17 @@ export default function DriveList({
     };
 
     fetchDrives();
+
+    // Refresh the list when a drive is plugged in or removed, or a delete frees space
+    const unlisteners = Promise.all([
+      listen<DriveInfo>("drive-added", fetchDrives),
+      listen<string>("drive-removed", fetchDrives),
+      listen<string>("drive-space-changed", fetchDrives),
+    ]);
+
+    return () => {
+      unlisteners.then((fns) => fns.forEach((unlisten) => unlisten()));
+    };
   }, []);
 
   if (loading) {
@@ -75,6 +87,11 @@ export default function DriveList({
                 <div className="text-sm text-right">
                   {formatBytes(drive.total_space)} total
                 </div>
+                {reservedNotes(drive).map((note) => (
+                  <p key={note} className="text-xs text-gray-500">
+                    {note}
+                  </p>
+                ))}
               </div>
             </div>
           ))}
@@ -83,3 +100,21 @@ export default function DriveList({
     </div>
   );
 }
+
+// Why "used" is larger than what a scan of the drive adds up to
+function reservedNotes(drive: DriveInfo): string[] {
+  const { root_reserved, local_snapshots, shadow_storage } = drive.reserved;
+  const notes: string[] = [];
+  if (root_reserved) {
+    notes.push(`Includes ${formatBytes(root_reserved)} reserved for the system`);
+  }
+  if (shadow_storage) {
+    notes.push(`Includes ${formatBytes(shadow_storage)} of shadow copies`);
+  }
+  if (local_snapshots) {
+    notes.push(
+      `${local_snapshots} local snapshot${local_snapshots === 1 ? "" : "s"} held as purgeable space`
+    );
+  }
+  return notes;
+}
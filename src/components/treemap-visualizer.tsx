@@ -8,6 +8,7 @@ import {
   formatBytes,
   getColorForSize,
   ScanOptions,
+  errorMessage,
 } from "@/lib/disk-utils";
 import ScanProgress from "./scan-progress";
 import { invoke } from "@tauri-apps/api/core";
@@ -80,8 +81,7 @@ export default function TreemapVisualizer({ path }: { path: string }) {
         updateBreadcrumbs(pathToScan);
       } catch (err) {
         setError(
-          "Failed to scan directory: " +
-            (err instanceof Error ? err.message : String(err))
+          "Failed to scan directory: " + errorMessage(err)
         );
         console.error(err);
       } finally {
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
 
 export interface DiskItem {
   name: string;
@@ -6,6 +6,44 @@ export interface DiskItem {
   size: number;
   is_dir: boolean;
   children?: DiskItem[];
+  game?: string | null;
+  git_repo?: boolean;
+  id: string;
+  allocated_size?: number | null;
+  sparse?: boolean;
+  link_target?: string | null;
+  hard_links?: number | null;
+  in_archive?: boolean;
+  // Exact path when the name isn't valid Unicode; pass it to commands instead of path
+  raw_path?: string | null;
+  // Size is a quick estimate that a later "scan-delta" will make exact
+  estimated?: boolean;
+  // Stands in for the hidden entries of its folder; path resolves to nothing on disk
+  hidden?: boolean;
+  // Label for Desktop, Documents etc., e.g. "Documents (OneDrive)"
+  known_folder?: string | null;
+  // allocated_size leaves out contents not measured, e.g. folders past the depth limit
+  allocated_partial?: boolean;
+}
+
+// Path to hand back to commands for an item, exact even for unusual names
+export function commandPath(item: DiskItem): string {
+  return item.raw_path ?? item.path;
+}
+
+// Payload of the "scan-delta" event emitted when a rescan changes a stored tree
+export interface NodeDelta {
+  id: string;
+  path: string;
+  size: number;
+  estimated: boolean;
+  added: DiskItem[];
+  removed: string[];
+}
+
+export interface ScanDelta {
+  scan_id: string;
+  changes: NodeDelta[];
 }
 
 export interface DriveInfo {
@@ -14,11 +52,66 @@ export interface DriveInfo {
   total_space: number;
   available_space: number;
   used_space: number;
+  purgeable_space?: number | null;
+  file_system: string;
+  label: string | null;
+  serial: string | null;
+  encryption_status: "locked" | "unlocked" | "unencrypted" | "unknown";
+  reserved: ReservedSpace;
+}
+
+// Space counted as used that a scan can't see; null where not applicable
+export interface ReservedSpace {
+  root_reserved: number | null;
+  local_snapshots: number | null;
+  shadow_storage: number | null;
 }
 
 export interface ScanOptions {
   fast_mode: boolean;
   skip_hidden: boolean;
+  background_priority?: boolean;
+  max_threads?: number | null;
+  max_concurrent_dir_reads?: number | null;
+  timeout_seconds?: number | null;
+  stall_timeout_seconds?: number | null;
+  respect_ignore_files?: boolean;
+  group_below_percent?: number | null;
+  expand_archives_over?: number | null;
+  instant_preview?: boolean;
+  two_pass?: boolean;
+}
+
+// Error shape returned by every backend command
+export interface DiskSenseError {
+  kind:
+    | "NotFound"
+    | "PermissionDenied"
+    | "InUse"
+    | "Protected"
+    | "Cancelled"
+    | "ReadOnly"
+    | "Io"
+    | "InvalidInput"
+    | "Unsupported"
+    | "Failed";
+  message: string;
+  path: string | null;
+  code: number | null;
+}
+
+export function isDiskSenseError(error: unknown): error is DiskSenseError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    "kind" in error &&
+    "message" in error
+  );
+}
+
+export function errorMessage(error: unknown): string {
+  if (isDiskSenseError(error)) return error.message;
+  return error instanceof Error ? error.message : String(error);
 }
 
 export async function scanDirectory(
@@ -34,6 +127,185 @@ export async function scanDirectory(
   }
 }
 
+// Open a second window that scans path on load; returns its window label.
+// Scan events only reach the window that started the scan.
+export async function openScanWindow(path: string): Promise<string> {
+  return await invoke("open_scan_window", { path });
+}
+
+// Path a window opened by openScanWindow should scan first, if any
+export function initialScanPath(): string | null {
+  const value = (globalThis as { __DISKSENSE_INITIAL_SCAN__?: string })
+    .__DISKSENSE_INITIAL_SCAN__;
+  return value ?? null;
+}
+
+// A folder to scan, from Explorer/Finder or `disksense <path> --depth N`
+export interface ExternalScan {
+  path: string;
+  depth: number | null;
+}
+
+// Request made before the UI could hear the "external-open" event; later
+// requests arrive as that event
+export async function takeExternalOpen(): Promise<ExternalScan | null> {
+  return await invoke("take_external_open");
+}
+
+// Add or remove "Analyze with DiskSense" in the folder menu (Windows) or
+// "Open With" (Linux); macOS gets it from the app bundle
+export async function registerShellIntegration(): Promise<void> {
+  return await invoke("register_shell_integration");
+}
+
+export async function unregisterShellIntegration(): Promise<void> {
+  return await invoke("unregister_shell_integration");
+}
+
+// A Windows known folder and where it is redirected, e.g. into OneDrive
+export interface KnownFolder {
+  name: string;
+  path: string;
+  default_path: string;
+  redirected: boolean;
+  onedrive: boolean;
+}
+
+// Empty on platforms without folder redirection
+export async function getKnownFolders(): Promise<KnownFolder[]> {
+  return await invoke("get_known_folders");
+}
+
+export type AppDataScope = "local" | "local_low" | "roaming";
+
+export interface CacheFolder {
+  path: string;
+  size: number;
+  label: string;
+}
+
+export interface AppDataEntry {
+  name: string;
+  scope: AppDataScope;
+  path: string;
+  size: number;
+  caches: CacheFolder[];
+  cache_size: number;
+}
+
+export interface AppDataReport {
+  local_size: number;
+  local_low_size: number;
+  roaming_size: number;
+  apps: AppDataEntry[];
+}
+
+// AppData per scope and per app folder, with known caches (Teams, Spotify,
+// browsers, Electron apps) marked. Windows only.
+export async function analyzeAppData(): Promise<AppDataReport> {
+  return await invoke("analyze_appdata");
+}
+
+export interface ElectronCache {
+  path: string;
+  kind: string;
+  size: number;
+}
+
+export interface ElectronApp {
+  name: string;
+  path: string;
+  caches: ElectronCache[];
+  cache_size: number;
+  in_use: boolean;
+}
+
+export interface ElectronCleanReport {
+  report: DeletionReport;
+  skipped_in_use: string[];
+}
+
+// Electron apps and Chromium browser profiles found in the user's app data, with
+// the size of each cache (Cache, Code Cache, GPUCache, Service Worker caches)
+export async function findElectronCaches(): Promise<ElectronApp[]> {
+  return await invoke("find_electron_caches");
+}
+
+// Clear the caches of all detected apps, or only of the given data folders.
+// Running apps are skipped.
+export async function cleanElectronCaches(
+  paths?: string[],
+  action?: string
+): Promise<ElectronCleanReport> {
+  return await invoke("clean_electron_caches", { paths, action });
+}
+
+export interface StructureNode {
+  name: string;
+  path: string;
+  files: number;
+  dirs: number;
+  unreadable: number;
+  children: StructureNode[] | null;
+}
+
+// Item counts per folder without reading any file size; a quick first map of a
+// huge tree before a full scan. Folders deeper than depth are only counted.
+export async function scanStructure(path: string, depth?: number): Promise<StructureNode> {
+  return await invoke("scan_structure", { path, depth });
+}
+
+export interface Revalidation {
+  missing: string[];
+  resized: string[];
+}
+
+// Stat the visible nodes (and their listed children) when the window regains
+// focus; deletions and size changes made by other programs arrive as "scan-delta"
+export async function revalidateVisible(paths: string[]): Promise<Revalidation> {
+  return await invoke("revalidate_visible", { paths });
+}
+
+// Patch a tree in place of re-rendering it from a fresh scan result
+export function applyScanDelta(root: DiskItem, delta: ScanDelta): DiskItem {
+  const changes = new Map(delta.changes.map((change) => [change.id, change]));
+
+  const patch = (item: DiskItem): DiskItem => {
+    const change = changes.get(item.id);
+    let children = item.children?.map(patch);
+
+    if (change) {
+      const removed = new Set(change.removed);
+      children = [
+        ...(children ?? []).filter((child) => !removed.has(child.id)),
+        ...change.added,
+      ].sort((a, b) => b.size - a.size);
+    }
+
+    return { ...item, size: change ? change.size : item.size, children };
+  };
+
+  return patch(root);
+}
+
+export interface ItemProperties {
+  path: string;
+  name: string;
+  size: number;
+  allocated_size: number | null;
+  is_dir: boolean;
+  is_symlink: boolean;
+  link_target: string | null;
+  hard_links: number | null;
+  readonly: boolean;
+  modified: number | null;
+  created: number | null;
+}
+
+export async function getItemProperties(path: string): Promise<ItemProperties> {
+  return await invoke("get_item_properties", { path });
+}
+
 export async function getDriveInfo(): Promise<DriveInfo[]> {
   try {
     return await invoke("get_drive_info");
@@ -75,3 +347,1476 @@ export function getColorForSize(size: number, maxSize: number): string {
   const index = Math.min(Math.floor(ratio * colors.length), colors.length - 1);
   return colors[index];
 }
+
+export interface ExtensionColor {
+  extension: string;
+  category: string;
+  color: string;
+  size: number;
+  count: number;
+}
+
+// Stable per-extension colors for coloring treemap tiles by file type
+export async function getExtensionColors(
+  scanId: string
+): Promise<ExtensionColor[]> {
+  return await invoke("get_extension_colors", { scanId });
+}
+
+export interface ExtensionStats {
+  extension: string;
+  total_size: number;
+  file_count: number;
+  largest_file: string;
+  largest_size: number;
+  average_size: number;
+}
+
+export type ExtensionStatsColumn =
+  | "extension"
+  | "total_size"
+  | "file_count"
+  | "largest_size"
+  | "average_size";
+
+// "natural" (the default), "ordinal", or a locale tag such as "de" or "sv-SE"
+export type NameCollation = "natural" | "ordinal" | (string & {});
+
+export async function getExtensionStats(
+  root: string,
+  sortBy: ExtensionStatsColumn = "total_size",
+  ascending?: boolean,
+  collation?: NameCollation
+): Promise<ExtensionStats[]> {
+  return await invoke("get_extension_stats", {
+    root,
+    sortBy,
+    ascending,
+    collation,
+  });
+}
+
+// Layout of stream_scan_tree's chunks this reader understands
+const TREE_STREAM_VERSION = 2;
+
+// Fetch a stored scan tree as binary chunks over a channel; much cheaper than
+// receiving the whole tree as JSON for scans with hundreds of thousands of nodes
+export async function streamScanTree(scanId: string): Promise<DiskItem> {
+  const nodes: DiskItem[] = [];
+  const decoder = new TextDecoder();
+
+  const done = new Promise<void>((resolve, reject) => {
+    const onChunk = new Channel<ArrayBuffer>();
+    onChunk.onmessage = (chunk) => {
+      const view = new DataView(chunk);
+      const version = view.getUint8(0);
+      if (version !== TREE_STREAM_VERSION) {
+        reject(new Error(`Unsupported tree stream version ${version}`));
+        return;
+      }
+      const count = view.getUint32(1, true);
+      if (count === 0) {
+        resolve();
+        return;
+      }
+
+      let offset = 5;
+      const readString = (length: number) => {
+        const value = decoder.decode(new Uint8Array(chunk, offset, length));
+        offset += length;
+        return value;
+      };
+
+      for (let i = 0; i < count; i++) {
+        const parent = view.getUint32(offset, true);
+        const size = Number(view.getBigUint64(offset + 4, true));
+        const id = view
+          .getBigUint64(offset + 12, true)
+          .toString(16)
+          .padStart(16, "0");
+        const flags = view.getUint8(offset + 20);
+        offset += 21;
+
+        const nameLength = view.getUint16(offset, true);
+        offset += 2;
+        const name = readString(nameLength);
+        const pathLength = view.getUint32(offset, true);
+        offset += 4;
+        const path = readString(pathLength);
+        const gameLength = view.getUint16(offset, true);
+        offset += 2;
+        const game = gameLength > 0 ? readString(gameLength) : null;
+        const rawPathLength = view.getUint32(offset, true);
+        offset += 4;
+        const rawPath = rawPathLength > 0 ? readString(rawPathLength) : null;
+
+        const node: DiskItem = {
+          id,
+          name,
+          path,
+          size,
+          is_dir: (flags & 1) !== 0,
+          git_repo: (flags & 2) !== 0,
+          game,
+          children: (flags & 4) !== 0 ? [] : undefined,
+          estimated: (flags & 8) !== 0,
+          hidden: (flags & 16) !== 0,
+          in_archive: (flags & 32) !== 0,
+          raw_path: rawPath,
+        };
+        nodes.push(node);
+        if (parent !== 0xffffffff) {
+          nodes[parent].children?.push(node);
+        }
+      }
+    };
+
+    invoke("stream_scan_tree", { scanId, onChunk }).catch((error) => {
+      console.error("Error streaming scan tree:", error);
+      reject(error);
+    });
+  });
+
+  await done;
+  return nodes[0];
+}
+
+export interface ScanTargetValidation {
+  path: string;
+  exists: boolean;
+  is_dir: boolean;
+  readable: boolean;
+  file_system: string | null;
+  is_network: boolean;
+  is_removable: boolean;
+  estimated_entries: number;
+  estimate_is_exact: boolean;
+  estimated_seconds: number;
+  denied_paths: string[];
+  is_elevated: boolean;
+  elevation_would_help: boolean;
+}
+
+// Check a path before scanning it, so the UI can warn about slow or partial scans
+export async function validateScanTarget(
+  path: string
+): Promise<ScanTargetValidation> {
+  return await invoke("validate_scan_target", { path });
+}
+
+// Re-read the drive list now; "drive-added" / "drive-removed" events fire for any changes
+export async function rescanDrives(): Promise<DriveInfo[]> {
+  return await invoke("rescan_drives");
+}
+
+// Unmount and eject a removable drive; fails with kind "InUse" while files are open on it
+export async function ejectDrive(mountPoint: string): Promise<void> {
+  await invoke("eject_drive", { mountPoint });
+}
+
+// Emit "drive-stats" events carrying the drive list every intervalMs until unsubscribed
+export async function subscribeDriveStats(intervalMs: number): Promise<void> {
+  await invoke("subscribe_drive_stats", { intervalMs });
+}
+
+export async function unsubscribeDriveStats(): Promise<void> {
+  await invoke("unsubscribe_drive_stats");
+}
+
+// Size limit on a directory; "budget-exceeded" fires with it when the limit is crossed
+export interface DirBudget {
+  path: string;
+  max_bytes: number;
+  last_size: number | null;
+  last_checked: number | null;
+  exceeded: boolean;
+}
+
+export async function setDirBudget(path: string, maxBytes: number): Promise<void> {
+  await invoke("set_dir_budget", { path, maxBytes });
+}
+
+export async function removeDirBudget(path: string): Promise<void> {
+  await invoke("remove_dir_budget", { path });
+}
+
+export async function listDirBudgets(): Promise<DirBudget[]> {
+  return await invoke("list_dir_budgets");
+}
+
+export async function checkDirBudgets(): Promise<DirBudget[]> {
+  return await invoke("check_dir_budgets");
+}
+
+// Reference footprint of a root, taken from its stored scan
+export interface BaselineInfo {
+  root: string;
+  set_at: number;
+  size: number;
+}
+
+// A node that differs from the baseline; null sizes mark added or removed nodes
+export interface Drift {
+  path: string;
+  name: string;
+  is_dir: boolean;
+  baseline_size: number | null;
+  current_size: number | null;
+  change: number;
+}
+
+export interface BaselineReport {
+  baseline: BaselineInfo;
+  current_size: number;
+  change: number;
+  drift: Drift[];
+}
+
+export async function setBaseline(root: string): Promise<BaselineInfo> {
+  return await invoke("set_baseline", { root });
+}
+
+// Drift of the stored scan of `root` from its baseline, largest change first
+export async function compareToBaseline(
+  root: string,
+  minChange?: number
+): Promise<BaselineReport> {
+  return await invoke("compare_to_baseline", { root, minChange });
+}
+
+export async function removeBaseline(root: string): Promise<void> {
+  await invoke("remove_baseline", { root });
+}
+
+export async function listBaselines(): Promise<BaselineInfo[]> {
+  return await invoke("list_baselines");
+}
+
+export type RuleCondition =
+  | { type: "older_than_days"; days: number }
+  | { type: "keep_newest"; count: number };
+
+export interface CleanupRule {
+  id?: string;
+  root: string;
+  pattern: string;
+  recursive?: boolean;
+  condition: RuleCondition;
+  staged?: boolean;
+}
+
+export interface RuleCandidate {
+  path: string;
+  size: number;
+  modified: number;
+}
+
+export interface RuleReport {
+  rule_id: string;
+  dry_run: boolean;
+  candidates: RuleCandidate[];
+  total_bytes: number;
+  removed: number;
+  errors: DiskSenseError[];
+}
+
+export async function addRule(rule: CleanupRule): Promise<string> {
+  return await invoke("add_rule", { rule });
+}
+
+export async function listRules(): Promise<CleanupRule[]> {
+  return await invoke("list_rules");
+}
+
+export async function removeRule(id: string): Promise<void> {
+  await invoke("remove_rule", { id });
+}
+
+// Always show the dry-run report to the user before running with dryRun = false
+export async function runRules(
+  dryRun: boolean,
+  ruleIds?: string[],
+  action?: string
+): Promise<RuleReport[]> {
+  return await invoke("run_rules", { dryRun, ruleIds, action });
+}
+
+export type HookEvent = "scan_completed" | "cleanup_completed";
+
+// External command run after an event; "{summary}" and "{root}" in args are
+// replaced with the summary JSON path and the scanned/cleaned path
+export interface Hook {
+  id?: string;
+  event: HookEvent;
+  program: string;
+  args?: string[];
+}
+
+export interface HookSettings {
+  enabled: boolean;
+  hooks: Hook[];
+}
+
+export async function getHookSettings(): Promise<HookSettings> {
+  return await invoke("get_hook_settings");
+}
+
+export async function setHooksEnabled(enabled: boolean): Promise<void> {
+  await invoke("set_hooks_enabled", { enabled });
+}
+
+export async function addHook(hook: Hook): Promise<string> {
+  return await invoke("add_hook", { hook });
+}
+
+export async function removeHook(id: string): Promise<void> {
+  await invoke("remove_hook", { id });
+}
+
+// Write settings, protected paths, budgets, cleanup rules, hooks and saved
+// searches to a JSON file; `frontEnd` holds UI settings to carry along
+export async function exportConfig(file: string, frontEnd?: unknown): Promise<void> {
+  await invoke("export_config", { file, frontEnd });
+}
+
+export interface ConfigImport {
+  imported: string[];
+  front_end: unknown | null;
+}
+
+// Replace the sections present in the file; imported hooks arrive switched off
+export async function importConfig(file: string): Promise<ConfigImport> {
+  return await invoke("import_config", { file });
+}
+
+// Write selected items to a file: a plain path list ("text") or a JSON manifest
+// with size, mtime and optional SHA-256 ("json"). Resolves to the number written.
+export async function exportSelection(
+  paths: string[],
+  format: "text" | "json",
+  file: string,
+  includeHash: boolean = false
+): Promise<number> {
+  return await invoke("export_selection", { paths, format, file, includeHash });
+}
+
+export interface ManifestVerification {
+  verified: number;
+  missing: string[];
+  changed: string[];
+  corrupted: string[];
+  added: string[];
+  unreadable: string[];
+}
+
+// Hash every file under root into a manifest; resolves to the number of files
+export async function createManifest(root: string, file: string): Promise<number> {
+  return await invoke("create_manifest", { root, file });
+}
+
+export async function verifyManifest(
+  root: string,
+  file: string
+): Promise<ManifestVerification> {
+  return await invoke("verify_manifest", { root, file });
+}
+
+export interface ArchiveEntry {
+  path: string;
+  size: number;
+  compressed_size: number | null;
+  is_dir: boolean;
+}
+
+export interface ArchiveListing {
+  path: string;
+  format: string;
+  archive_size: number;
+  uncompressed_size: number;
+  entries: ArchiveEntry[];
+}
+
+// List a zip/tar/tar.gz/7z archive's contents without extracting it
+export async function inspectArchive(path: string): Promise<ArchiveListing> {
+  return await invoke("inspect_archive", { path });
+}
+
+// List an archive of a stored scan into the tree; scans only expand zip and 7z,
+// so tar and tar.gz contents show up after this. The tree is patched by "scan-delta".
+export async function expandArchive(path: string): Promise<DiskItem[]> {
+  return await invoke("expand_archive", { path });
+}
+
+export interface VmDiskInfo {
+  path: string;
+  format: "vhd" | "vhdx" | "vmdk" | "qcow2";
+  file_size: number;
+  allocated_size: number | null;
+  virtual_size: number | null;
+  dynamic: boolean;
+  compactable: boolean;
+}
+
+export async function analyzeVmDisk(path: string): Promise<VmDiskInfo> {
+  return await invoke("analyze_vm_disk", { path });
+}
+
+// Windows only; the image must not be attached to a running VM
+export async function compactVhd(path: string, confirm: boolean, action?: string): Promise<string> {
+  return await invoke("compact_vhd", { path, confirm, action });
+}
+
+export interface MediaFile {
+  path: string;
+  size: number;
+  container: string;
+  codec: string | null;
+  width: number | null;
+  height: number | null;
+  bitrate: number | null;
+  duration_seconds: number | null;
+  estimated_savings: number;
+}
+
+export interface MediaReport {
+  target_codec: "hevc" | "av1";
+  total_size: number;
+  estimated_savings: number;
+  files: MediaFile[];
+}
+
+// Videos under root, ranked by space a re-encode to targetCodec would likely save
+export async function analyzeMedia(
+  root: string,
+  targetCodec: "hevc" | "av1" = "hevc"
+): Promise<MediaReport> {
+  return await invoke("analyze_media", { root, targetCodec });
+}
+
+export interface PhotoPair {
+  raw: string;
+  jpeg: string;
+  raw_size: number;
+  jpeg_size: number;
+}
+
+export interface PhotoBurst {
+  files: string[];
+  shots: number;
+  total_size: number;
+  reclaimable: number;
+}
+
+export interface PhotoReport {
+  pairs: PhotoPair[];
+  bursts: PhotoBurst[];
+  pair_reclaimable_keep_raw: number;
+  pair_reclaimable_keep_jpeg: number;
+  burst_reclaimable: number;
+}
+
+// RAW+JPEG pairs and burst sequences under root, with space freed by keeping one of each
+export async function analyzePhotos(
+  root: string,
+  burstGapSeconds?: number
+): Promise<PhotoReport> {
+  return await invoke("analyze_photos", { root, burstGapSeconds });
+}
+
+export interface DuplicateFile {
+  path: string;
+  size: number;
+}
+
+export interface DuplicateGroup {
+  key: string;
+  files: DuplicateFile[];
+  reclaimable: number;
+}
+
+// Payload of the "duplicate-group" event, sent per group as content searches
+// confirm them; `roots` are the roots the search was started with
+export interface DuplicateFound {
+  roots: string[];
+  group: DuplicateGroup;
+}
+
+// "content" compares file bytes; "audio" matches tracks by artist/title/duration
+// tags; "name" matches name and size, ignoring case and Unicode normalization.
+// `include`/`exclude` globs without a slash match file names ("*.jpg"), others
+// the path below the root; excluded folders are skipped whole.
+export async function findDuplicates(
+  root: string,
+  mode: "content" | "audio" | "name" = "content",
+  minSize?: number,
+  include?: string[],
+  exclude?: string[]
+): Promise<DuplicateGroup[]> {
+  return await invoke("find_duplicates", { root, mode, minSize, include, exclude });
+}
+
+export interface RootTotals {
+  root: string;
+  files: number;
+  bytes: number;
+}
+
+// Content two roots have in common, counting one copy per group
+export interface RootOverlap {
+  first: string;
+  second: string;
+  shared_files: number;
+  shared_bytes: number;
+}
+
+export interface CrossRootDuplicates {
+  groups: DuplicateGroup[];
+  roots: RootTotals[];
+  overlaps: RootOverlap[];
+}
+
+// Compare the contents of several roots (e.g. a folder and its backup) in one pass
+export async function findDuplicatesAcross(
+  roots: string[],
+  minSize?: number,
+  include?: string[],
+  exclude?: string[]
+): Promise<CrossRootDuplicates> {
+  return await invoke("find_duplicates_across", { roots, minSize, include, exclude });
+}
+
+// Bytes under each node of a stored scan that also exist elsewhere, keyed by
+// node id, from the content-mode duplicate searches run so far
+export async function getDuplicateOverlay(
+  scanId: string
+): Promise<Record<string, number>> {
+  return await invoke("get_duplicate_overlay", { scanId });
+}
+
+export interface ReflinkReport {
+  files_deduped: number;
+  bytes_shared: number;
+  failures: DiskSenseError[];
+}
+
+// Make the copies share the first file's data blocks (btrfs/XFS, APFS, ReFS);
+// unlike hard links the files stay independent
+export async function dedupeReflink(paths: string[], action?: string): Promise<ReflinkReport> {
+  return await invoke("dedupe_reflink", { paths, action });
+}
+
+export interface FragmentedFile {
+  path: string;
+  size: number;
+  fragments: number;
+}
+
+export interface FragmentationReport {
+  rotational: boolean | null;
+  files: FragmentedFile[];
+}
+
+// Windows only: fragment counts for the largest files under path
+export async function analyzeFragmentation(
+  path: string,
+  limit?: number
+): Promise<FragmentationReport> {
+  return await invoke("analyze_fragmentation", { path, limit });
+}
+
+export interface ProcessDiskActivity {
+  pid: number;
+  name: string;
+  read_bytes: number;
+  written_bytes: number;
+  read_rate: number;
+  write_rate: number;
+}
+
+// Processes by current write rate; the first call starts sampling
+export async function getProcessDiskActivity(): Promise<ProcessDiskActivity[]> {
+  return await invoke("get_process_disk_activity");
+}
+
+export interface StorageClassUsage {
+  storage_class: string;
+  objects: number;
+  size: number;
+}
+
+export interface BucketScan {
+  root: DiskItem;
+  object_count: number;
+  storage_classes: StorageClassUsage[];
+}
+
+// Scan an S3-compatible bucket; leave endpoint empty for AWS
+export async function scanBucket(
+  endpoint: string,
+  bucket: string,
+  prefix?: string
+): Promise<BucketScan> {
+  return await invoke("scan_bucket", { endpoint, bucket, prefix });
+}
+
+export interface SftpCredentials {
+  username: string;
+  password?: string;
+  private_key?: string;
+  passphrase?: string;
+}
+
+export interface RemoteScan {
+  root: DiskItem;
+  file_count: number;
+  unreadable: string[];
+}
+
+// Scan a directory on an SFTP server; host may include a port
+export async function scanRemoteSftp(
+  host: string,
+  path: string,
+  credentials: SftpCredentials
+): Promise<RemoteScan> {
+  return await invoke("scan_remote_sftp", { host, path, credentials });
+}
+
+export interface MtpDevice {
+  name: string;
+  id: string;
+}
+
+// Phones and cameras connected over USB in MTP mode
+export async function listMtpDevices(): Promise<MtpDevice[]> {
+  return await invoke("list_mtp_devices");
+}
+
+export async function scanMtpDevice(id: string): Promise<RemoteScan> {
+  return await invoke("scan_mtp_device", { id });
+}
+
+export interface StorageVolume {
+  name: string;
+  path: string;
+  removable: boolean;
+  total_space?: number;
+  available_space?: number;
+}
+
+// Storage the app can read; on mobile this includes shared storage and SD cards
+export async function listStorageVolumes(): Promise<StorageVolume[]> {
+  return await invoke("list_storage_volumes");
+}
+
+// Serve Prometheus metrics (free space per mount, last scan totals) at address,
+// e.g. "127.0.0.1:9184"; it has no authentication, so keep it on loopback
+export async function startMetricsServer(address: string): Promise<void> {
+  return await invoke("start_metrics_server", { address });
+}
+
+export async function stopMetricsServer(): Promise<void> {
+  return await invoke("stop_metrics_server");
+}
+
+// Payload of the "delete-progress" event
+export interface DeleteProgress {
+  items_removed: number;
+  bytes_freed: number;
+  current_path: string;
+}
+
+export interface DeletionReport {
+  files_removed: number;
+  dirs_removed: number;
+  bytes_reclaimed: number;
+  failures: DiskSenseError[];
+  // Stopped by cancelDelete; the counts are what was removed before that
+  cancelled: boolean;
+}
+
+// Delete a path; the stored scan is patched and a "scan-delta" event emitted
+export async function deletePath(
+  path: string,
+  force?: boolean,
+  staged?: boolean,
+  action?: string
+): Promise<DeletionReport> {
+  return await invoke("delete_path", { path, force, staged, action });
+}
+
+export async function deletePaths(
+  paths: string[],
+  force?: boolean,
+  staged?: boolean,
+  action?: string
+): Promise<DeletionReport> {
+  return await invoke("delete_paths", { paths, force, staged, action });
+}
+
+// Stop a running delete; items already removed stay removed
+export async function cancelDelete(): Promise<void> {
+  return await invoke("cancel_delete");
+}
+
+export interface ChildrenPage {
+  total: number;
+  offset: number;
+  children: DiskItem[];
+}
+
+// One page of a scanned folder's children, without their subtrees
+export async function getChildrenPage(
+  path: string,
+  options: {
+    offset?: number;
+    limit?: number;
+    sortBy?: "size" | "name";
+    ascending?: boolean;
+    collation?: NameCollation;
+  } = {}
+): Promise<ChildrenPage> {
+  return await invoke("get_children_page", { path, ...options });
+}
+
+export type CleanupAction =
+  | { kind: "empty_trash" }
+  | { kind: "clean_package_cache"; manager: string }
+  | { kind: "delete_paths"; paths: string[]; staged: boolean }
+  | { kind: "purge_staged" }
+  | { kind: "vacuum_journal" }
+  | { kind: "review"; paths: string[] };
+
+export interface CleanupStep {
+  id: string;
+  category:
+    | "trash"
+    | "caches"
+    | "dev_artifacts"
+    | "old_downloads"
+    | "duplicates"
+    | "system_files";
+  title: string;
+  reclaimable: number;
+  safety: "safe" | "review" | "caution";
+  action: CleanupAction;
+}
+
+export interface CleanupStepResult {
+  step_id: string;
+  message: string;
+  report: DeletionReport | null;
+}
+
+// Prioritized cleanup plan for the wizard, safest and largest steps first
+export async function getCleanupPlan(): Promise<CleanupStep[]> {
+  return await invoke("get_cleanup_plan");
+}
+
+export async function runCleanupStep(
+  stepId: string,
+  confirm: boolean,
+  action?: string
+): Promise<CleanupStepResult> {
+  return await invoke("run_cleanup_step", { stepId, confirm, action });
+}
+
+export interface ItemAnnotation {
+  tags: string[];
+  note: string | null;
+  updated_at: number;
+}
+
+export interface TaggedItem extends ItemAnnotation {
+  path: string;
+  exists: boolean;
+}
+
+// Tags and notes persist across sessions, e.g. "keep" or "delete next cleanup"
+export async function tagItem(path: string, tag: string): Promise<void> {
+  return await invoke("tag_item", { path, tag });
+}
+
+export async function untagItem(path: string, tag: string): Promise<void> {
+  return await invoke("untag_item", { path, tag });
+}
+
+export async function setItemNote(
+  path: string,
+  note: string | null
+): Promise<void> {
+  return await invoke("set_item_note", { path, note });
+}
+
+export async function getItemAnnotation(
+  path: string
+): Promise<ItemAnnotation | null> {
+  return await invoke("get_item_annotation", { path });
+}
+
+export async function getItemsByTag(tag: string): Promise<TaggedItem[]> {
+  return await invoke("get_items_by_tag", { tag });
+}
+
+export async function listTags(): Promise<[string, number][]> {
+  return await invoke("list_tags");
+}
+
+// Filters over a stored scan; every field that is set must match
+export interface ScanQuery {
+  name?: string | null;
+  extensions?: string[];
+  min_size?: number | null;
+  max_size?: number | null;
+  is_dir?: boolean | null;
+  modified_before_days?: number | null;
+  accessed_before_days?: number | null;
+  limit?: number | null;
+}
+
+export interface SearchResult {
+  path: string;
+  name: string;
+  size: number;
+  is_dir: boolean;
+  raw_path: string | null;
+}
+
+export interface SavedSearch {
+  name: string;
+  scan_id: string;
+  query: ScanQuery;
+}
+
+export async function queryScan(
+  scanId: string,
+  query: ScanQuery
+): Promise<SearchResult[]> {
+  return await invoke("query_scan", { scanId, query });
+}
+
+// Saved searches ("smart views") run against the latest scan of their root
+export async function saveSearch(
+  name: string,
+  scanId: string,
+  query: ScanQuery
+): Promise<void> {
+  return await invoke("save_search", { name, scanId, query });
+}
+
+export async function listSearches(): Promise<SavedSearch[]> {
+  return await invoke("list_searches");
+}
+
+export async function removeSearch(name: string): Promise<void> {
+  return await invoke("remove_search", { name });
+}
+
+export async function runSavedSearch(name: string): Promise<SearchResult[]> {
+  return await invoke("run_saved_search", { name });
+}
+
+export interface SessionScan {
+  root: string;
+  depth: number;
+  options: ScanOptions;
+  expanded: string[];
+}
+
+export interface RestoredScan extends SessionScan {
+  tree: DiskItem;
+  from_snapshot: boolean;
+  snapshot_age_seconds: number | null;
+  // Folders relisted from the change history since the snapshot, when replayed
+  refreshed_dirs: number | null;
+}
+
+export interface RestoredSession {
+  scans: RestoredScan[];
+  active: string | null;
+  missing: string[];
+}
+
+// Report the expanded folders of a scan (and whether it's the active one) so
+// they can be restored next launch
+export async function setSessionView(
+  root: string,
+  expanded: string[],
+  active: boolean
+): Promise<void> {
+  return await invoke("set_session_view", { root, expanded, active });
+}
+
+export async function closeSessionScan(root: string): Promise<void> {
+  return await invoke("close_session_scan", { root });
+}
+
+// Reopen the previous session; snapshots older than maxAgeSeconds are rescanned
+export async function restoreSession(
+  maxAgeSeconds?: number
+): Promise<RestoredSession> {
+  return await invoke("restore_session", { maxAgeSeconds });
+}
+
+export type JobKind =
+  | { kind: "delete"; paths: string[]; staged: boolean }
+  | { kind: "move"; paths: string[]; destination: string }
+  | { kind: "compress"; paths: string[]; archive: string }
+  | { kind: "hash"; paths: string[] };
+
+// Also the payload of the "job-progress" event
+export interface Job {
+  id: number;
+  job: JobKind;
+  status: "queued" | "running" | "done" | "failed" | "cancelled";
+  progress: number;
+  current_path: string | null;
+  result: unknown;
+  error: DiskSenseError | null;
+  created_at: number;
+  action: string | null;
+}
+
+// Queue a long operation; jobs run one at a time in the background
+export async function enqueueJob(job: JobKind, action?: string): Promise<number> {
+  return await invoke("enqueue_job", { job, action });
+}
+
+export async function listJobs(): Promise<Job[]> {
+  return await invoke("list_jobs");
+}
+
+export async function cancelJob(id: number): Promise<void> {
+  return await invoke("cancel_job", { id });
+}
+
+export interface MatchedFile {
+  path: string;
+  raw_path: string | null;
+  size: number;
+}
+
+export interface MatchCleanup {
+  files: MatchedFile[];
+  total_size: number;
+  deleted: DeletionReport | null;
+}
+
+// Preview (dryRun) or delete every file under root matching a glob like "*.tmp".
+// Deleting reports "delete-matching-progress" and can be stopped with cancelDelete.
+export async function deleteMatching(
+  root: string,
+  glob: string,
+  dryRun: boolean,
+  action?: string
+): Promise<MatchCleanup> {
+  return await invoke("delete_matching", { root, glob, dryRun, action });
+}
+
+export interface ArchiveResult {
+  archived_to: string;
+  files: number;
+  bytes: number;
+  linked: boolean;
+}
+
+// Move an item to another drive after a hash-verified copy, optionally leaving a
+// link behind. Progress arrives as "archive-progress" events.
+export async function archiveItem(
+  path: string,
+  dest: string,
+  leaveLink?: boolean,
+  action?: string
+): Promise<ArchiveResult> {
+  return await invoke("archive_item", { path, dest, leaveLink, action });
+}
+
+export interface CleanupCategory {
+  id: string;
+  size: number | null;
+}
+
+// Disk Cleanup (cleanmgr) categories on Windows, e.g. "Delivery Optimization Files"
+export async function getWindowsCleanupCategories(): Promise<CleanupCategory[]> {
+  return await invoke("get_windows_cleanup_categories");
+}
+
+export async function runWindowsCleanup(
+  categories: string[],
+  confirm: boolean,
+  action?: string
+): Promise<void> {
+  return await invoke("run_windows_cleanup", { categories, confirm, action });
+}
+
+export interface ShadowStorage {
+  volume: string;
+  storage_volume: string;
+  used: number;
+  allocated: number;
+  maximum: number | null;
+  shadow_copies: number;
+}
+
+// Windows shadow copy (restore point) storage per volume; needs elevation
+export async function getShadowStorage(): Promise<ShadowStorage[]> {
+  return await invoke("get_shadow_storage");
+}
+
+export async function resizeShadowStorage(
+  volume: string,
+  maxSize: number,
+  confirm: boolean,
+  action?: string
+): Promise<void> {
+  return await invoke("resize_shadow_storage", { volume, maxSize, confirm, action });
+}
+
+export async function deleteOldestShadowCopy(
+  volume: string,
+  confirm: boolean,
+  action?: string
+): Promise<void> {
+  return await invoke("delete_oldest_shadow_copy", { volume, confirm, action });
+}
+
+export interface ComponentStoreReport {
+  apparent_size: number;
+  actual_size: number;
+  shared_with_windows: number;
+  backups_and_disabled_features: number;
+  cache_and_temporary_data: number;
+  reclaimable: number;
+  reclaimable_packages: number;
+  last_cleanup: string | null;
+  cleanup_recommended: boolean;
+}
+
+// WinSxS real vs apparent size via DISM; Windows only, needs elevation
+export async function analyzeComponentStore(): Promise<ComponentStoreReport> {
+  return await invoke("analyze_component_store");
+}
+
+export async function cleanComponentStore(
+  resetBase: boolean,
+  confirm: boolean,
+  action?: string
+): Promise<string> {
+  return await invoke("clean_component_store", { resetBase, confirm, action });
+}
+
+export interface SystemFile {
+  kind: "hibernation" | "pagefile" | "swapfile";
+  path: string;
+  size: number;
+}
+
+// hiberfil.sys, pagefile.sys and swap files, which scans usually can't read
+export async function getSystemFileSizes(): Promise<SystemFile[]> {
+  return await invoke("get_system_file_sizes");
+}
+
+// Windows only: removes hiberfil.sys and disables Fast Startup
+export async function disableHibernation(confirm: boolean, action?: string): Promise<void> {
+  return await invoke("disable_hibernation", { confirm, action });
+}
+
+// Fixed pagefile size in MB, or both null for Windows-managed. Applies after restart.
+export async function setPagefileSize(
+  initialMb: number | null,
+  maximumMb: number | null,
+  confirm: boolean,
+  action?: string
+): Promise<void> {
+  return await invoke("set_pagefile_size", { initialMb, maximumMb, confirm, action });
+}
+
+export interface ReadOnlySettings {
+  enabled: boolean;
+  // Forced on by DISKSENSE_READ_ONLY=1 and can't be turned off
+  locked: boolean;
+}
+
+// Audit mode: destructive commands fail with kind "ReadOnly" while enabled
+export async function getReadOnlyMode(): Promise<ReadOnlySettings> {
+  return await invoke("get_read_only_mode");
+}
+
+export async function setReadOnlyMode(enabled: boolean): Promise<ReadOnlySettings> {
+  return await invoke("set_read_only_mode", { enabled });
+}
+
+// One destructive operation; `action` is the name passed by the UI that started it
+export interface AuditEntry {
+  at: number;
+  command: string;
+  action: string | null;
+  targets: string[];
+  bytes: number;
+  ok: boolean;
+  error: string | null;
+}
+
+// Newest first; the log itself is append-only
+export async function getAuditLog(limit?: number): Promise<AuditEntry[]> {
+  return await invoke("get_audit_log", { limit });
+}
+
+export interface ProfileUsage {
+  user: string;
+  path: string;
+  size: number;
+  file_count: number;
+  // Non-zero means some folders were unreadable and size is a lower bound
+  unreadable_dirs: number;
+}
+
+export interface ProfilesReport {
+  profiles: ProfileUsage[];
+  is_elevated: boolean;
+}
+
+// Usage per user profile (C:\Users\*, /home/*); run elevated for complete numbers
+export async function getUserProfilesUsage(): Promise<ProfilesReport> {
+  return await invoke("get_user_profiles_usage");
+}
+
+export interface TreeHash {
+  algo: string;
+  hash: string;
+  files: number;
+  dirs: number;
+  bytes: number;
+}
+
+// Combined hash of a folder's paths and contents; equal hashes mean identical
+// copies. Progress arrives as "hash-tree-progress" events.
+export async function hashTree(
+  path: string,
+  algo?: "sha256" | "sha512"
+): Promise<TreeHash> {
+  return await invoke("hash_tree", { path, algo });
+}
+
+export type OverwritePolicy = "skip" | "overwrite" | "rename";
+
+export interface CopyReport {
+  destination: string;
+  files_copied: number;
+  files_skipped: number;
+  files_renamed: number;
+  bytes_copied: number;
+  failures: DiskSenseError[];
+}
+
+// Copy a file or folder into dest; progress arrives as "copy-progress" events
+export async function copyPath(
+  src: string,
+  dest: string,
+  overwritePolicy: OverwritePolicy,
+  action?: string
+): Promise<CopyReport> {
+  return await invoke("copy_path", { src, dest, overwritePolicy, action });
+}
+
+// Put files on the OS clipboard for pasting into Explorer, Finder or Files
+export async function clipboardCopyFiles(paths: string[]): Promise<void> {
+  return await invoke("clipboard_copy_files", { paths });
+}
+
+// Pasting moves the files; on macOS paste with Option-Command-V to move
+export async function clipboardCutFiles(paths: string[]): Promise<void> {
+  return await invoke("clipboard_cut_files", { paths });
+}
+
+// macOS only: preview a file in the Quick Look panel
+export async function quickLook(path: string): Promise<void> {
+  return await invoke("quick_look", { path });
+}
+
+// Payload of "scan-partial-sizes": running totals of the scan root's top-level
+// folders while they are still being scanned
+export interface PartialSizes {
+  root: string;
+  sizes: { path: string; size: number }[];
+}
+
+export interface ContainerStorageEntry {
+  kind: string;
+  total_count: number;
+  active_count: number;
+  size: number;
+  reclaimable: number;
+}
+
+export interface ContainerStorageReport {
+  engine: "docker" | "podman";
+  data_root: string | null;
+  data_root_size: number | null;
+  entries: ContainerStorageEntry[];
+}
+
+export async function analyzeDocker(): Promise<ContainerStorageReport> {
+  return await invoke("analyze_docker");
+}
+
+// "build-cache" is only pruned with docker
+export async function pruneDocker(
+  engine: "docker" | "podman",
+  target: "images" | "containers" | "volumes" | "build-cache",
+  confirm: boolean,
+  action?: string
+): Promise<string> {
+  return await invoke("prune_docker", { engine, target, confirm, action });
+}
+
+export interface GameInfo {
+  name: string;
+  launcher: string;
+  install_path: string;
+  size: number;
+}
+
+export async function getGameSizes(): Promise<GameInfo[]> {
+  return await invoke("get_game_sizes");
+}
+
+export interface PackFileInfo {
+  path: string;
+  size: number;
+}
+
+export interface GitRepoInfo {
+  path: string;
+  total_size: number;
+  git_dir_size: number;
+  working_tree_size: number;
+  large_packs: PackFileInfo[];
+  gc_suggested: boolean;
+}
+
+// Payload of "git-gc-progress"
+export interface GitGcProgress {
+  path: string;
+  message: string;
+}
+
+export interface GitGcResult {
+  size_before: number;
+  size_after: number;
+  reclaimed: number;
+}
+
+export async function getGitRepoInfo(path: string): Promise<GitRepoInfo> {
+  return await invoke("get_git_repo_info", { path });
+}
+
+export async function gitGc(path: string, action?: string): Promise<GitGcResult> {
+  return await invoke("git_gc", { path, action });
+}
+
+export interface MailStore {
+  client: string;
+  profile: string;
+  mailbox: string;
+  path: string;
+  size: number;
+  last_modified: number | null;
+}
+
+export async function getMailStores(): Promise<MailStore[]> {
+  return await invoke("get_mail_stores");
+}
+
+export interface PackageCacheInfo {
+  manager: string;
+  paths: string[];
+  size: number;
+  clean_command: string;
+}
+
+export async function getPackageCacheSizes(): Promise<PackageCacheInfo[]> {
+  return await invoke("get_package_cache_sizes");
+}
+
+export interface LogFileInfo {
+  path: string;
+  size: number;
+  last_modified: number | null;
+}
+
+export interface CleanupSuggestion {
+  description: string;
+  command: string;
+  reclaimable: number;
+}
+
+export interface LinuxLogReport {
+  var_log_size: number;
+  largest_logs: LogFileInfo[];
+  journal_size: number | null;
+  core_pattern: string | null;
+  core_dumps: LogFileInfo[];
+  core_dumps_size: number;
+  suggestions: CleanupSuggestion[];
+}
+
+// Linux only
+export async function analyzeLinuxLogs(): Promise<LinuxLogReport> {
+  return await invoke("analyze_linux_logs");
+}
+
+export interface SnapRevision {
+  name: string;
+  revision: string;
+  size: number;
+  current: boolean;
+}
+
+export interface FlatpakRef {
+  id: string;
+  kind: string;
+  installation: string;
+  size: number;
+}
+
+export interface SandboxedPackageReport {
+  snap_revisions: SnapRevision[];
+  snap_reclaimable: number;
+  flatpak_refs: FlatpakRef[];
+  flatpak_app_data: FlatpakRef[];
+}
+
+// Linux only
+export async function getSandboxedPackageSizes(): Promise<SandboxedPackageReport> {
+  return await invoke("get_sandboxed_package_sizes");
+}
+
+export async function removeSnapRevision(
+  name: string,
+  revision: string,
+  confirm: boolean,
+  action?: string
+): Promise<string> {
+  return await invoke("remove_snap_revision", { name, revision, confirm, action });
+}
+
+export async function removeUnusedFlatpaks(confirm: boolean, action?: string): Promise<string> {
+  return await invoke("remove_unused_flatpaks", { confirm, action });
+}
+
+export interface LocalSnapshot {
+  name: string;
+  date: string;
+  size: number | null;
+}
+
+// macOS only: Time Machine local snapshots on a volume (the startup disk by default)
+export async function getLocalSnapshots(mountPoint?: string): Promise<LocalSnapshot[]> {
+  return await invoke("get_local_snapshots", { mountPoint });
+}
+
+export async function deleteLocalSnapshot(
+  date: string,
+  confirm: boolean,
+  action?: string
+): Promise<void> {
+  return await invoke("delete_local_snapshot", { date, confirm, action });
+}
+
+// Ask the system to thin snapshots until `bytes` are free
+export async function thinLocalSnapshots(
+  bytes: number,
+  confirm: boolean,
+  mountPoint?: string,
+  action?: string
+): Promise<void> {
+  return await invoke("thin_local_snapshots", { mountPoint, bytes, confirm, action });
+}
+
+// Write a self-contained HTML report of a stored scan
+export async function exportReportHtml(scanId: string, path: string): Promise<void> {
+  return await invoke("export_report_html", { scanId, path });
+}
+
+// Render the treemap of the node at `nodePath` (its path, not its id) to an SVG
+// or PNG file at `path`; `size` is [width, height]
+export async function exportTreemapImage(
+  nodePath: string,
+  format: "svg" | "png",
+  path: string,
+  size?: [number, number]
+): Promise<void> {
+  return await invoke("export_treemap_image", { nodePath, format, size, path });
+}
+
+export interface BenchmarkResult {
+  mount_point: string;
+  sequential_mb_per_sec: number;
+  random_iops: number;
+  random_mb_per_sec: number;
+  bytes_read: number;
+  duration_ms: number;
+  sample_files: number;
+  recommended_threads: number;
+}
+
+// Later scans of the drive use recommended_threads unless max_threads is set
+export async function benchmarkDrive(mount: string): Promise<BenchmarkResult> {
+  return await invoke("benchmark_drive", { mount });
+}
+
+export interface LockingProcess {
+  pid: number;
+  name: string;
+}
+
+export interface InUseReport {
+  path: string;
+  processes: LockingProcess[];
+}
+
+// Paths that nobody holds open are left out of the result
+export async function checkInUse(paths: string[]): Promise<InUseReport[]> {
+  return await invoke("check_in_use", { paths });
+}
+
+// The user's own protected paths, which deletion refuses like system folders
+export async function getProtectedPaths(): Promise<string[]> {
+  return await invoke("get_protected_paths");
+}
+
+export async function addProtectedPath(path: string): Promise<void> {
+  return await invoke("add_protected_path", { path });
+}
+
+export async function removeProtectedPath(path: string): Promise<void> {
+  return await invoke("remove_protected_path", { path });
+}
+
+export interface StagedItem {
+  id: string;
+  original_path: string;
+  staged_path: string;
+  size: number;
+  staged_at: number;
+}
+
+export async function listStaged(): Promise<StagedItem[]> {
+  return await invoke("list_staged");
+}
+
+// Move a staged item back to where it was deleted from; `path` is its original
+// path or its id
+export async function restoreStaged(path: string, action?: string): Promise<void> {
+  return await invoke("restore_staged", { path, action });
+}
+
+// Write environment info and recent scan logs for a bug report; returns the file's path
+export async function exportDiagnostics(path?: string): Promise<string> {
+  return await invoke("export_diagnostics", { path });
+}
+
+// Continue the last interrupted scan, reusing every directory it finished
+export async function resumeLastScan(): Promise<DiskItem> {
+  return await invoke("resume_last_scan");
+}